@@ -2,9 +2,15 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use basset_vault::basset_vault::{
-    AnyoneMsg, ChildContractsInfoResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, GovernanceMsg,
-    InstantiateMsg, IsRewardsClaimableResponse, MigrateMsg, QueryMsg, RebalanceResponse,
-    YourselfMsg,
+    AnyoneMsg, ChildContractsInfoResponse, CompoundingConfigResponse, ConfigResponse,
+    ConvertToAssetsResponse, ConvertToSharesResponse, Cw20HookMsg,
+    EmergencyDeleverageConfigResponse, EpochsResponse, EstimatedAprResponse, ExecuteMsg,
+    GovernanceMsg, GuardianMsg, InstantiateMsg, IsRewardsClaimableResponse, KeeperConfigResponse,
+    MaxDepositResponse, MaxWithdrawResponse, MigrateMsg, PauseStatusResponse,
+    PositionHealthResponse, PreviewDepositResponse, PreviewWithdrawResponse, QueryMsg,
+    RebalanceResponse, RepaymentPlanResponse, SimulateHonestWorkResponse,
+    SimulateRebalanceResponse, StatsResponse, SwapRouterConfigResponse, TotalAssetsResponse,
+    UnwindStatusResponse, YourselfMsg,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -19,11 +25,32 @@ fn main() {
     export_schema(&schema_for!(YourselfMsg), &out_dir);
     export_schema(&schema_for!(AnyoneMsg), &out_dir);
     export_schema(&schema_for!(GovernanceMsg), &out_dir);
+    export_schema(&schema_for!(GuardianMsg), &out_dir);
     export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(RebalanceResponse), &out_dir);
     export_schema(&schema_for!(ChildContractsInfoResponse), &out_dir);
     export_schema(&schema_for!(IsRewardsClaimableResponse), &out_dir);
+    export_schema(&schema_for!(ConvertToSharesResponse), &out_dir);
+    export_schema(&schema_for!(ConvertToAssetsResponse), &out_dir);
+    export_schema(&schema_for!(PreviewDepositResponse), &out_dir);
+    export_schema(&schema_for!(PreviewWithdrawResponse), &out_dir);
+    export_schema(&schema_for!(MaxDepositResponse), &out_dir);
+    export_schema(&schema_for!(MaxWithdrawResponse), &out_dir);
+    export_schema(&schema_for!(TotalAssetsResponse), &out_dir);
+    export_schema(&schema_for!(PositionHealthResponse), &out_dir);
+    export_schema(&schema_for!(KeeperConfigResponse), &out_dir);
+    export_schema(&schema_for!(PauseStatusResponse), &out_dir);
+    export_schema(&schema_for!(UnwindStatusResponse), &out_dir);
+    export_schema(&schema_for!(RepaymentPlanResponse), &out_dir);
+    export_schema(&schema_for!(EmergencyDeleverageConfigResponse), &out_dir);
+    export_schema(&schema_for!(SwapRouterConfigResponse), &out_dir);
+    export_schema(&schema_for!(CompoundingConfigResponse), &out_dir);
+    export_schema(&schema_for!(SimulateRebalanceResponse), &out_dir);
+    export_schema(&schema_for!(SimulateHonestWorkResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(EpochsResponse), &out_dir);
+    export_schema(&schema_for!(EstimatedAprResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
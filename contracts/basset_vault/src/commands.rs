@@ -1,35 +1,51 @@
 use crate::{
     commands,
+    error::ContractError,
     state::{
-        load_aim_buffer_size, load_config, load_gov_update, load_repaying_loan_state,
-        load_stable_balance_before_selling_anc, remove_gov_update, store_aim_buffer_size,
-        store_config, store_gov_update, store_last_anc_claim_seconds, store_repaying_loan_state,
-        store_stable_balance_before_selling_anc, Config, GovernanceUpdateState, RepayingLoanState,
+        load_aim_buffer_size, load_basset_balance_before_compounding, load_compounding_config,
+        load_config, load_emergency_deleverage_config, load_gov_update, load_guardian,
+        load_keeper_config, load_last_honest_work_reward_seconds,
+        load_last_rebalance_reward_seconds, load_pause_state, load_rebalance_reward_reserve,
+        load_repaying_loan_state, load_stable_balance_before_selling_anc,
+        load_stable_balance_before_selling_basset, load_swap_router_config, load_unwinding,
+        load_withdrawal_queue_state, pop_pending_withdrawal, push_epoch_record,
+        push_pending_withdrawal, remove_gov_update, remove_guardian, store_aim_buffer_size,
+        store_basset_balance_before_compounding, store_compounding_config, store_config,
+        store_emergency_deleverage_config, store_gov_update, store_guardian,
+        store_honest_work_keeper, store_keeper_config, store_last_anc_claim_seconds,
+        store_last_honest_work_reward_seconds, store_last_rebalance_reward_seconds,
+        store_pause_state, store_pending_rebalance_reward, store_rebalance_reward_reserve,
+        store_repaying_loan_state, store_stable_balance_before_selling_anc,
+        store_stable_balance_before_selling_basset, store_swap_router_config, store_unwinding,
+        take_honest_work_keeper, take_pending_rebalance_reward, update_ledger, Config, EpochRecord,
+        GovernanceUpdateState, PauseState, PendingWithdrawal, RepayingLoanState,
     },
-    tax_querier::get_tax_info,
+    tax_querier::{get_tax_info, TaxInfo},
     utils::{
-        calc_after_borrow_action, get_repay_loan_action, is_anc_rewards_claimable,
-        split_profit_to_handle_interest,
+        calc_after_borrow_action, calc_basset_to_sell_on_emergency, floor_to_uint,
+        get_repay_loan_action, is_anc_rewards_claimable, plan_loan_repayment, query_reward_swap,
+        query_swap_limits, split_profit_to_handle_interest, ActionWithProfit, Compounding,
+        DistributedRewards, UnwindProgress, VaultBalances,
     },
-    SubmsgIds,
+    ContractResult, SubmsgIds, MINIMUM_NASSET_LIQUIDITY,
 };
 use basset_vault::{
-    anchor::basset_custody::get_basset_in_custody,
-    anchor::market::{query_borrower_info, BorrowerInfoResponse},
-    astroport_pair::{Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg},
+    astroport_pair::Cw20HookMsg as AstroportCw20HookMsg,
+    astroport_router::SwapOperation,
     basset_vault::{AnyoneMsg, Cw20HookMsg, ExecuteMsg, YourselfMsg},
-    basset_vault_strategy::{query_borrower_action, BorrowerActionResponse},
-    querier::{
-        query_aterra_state, query_balance, query_supply, query_token_balance, AnchorCustodyCw20Msg,
-        AnchorCustodyMsg, AnchorMarketCw20Msg, AnchorMarketMsg, AnchorOverseerMsg,
+    basset_vault_strategy::{
+        query_borrower_action, query_strategy_config, query_strategy_fresh_price,
+        BorrowerActionResponse,
     },
+    querier::{query_balance, query_token_balance, AnchorMarketMsg},
     terraswap::{Asset, AssetInfo},
+    yield_venue::YieldVenueKind,
     BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP,
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, BlockInfo, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
@@ -45,7 +61,11 @@ pub fn update_config(
     basset_vault_strategy_contract_addr: Option<String>,
     claiming_rewards_delay: Option<u64>,
     over_loan_balance_value: Option<Decimal256>,
-) -> StdResult<Response> {
+    anc_stable_max_spread: Option<Decimal256>,
+    psi_stable_max_spread: Option<Decimal256>,
+    anc_stable_twap_oracle_addr: Option<String>,
+    psi_stable_twap_oracle_addr: Option<String>,
+) -> ContractResult<Response> {
     if let Some(ref psi_distributor_addr) = psi_distributor_addr {
         current_config.psi_distributor = deps.api.addr_validate(psi_distributor_addr)?;
     }
@@ -90,6 +110,26 @@ pub fn update_config(
         current_config.over_loan_balance_value = over_loan_balance_value;
     }
 
+    if let Some(anc_stable_max_spread) = anc_stable_max_spread {
+        validate_max_spread(anc_stable_max_spread)?;
+        current_config.anc_stable_max_spread = anc_stable_max_spread;
+    }
+
+    if let Some(psi_stable_max_spread) = psi_stable_max_spread {
+        validate_max_spread(psi_stable_max_spread)?;
+        current_config.psi_stable_max_spread = psi_stable_max_spread;
+    }
+
+    if let Some(ref anc_stable_twap_oracle_addr) = anc_stable_twap_oracle_addr {
+        current_config.anc_stable_twap_oracle =
+            Some(deps.api.addr_validate(anc_stable_twap_oracle_addr)?);
+    }
+
+    if let Some(ref psi_stable_twap_oracle_addr) = psi_stable_twap_oracle_addr {
+        current_config.psi_stable_twap_oracle =
+            Some(deps.api.addr_validate(psi_stable_twap_oracle_addr)?);
+    }
+
     store_config(deps.storage, &current_config)?;
     Ok(Response::default())
 }
@@ -99,7 +139,7 @@ pub fn update_governance_addr(
     env: Env,
     gov_addr: String,
     seconds_to_wait_for_accept_gov_tx: u64,
-) -> StdResult<Response> {
+) -> ContractResult<Response> {
     let current_time = get_time(&env.block);
     let gov_update = GovernanceUpdateState {
         new_governance_contract_addr: deps.api.addr_validate(&gov_addr)?,
@@ -109,18 +149,16 @@ pub fn update_governance_addr(
     Ok(Response::default())
 }
 
-pub fn accept_governance(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+pub fn accept_governance(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<Response> {
     let gov_update = load_gov_update(deps.storage)?;
     let current_time = get_time(&env.block);
 
     if gov_update.wait_approve_until < current_time {
-        return Err(StdError::generic_err(
-            "too late to accept governance owning",
-        ));
+        return Err(StdError::generic_err("too late to accept governance owning").into());
     }
 
     if info.sender != gov_update.new_governance_contract_addr {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(StdError::generic_err("unauthorized").into());
     }
 
     let new_gov_add_str = gov_update.new_governance_contract_addr.to_string();
@@ -141,10 +179,39 @@ pub fn receive_cw20(
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+) -> ContractResult<Response> {
     match from_binary(&cw20_msg.msg)? {
-        Cw20HookMsg::Deposit {} => commands::receive_cw20_deposit(deps, env, info, cw20_msg),
-        Cw20HookMsg::Withdraw {} => commands::receive_cw20_withdraw(deps, env, info, cw20_msg),
+        Cw20HookMsg::Deposit {
+            min_nasset_out,
+            deadline,
+            recipient,
+        } => commands::receive_cw20_deposit(
+            deps,
+            env,
+            info,
+            cw20_msg,
+            min_nasset_out,
+            deadline,
+            recipient,
+        ),
+        Cw20HookMsg::Withdraw {
+            min_basset_out,
+            deadline,
+            recipient,
+            msg,
+        } => commands::receive_cw20_withdraw(
+            deps,
+            env,
+            info,
+            cw20_msg,
+            min_basset_out,
+            deadline,
+            recipient,
+            msg,
+        ),
+        Cw20HookMsg::RequestWithdraw { recipient } => {
+            commands::receive_cw20_request_withdraw(deps, env, info, cw20_msg, recipient)
+        }
     }
 }
 
@@ -153,18 +220,37 @@ pub fn receive_cw20_deposit(
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+    min_nasset_out: Option<Uint256>,
+    deadline: Option<u64>,
+    recipient: Option<String>,
+) -> ContractResult<Response> {
     let basset_addr = info.sender;
     // only bAsset contract can execute this message
     let config: Config = load_config(deps.storage)?;
     if basset_addr != config.basset_token {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(StdError::generic_err("unauthorized").into());
+    }
+
+    assert_not_paused(deps.as_ref(), "deposit", |pause| pause.deposit)?;
+    if load_unwinding(deps.storage)? {
+        return Err(ContractError::Unwinding);
     }
+    assert_deadline(&env.block, deadline)?;
 
-    //we trust cw20 contract
-    let farmer_addr: Addr = Addr::unchecked(cw20_msg.sender);
+    let farmer_addr: Addr = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        //we trust cw20 contract
+        None => Addr::unchecked(cw20_msg.sender),
+    };
 
-    deposit_basset(deps, env, config, farmer_addr)
+    deposit_basset(
+        deps,
+        env,
+        config,
+        farmer_addr,
+        cw20_msg.amount.into(),
+        min_nasset_out,
+    )
 }
 
 pub fn deposit_basset(
@@ -172,111 +258,157 @@ pub fn deposit_basset(
     env: Env,
     config: Config,
     farmer: Addr,
-) -> StdResult<Response> {
-    let nasset_supply: Uint256 = query_supply(&deps.querier, &config.nasset_token.clone())?.into();
+    deposit_amount: Uint256,
+    min_nasset_out: Option<Uint256>,
+) -> ContractResult<Response> {
+    let vault_balances = VaultBalances::query(deps.as_ref(), &env, &config)?;
+    //read comments in 'withdraw_basset' function for a reason to return error here
+    vault_balances.assert_not_frozen()?;
+
+    //only amount sent with 'Deposit' is deposited. bAssets transferred directly
+    //to this contract stay here as unsolicited balance and do not change share price
+    let nasset_to_mint = vault_balances.nasset_to_mint(deposit_amount);
+    if nasset_to_mint.is_zero() {
+        return Err(ContractError::ZeroNAssetToMint);
+    }
 
-    let basset_in_custody = get_basset_in_custody(
-        deps.as_ref(),
-        &config.anchor_custody_basset_contract,
-        &env.contract.address,
-    )?;
+    if let Some(min_nasset_out) = min_nasset_out {
+        if nasset_to_mint < min_nasset_out {
+            return Err(ContractError::TooLittleNAssetOut {
+                min_nasset_out,
+                nasset_to_mint,
+            });
+        }
+    }
 
-    if basset_in_custody.is_zero() && !nasset_supply.is_zero() {
-        //read comments in 'withdraw_basset' function for a reason to return error here
-        return Err(StdError::generic_err(
-            "bAsset balance is zero, but nAsset supply is not! Freeze contract.",
-        ));
+    //0. send basset to lending market and lock it
+    //1. mint nasset
+    //2. mint locked nasset to vault itself (first deposit only)
+    //3. rebalance
+    let mut messages = config
+        .market_adapter()
+        .lock_collateral(&config.basset_token, deposit_amount)?;
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.nasset_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint {
+            recipient: farmer.to_string(),
+            amount: nasset_to_mint.into(),
+        })?,
+        funds: vec![],
+    }));
+
+    if vault_balances.is_first_deposit() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.nasset_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: env.contract.address.to_string(),
+                amount: MINIMUM_NASSET_LIQUIDITY.into(),
+            })?,
+            funds: vec![],
+        }));
     }
 
-    // basset balance in cw20 contract
-    // it should be equal to 'deposit_amout',
-    // unless someone directly transfer cw20 tokens to this contract without calling 'Deposit'
-    let basset_in_contract_address =
-        query_token_balance(deps.as_ref(), &config.basset_token, &env.contract.address);
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::Anyone {
+            anyone_msg: AnyoneMsg::Rebalance {},
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "deposit_basset"),
+        ("farmer", &farmer.to_string()),
+        ("amount", &deposit_amount.to_string()),
+    ]))
+}
 
-    let basset_balance: Uint256 = basset_in_custody + basset_in_contract_address.into();
-    if basset_balance == Uint256::zero() {
-        //impossible because 'farmer' already sent some basset
-        return Err(StdError::generic_err(
-            "basset balance is zero (impossible case)".to_string(),
-        ));
+pub fn receive_cw20_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+    min_basset_out: Option<Uint256>,
+    deadline: Option<u64>,
+    recipient: Option<String>,
+    msg: Option<Binary>,
+) -> ContractResult<Response> {
+    let contract_addr = info.sender;
+    // only nAsset contract can execute this message
+    let config: Config = load_config(deps.storage)?;
+    if contract_addr != config.nasset_token {
+        return Err(StdError::generic_err("unauthorized").into());
     }
 
-    let deposit_amount: Uint256 = basset_in_contract_address.into();
-    let is_first_depositor = deposit_amount == basset_balance;
+    assert_not_paused(deps.as_ref(), "withdraw", |pause| pause.withdraw)?;
+    assert_deadline(&env.block, deadline)?;
 
-    // nAsset tokens to mint:
-    // user_share = (deposited_basset / total_basset)
-    // nAsset_to_mint = nAsset_supply * user_share / (1 - user_share)
-    let nasset_to_mint = if is_first_depositor {
-        deposit_amount
-    } else {
-        // 'nasset_supply' can't be zero here, cause we already mint some for first farmer
-        nasset_supply * deposit_amount / Decimal256::from_uint256(basset_balance - deposit_amount)
+    let farmer_addr: Addr = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        //we trust cw20 contract
+        None => Addr::unchecked(cw20_msg.sender),
     };
 
-    //0. send basset to anchor_custody contract
-    //1. lock basset
-    //2. mint nasset
-    //3. rebalance
-    Ok(Response::new()
-        .add_messages(vec![
-            WasmMsg::Execute {
-                contract_addr: config.basset_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
-                    contract: config.anchor_custody_basset_contract.to_string(),
-                    amount: deposit_amount.into(),
-                    msg: to_binary(&AnchorCustodyCw20Msg::DepositCollateral {})?,
-                })?,
-                funds: vec![],
-            },
-            WasmMsg::Execute {
-                contract_addr: config.anchor_overseer_contract.to_string(),
-                msg: to_binary(&AnchorOverseerMsg::LockCollateral {
-                    collaterals: vec![(config.basset_token.to_string(), deposit_amount)],
-                })?,
-                funds: vec![],
-            },
-            WasmMsg::Execute {
-                contract_addr: config.nasset_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Mint {
-                    recipient: farmer.to_string(),
-                    amount: nasset_to_mint.into(),
-                })?,
-                funds: vec![],
-            },
-            WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                msg: to_binary(&ExecuteMsg::Anyone {
-                    anyone_msg: AnyoneMsg::Rebalance {},
-                })?,
-                funds: vec![],
-            },
-        ])
-        .add_attributes(vec![
-            ("action", "deposit_basset"),
-            ("farmer", &farmer.to_string()),
-            ("amount", &deposit_amount.to_string()),
-        ]))
+    withdraw_basset(
+        deps,
+        env,
+        config,
+        farmer_addr,
+        cw20_msg.amount.into(),
+        min_basset_out,
+        msg,
+    )
 }
 
-pub fn receive_cw20_withdraw(
+pub fn receive_cw20_request_withdraw(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+    recipient: Option<String>,
+) -> ContractResult<Response> {
     let contract_addr = info.sender;
     // only nAsset contract can execute this message
     let config: Config = load_config(deps.storage)?;
     if contract_addr != config.nasset_token {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(StdError::generic_err("unauthorized").into());
     }
 
-    //we trust cw20 contract
-    let farmer_addr: Addr = Addr::unchecked(cw20_msg.sender);
+    assert_not_paused(deps.as_ref(), "withdraw", |pause| pause.withdraw)?;
+
+    let recipient: Addr = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        //we trust cw20 contract
+        None => Addr::unchecked(cw20_msg.sender),
+    };
+
+    //request that is worth nothing would fail on every processing and block the queue
+    let vault_balances = VaultBalances::query(deps.as_ref(), &env, &config)?;
+    vault_balances.assert_not_frozen()?;
+    if vault_balances
+        .basset_to_withdraw(cw20_msg.amount.into())
+        .is_zero()
+    {
+        return Err(ContractError::ZeroBAssetToWithdraw);
+    }
 
-    withdraw_basset(deps, env, config, farmer_addr, cw20_msg.amount.into())
+    //nAsset stays locked on vault balance until request is processed,
+    //so it is still a part of nAsset supply
+    let pending_withdrawal = PendingWithdrawal {
+        recipient,
+        nasset_amount: cw20_msg.amount.into(),
+    };
+    let withdrawal_id = push_pending_withdrawal(deps.storage, &pending_withdrawal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "request_withdraw"),
+        ("withdrawal_id", &withdrawal_id.to_string()),
+        ("recipient", pending_withdrawal.recipient.as_str()),
+        (
+            "nasset_amount",
+            &pending_withdrawal.nasset_amount.to_string(),
+        ),
+    ]))
 }
 
 pub fn withdraw_basset(
@@ -285,77 +417,85 @@ pub fn withdraw_basset(
     config: Config,
     farmer: Addr,
     nasset_to_withdraw_amount: Uint256,
-) -> StdResult<Response> {
+    min_basset_out: Option<Uint256>,
+    hook_msg: Option<Binary>,
+) -> ContractResult<Response> {
     //nasset_to_withdraw_amount is not zero here, cw20 contract check it
 
     //basset_in_contract_address is always zero (except Deposit stage)
-    let basset_in_custody = get_basset_in_custody(
-        deps.as_ref(),
-        &config.anchor_custody_basset_contract,
-        &env.contract.address,
-    )?;
-
-    let nasset_token_supply = query_supply(&deps.querier, &config.nasset_token)?;
-
-    if basset_in_custody.is_zero() {
-        //interesting case - user owns some nAsset, but bAsset balance is zero
-        //what we can do here:
-        //1. Burn his nAsset, cause they do not have value in that context
-        //2. return error. In that case if someone will deposit bAsset those nAsset owners will
-        //   own share of his tokens. But I prevent deposists in that case, so contract is kinds "frozen" -
-        //   no withdraw and deposits available when bLuna balance is zero. Looks like the best
-        //   solution.
-        //3. Burn all nAsset supply (not possible with cw20 messages)
-        //
-        //Second choice is best one in my opinion.
-        return Err(StdError::generic_err(
-            "bAsset balance is zero, but nAsset supply is not! Freeze contract.",
-        ));
+    let vault_balances = VaultBalances::query(deps.as_ref(), &env, &config)?;
+    let basset_in_custody = vault_balances.basset_in_custody;
+
+    //interesting case - user owns some nAsset, but bAsset balance is zero
+    //what we can do here:
+    //1. Burn his nAsset, cause they do not have value in that context
+    //2. return error. In that case if someone will deposit bAsset those nAsset owners will
+    //   own share of his tokens. But I prevent deposists in that case, so contract is kinds "frozen" -
+    //   no withdraw and deposits available when bLuna balance is zero. Looks like the best
+    //   solution.
+    //3. Burn all nAsset supply (not possible with cw20 messages)
+    //
+    //Second choice is best one in my opinion.
+    vault_balances.assert_not_frozen()?;
+
+    let basset_to_withdraw: Uint256 = vault_balances.basset_to_withdraw(nasset_to_withdraw_amount);
+
+    if let Some(min_basset_out) = min_basset_out {
+        if basset_to_withdraw < min_basset_out {
+            return Err(ContractError::TooLittleBAssetOut {
+                min_basset_out,
+                basset_to_withdraw,
+            });
+        }
     }
 
-    let basset_to_withdraw: Uint256 = basset_in_custody * nasset_to_withdraw_amount
-        / Decimal256::from_uint256(Uint256::from(nasset_token_supply));
-
     //1. rebalance in a way you don't have basset_to_withdraw
-    //2. unlock basset from anchor_overseer
-    //3. withdraw basset from anchor_custody
-    //4. send basset to farmer
-    //5. burn nasset
-    let mut rebalance_response = rebalance(
-        deps,
-        env,
-        &config,
-        basset_in_custody,
-        Some(basset_to_withdraw),
-    )?;
-
-    rebalance_response
-        .messages
-        .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.anchor_overseer_contract.to_string(),
-            msg: to_binary(&AnchorOverseerMsg::UnlockCollateral {
-                collaterals: vec![(config.basset_token.to_string(), basset_to_withdraw)],
-            })?,
-            funds: vec![],
-        })));
+    //   (or send share of remaining stables if vault is unwound)
+    //2. unlock basset and withdraw it from lending market
+    //3. send basset to farmer (with hook msg if any)
+    //4. burn nasset
+    let mut rebalance_response = if load_unwinding(deps.storage)? {
+        unwound_stables_response(
+            deps.as_ref(),
+            &env,
+            &config,
+            &farmer,
+            nasset_to_withdraw_amount,
+            vault_balances.nasset_supply,
+        )?
+    } else {
+        rebalance(
+            deps,
+            env,
+            &config,
+            basset_in_custody,
+            Some(basset_to_withdraw),
+            None,
+        )?
+    };
 
-    rebalance_response
-        .messages
-        .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.anchor_custody_basset_contract.to_string(),
-            msg: to_binary(&AnchorCustodyMsg::WithdrawCollateral {
-                amount: Some(basset_to_withdraw),
-            })?,
-            funds: vec![],
-        })));
+    rebalance_response.messages.extend(
+        config
+            .market_adapter()
+            .unlock_collateral(&config.basset_token, basset_to_withdraw)?
+            .into_iter()
+            .map(SubMsg::new),
+    );
 
     rebalance_response
         .messages
         .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: config.basset_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: farmer.to_string(),
-                amount: basset_to_withdraw.into(),
+            msg: to_binary(&match hook_msg {
+                Some(msg) => Cw20ExecuteMsg::Send {
+                    contract: farmer.to_string(),
+                    amount: basset_to_withdraw.into(),
+                    msg,
+                },
+                None => Cw20ExecuteMsg::Transfer {
+                    recipient: farmer.to_string(),
+                    amount: basset_to_withdraw.into(),
+                },
             })?,
             funds: vec![],
         })));
@@ -378,20 +518,23 @@ pub fn withdraw_basset(
 
 /// Executor: anyone
 pub fn rebalance(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     config: &Config,
     basset_in_custody: Uint256,
     basset_to_withdraw: Option<Uint256>,
-) -> StdResult<Response> {
+    keeper: Option<&Addr>,
+) -> ContractResult<Response> {
+    if load_unwinding(deps.storage)? {
+        return unwind_logic(deps, env, config);
+    }
+
     let basset_in_custody = basset_in_custody - basset_to_withdraw.unwrap_or_default();
 
-    let borrower_info: BorrowerInfoResponse = query_borrower_info(
-        deps.as_ref(),
-        &config.anchor_market_contract,
-        &env.contract.address,
-    )?;
-    let borrowed_ust = borrower_info.loan_amount;
+    let borrowed_ust = config
+        .market_adapter()
+        .loan_info(deps.as_ref(), &env.contract.address)?
+        .loan_amount;
 
     let borrower_action = query_borrower_action(
         deps.as_ref(),
@@ -400,25 +543,51 @@ pub fn rebalance(
         basset_in_custody,
     )?;
 
-    match borrower_action {
-        BorrowerActionResponse::Nothing {} => {
+    if let BorrowerActionResponse::Borrow { .. } = borrower_action {
+        if load_pause_state(deps.storage)?.borrow {
+            //do not fail, cause it is used in 'deposit'
+            return Ok(Response::new()
+                .add_attributes(vec![("action", "rebalance"), ("skipped", "borrow_paused")]));
+        }
+    }
+
+    let keeper_reward = match (&borrower_action, keeper) {
+        (BorrowerActionResponse::Nothing { .. }, _) | (_, None) => None,
+        (_, Some(keeper)) => rebalance_keeper_reward(deps.branch(), &env, config, keeper)?,
+    };
+
+    let price_is_stale = borrower_action.price_is_stale();
+    let response = match borrower_action {
+        BorrowerActionResponse::Nothing { .. } => {
             //maybe it is better to return error here, but
             //we cant, cause it is used in 'withdraw'
-            return Ok(Response::new().add_attribute("action", "rebalance_not_needed"));
+            let response = Response::new().add_attribute("action", "rebalance_not_needed");
+            return Ok(with_stale_price_attribute(response, price_is_stale));
         }
 
         BorrowerActionResponse::Borrow {
             amount,
             advised_buffer_size,
+            ..
         } => {
+            update_ledger(deps.storage, |ledger| {
+                ledger.observe_loan(borrowed_ust);
+                ledger.loan_borrowed(amount);
+                ledger.rebalances += 1;
+            })?;
             store_aim_buffer_size(deps.storage, &advised_buffer_size)?;
-            borrow_logic(config, amount, advised_buffer_size)
+            Ok(borrow_logic(config, amount, advised_buffer_size)?)
         }
 
         BorrowerActionResponse::Repay {
             amount,
             advised_buffer_size,
+            ..
         } => {
+            update_ledger(deps.storage, |ledger| {
+                ledger.observe_loan(borrowed_ust);
+                ledger.rebalances += 1;
+            })?;
             let repaying_loan_state = RepayingLoanState {
                 to_repay_amount: amount,
                 aim_buffer_size: advised_buffer_size,
@@ -426,27 +595,136 @@ pub fn rebalance(
             };
             repay_logic(deps, env, config, repaying_loan_state)
         }
+    }?;
+    let response = with_stale_price_attribute(response, price_is_stale);
+
+    match keeper_reward {
+        //error on paying reward should not revert rebalancing
+        Some((send_msg, reward)) => Ok(response
+            .add_submessage(SubMsg::reply_on_error(
+                send_msg,
+                SubmsgIds::KeeperReward.id(),
+            ))
+            .add_attribute("keeper_reward", reward.to_string())),
+        None => Ok(response),
     }
 }
 
-fn borrow_logic(
+/// Lets monitors tell decisions made by strategy stale price policy from normal ones
+fn with_stale_price_attribute(response: Response, price_is_stale: bool) -> Response {
+    if price_is_stale {
+        response.add_attribute("price_is_stale", "true")
+    } else {
+        response
+    }
+}
+
+/// Reward for Rebalance caller, paid from ANC selling profit set aside in reserve
+fn rebalance_keeper_reward(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    keeper: &Addr,
+) -> ContractResult<Option<(CosmosMsg, Uint256)>> {
+    //reserve is handed to nAsset holders on unwinding
+    if load_unwinding(deps.storage)? {
+        return Ok(None);
+    }
+
+    let keeper_config = load_keeper_config(deps.storage)?;
+    let current_time = env.block.time.seconds();
+    let last_reward_time = load_last_rebalance_reward_seconds(deps.storage)?;
+    if !keeper_config.is_interval_passed(last_reward_time, current_time) {
+        return Ok(None);
+    }
+
+    let reserve = load_rebalance_reward_reserve(deps.storage)?;
+    let reward = keeper_config.rebalance_reward_from(reserve);
+    if reward.is_zero() {
+        return Ok(None);
+    }
+
+    store_rebalance_reward_reserve(deps.storage, &(reserve - reward))?;
+    store_pending_rebalance_reward(deps.storage, &reward)?;
+    store_last_rebalance_reward_seconds(deps.storage, &current_time)?;
+    let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
+    Ok(Some((
+        keeper_reward_msg(config, keeper, tax_info.subtract_tax(reward)),
+        reward,
+    )))
+}
+
+/// Puts Rebalance reward back to reserve when sending it to keeper failed
+pub(crate) fn restore_rebalance_reward_reserve(deps: DepsMut) -> ContractResult<Response> {
+    let reward = take_pending_rebalance_reward(deps.storage)?;
+    let reserve = load_rebalance_reward_reserve(deps.storage)?;
+    store_rebalance_reward_reserve(deps.storage, &(reserve + reward))?;
+    Ok(Response::new().add_attributes(vec![
+        ("action", "keeper_reward"),
+        ("skipped", "send_failed"),
+    ]))
+}
+
+/// Reward for HonestWork caller, paid from ANC selling profit
+fn honest_work_keeper_reward(
+    deps: DepsMut,
+    env: &Env,
+    selling_anc_profit: Uint256,
+) -> ContractResult<Option<(Addr, Uint256)>> {
+    let keeper = match take_honest_work_keeper(deps.storage)? {
+        Some(keeper) => keeper,
+        None => return Ok(None),
+    };
+
+    let keeper_config = load_keeper_config(deps.storage)?;
+    let current_time = env.block.time.seconds();
+    let last_reward_time = load_last_honest_work_reward_seconds(deps.storage)?;
+    if !keeper_config.is_interval_passed(last_reward_time, current_time) {
+        return Ok(None);
+    }
+
+    let reward = keeper_config.capped(selling_anc_profit * keeper_config.honest_work_reward_rate);
+    if reward.is_zero() {
+        return Ok(None);
+    }
+
+    store_last_honest_work_reward_seconds(deps.storage, &current_time)?;
+    Ok(Some((keeper, reward)))
+}
+
+/// Sets aside part of ANC selling profit for Rebalance rewards
+fn top_up_rebalance_reward_reserve(deps: DepsMut, profit: Uint256) -> StdResult<Uint256> {
+    let keeper_config = load_keeper_config(deps.storage)?;
+    let reserve = load_rebalance_reward_reserve(deps.storage)?;
+    let top_up = keeper_config.reserve_top_up(reserve, profit);
+    if !top_up.is_zero() {
+        store_rebalance_reward_reserve(deps.storage, &(reserve + top_up))?;
+    }
+
+    Ok(top_up)
+}
+
+fn keeper_reward_msg(config: &Config, keeper: &Addr, amount: Uint256) -> CosmosMsg {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: keeper.to_string(),
+        amount: vec![Coin {
+            denom: config.stable_denom.clone(),
+            amount: amount.into(),
+        }],
+    })
+}
+
+pub(crate) fn borrow_logic(
     config: &Config,
     borrow_amount: Uint256,
     aim_buffer_size: Uint256,
 ) -> StdResult<Response> {
-    // If can't borrow from Anchor we can't do anything, so just return error, consequence:
+    // If can't borrow from lending market we can't do anything, so just return error, consequence:
     // 1. user will not be able to deposit
     // 2. Rebalance return error
     Ok(Response::new()
         .add_submessage(SubMsg::reply_on_success(
-            WasmMsg::Execute {
-                contract_addr: config.anchor_market_contract.to_string(),
-                msg: to_binary(&AnchorMarketMsg::BorrowStable {
-                    borrow_amount,
-                    to: None,
-                })?,
-                funds: vec![],
-            },
+            config.market_adapter().borrow(borrow_amount)?,
             SubmsgIds::Borrowing.id(),
         ))
         .add_attributes(vec![
@@ -456,7 +734,12 @@ fn borrow_logic(
         ]))
 }
 
-pub(crate) fn borrow_logic_on_reply(deps: DepsMut, env: Env) -> StdResult<Response> {
+pub(crate) fn borrow_logic_on_reply(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    deposit_to_yield_venue(deps, env)
+}
+
+/// Deposit stables above aim buffer to yield venue
+pub fn deposit_to_yield_venue(deps: DepsMut, env: Env) -> ContractResult<Response> {
     let config = load_config(deps.storage)?;
     let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
     let aim_buf_size = load_aim_buffer_size(deps.as_ref().storage)?;
@@ -467,7 +750,7 @@ pub(crate) fn borrow_logic_on_reply(deps: DepsMut, env: Env) -> StdResult<Respon
     )?;
     let after_borrow_action =
         calc_after_borrow_action(stable_coin_balance.into(), aim_buf_size, &tax_info);
-    after_borrow_action.to_response(&config)
+    Ok(after_borrow_action.to_response(&config)?)
 }
 
 pub(crate) fn repay_logic(
@@ -475,22 +758,55 @@ pub(crate) fn repay_logic(
     env: Env,
     config: &Config,
     mut repaying_loan_state: RepayingLoanState,
+) -> ContractResult<Response> {
+    let response = repay_response(deps.as_ref(), &env, config, &mut repaying_loan_state)?;
+    store_repaying_loan_state(deps.storage, &repaying_loan_state)?;
+    Ok(response)
+}
+
+/// Loan repayment messages for current balances, sets `repaying_amount` in given state.
+/// Do not change contract state, so it is used by simulation too
+pub(crate) fn repay_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    repaying_loan_state: &mut RepayingLoanState,
 ) -> StdResult<Response> {
-    let aterra_balance =
-        query_token_balance(deps.as_ref(), &config.aterra_token, &env.contract.address);
-    let aterra_exchange_rate: Decimal256 =
-        query_aterra_state(deps.as_ref(), &config.anchor_market_contract)?.exchange_rate;
+    let yield_venue = config.venue_adapter();
+    let aterra_balance = yield_venue.share_balance(deps, &env.contract.address)?;
+    let aterra_exchange_rate = yield_venue.exchange_rate(deps)?;
     let stable_coin_balance = query_balance(
         &deps.querier,
         &env.contract.address,
         config.stable_denom.clone(),
     )?;
 
-    let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
+    let redeemable_stables = yield_venue.available_liquidity(deps, &config.stable_denom)?;
+
+    let tax_info = get_tax_info(deps, &config.stable_denom)?;
+    if repaying_loan_state.iteration_index == 0 {
+        let repay_plan = plan_loan_repayment(
+            stable_coin_balance.into(),
+            aterra_balance,
+            aterra_exchange_rate,
+            redeemable_stables,
+            repaying_loan_state.to_repay_amount,
+            repaying_loan_state.aim_buffer_size,
+            &tax_info,
+        );
+
+        if repay_plan.is_complete {
+            repaying_loan_state.repaying_amount = repay_plan.repay_amount;
+            return repay_plan.to_response(config);
+        }
+    }
+
+    //Anchor liquidity is constrained, repay loan step by step in 'reply' handler
     let repay_action = get_repay_loan_action(
         stable_coin_balance.into(),
-        aterra_balance.into(),
+        aterra_balance,
         aterra_exchange_rate,
+        redeemable_stables,
         repaying_loan_state.to_repay_amount,
         repaying_loan_state.aim_buffer_size,
         &tax_info,
@@ -498,12 +814,32 @@ pub(crate) fn repay_logic(
     );
 
     repaying_loan_state.repaying_amount = repay_action.repaying_loan_amount();
-    store_repaying_loan_state(deps.storage, &repaying_loan_state)?;
+    repaying_loan_state.redeeming_stables = repay_action.aterra_to_sell() * aterra_exchange_rate;
+    repay_action.to_response(config)
+}
+
+/// Redeeming aterra can fail only if liquidity was taken from the market after
+/// 'get_repay_loan_action' checked it. In that case loan is repaid a bit first,
+/// any other error is returned as is
+pub(crate) fn repay_logic_on_redeem_error(
+    deps: DepsMut,
+    env: Env,
+    err_msg: String,
+) -> ContractResult<Response> {
+    let config = load_config(deps.storage)?;
+    let repaying_loan_state = load_repaying_loan_state(deps.storage)?;
+    let redeemable_stables = config
+        .venue_adapter()
+        .available_liquidity(deps.as_ref(), &config.stable_denom)?;
 
-    repay_action.to_response(&config)
+    if redeemable_stables < repaying_loan_state.redeeming_stables {
+        repay_logic_on_reply(deps, env)
+    } else {
+        Err(StdError::generic_err(err_msg).into())
+    }
 }
 
-pub(crate) fn repay_logic_on_reply(deps: DepsMut, env: Env) -> StdResult<Response> {
+pub(crate) fn repay_logic_on_reply(deps: DepsMut, env: Env) -> ContractResult<Response> {
     let mut repaying_loan_state = load_repaying_loan_state(deps.storage)?;
     repaying_loan_state.iteration_index += 1;
     if repaying_loan_state.iteration_index >= BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP {
@@ -521,16 +857,19 @@ pub(crate) fn repay_logic_on_reply(deps: DepsMut, env: Env) -> StdResult<Respons
 /// ANC rewards, swap ANC => UST token, swap
 /// part of UST => PSI token and distribute
 /// result PSI token to gov contract
-pub fn claim_anc_rewards(deps: DepsMut, env: Env) -> StdResult<Response> {
+pub fn claim_anc_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<Response> {
+    assert_not_paused(deps.as_ref(), "honest_work", |pause| pause.honest_work)?;
     let config: Config = load_config(deps.storage)?;
 
-    let borrower_info = query_borrower_info(
-        deps.as_ref(),
-        &config.anchor_market_contract,
-        &env.contract.address,
-    )?;
+    let borrower_info = config
+        .market_adapter()
+        .loan_info(deps.as_ref(), &env.contract.address)?;
 
     if is_anc_rewards_claimable(deps.as_ref(), &env, borrower_info.pending_rewards)? {
+        store_honest_work_keeper(deps.storage, &info.sender)?;
+        update_ledger(deps.storage, |ledger| {
+            ledger.anc_claimed += floor_to_uint(borrower_info.pending_rewards);
+        })?;
         Ok(Response::new()
             .add_messages(vec![
                 WasmMsg::Execute {
@@ -552,7 +891,7 @@ pub fn claim_anc_rewards(deps: DepsMut, env: Env) -> StdResult<Response> {
     }
 }
 
-pub fn swap_anc(deps: DepsMut, env: Env) -> StdResult<Response> {
+pub fn swap_anc(deps: DepsMut, env: Env) -> ContractResult<Response> {
     let config: Config = load_config(deps.storage)?;
 
     let anc_amount =
@@ -562,36 +901,54 @@ pub fn swap_anc(deps: DepsMut, env: Env) -> StdResult<Response> {
         return Err(StdError::generic_err("ANC amount is zero").into());
     }
 
+    store_last_anc_claim_seconds(deps.storage, &env.block.time.seconds())?;
+
+    let router_config = load_swap_router_config(deps.storage)?;
+    let anc_swap = query_reward_swap(
+        deps.as_ref(),
+        &config.anc_stable_swap_contract,
+        router_config.anc_router_route(),
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: config.anchor_token.clone(),
+            },
+            amount: anc_amount,
+        },
+        config.anc_stable_max_spread,
+        config.anc_stable_twap_oracle.as_ref(),
+    )?;
+    let anc_swap = match anc_swap {
+        Some(anc_swap) => anc_swap,
+        //keep ANC, it will be swapped on next HonestWork
+        None => {
+            return Ok(Response::new().add_attributes(vec![
+                ("action", "swap_anc"),
+                ("skipped", "price_impact_too_high"),
+            ]))
+        }
+    };
+
     let stable_coin_balance = query_balance(
         &deps.querier,
         &env.contract.address,
         config.stable_denom.clone(),
     )?;
     store_stable_balance_before_selling_anc(deps.storage, &stable_coin_balance)?;
-    store_last_anc_claim_seconds(deps.storage, &env.block.time.seconds())?;
+    update_ledger(deps.storage, |ledger| {
+        ledger.anc_sold += Uint256::from(anc_amount);
+        ledger.epoch_anc_sold += Uint256::from(anc_amount);
+    })?;
 
     Ok(Response::new()
         .add_messages(vec![
-            WasmMsg::Execute {
-                contract_addr: config.anchor_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
-                    amount: anc_amount,
-                    contract: config.anc_stable_swap_contract.to_string(),
-                    msg: to_binary(&AstroportCw20HookMsg::Swap {
-                        belief_price: None,
-                        max_spread: None,
-                        to: None,
-                    })?,
-                })?,
-                funds: vec![],
-            },
-            WasmMsg::Execute {
+            anc_swap.token_swap_msg(&config.anchor_token, anc_amount, None)?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 msg: to_binary(&ExecuteMsg::Yourself {
                     yourself_msg: YourselfMsg::DisributeRewards {},
                 })?,
                 funds: vec![],
-            },
+            }),
         ])
         .add_attributes(vec![
             ("action", "swap_anc"),
@@ -599,7 +956,15 @@ pub fn swap_anc(deps: DepsMut, env: Env) -> StdResult<Response> {
         ]))
 }
 
-pub fn distribute_rewards(deps: DepsMut, env: Env) -> StdResult<Response> {
+pub fn distribute_rewards(mut deps: DepsMut, env: Env) -> ContractResult<Response> {
+    if load_unwinding(deps.storage)? {
+        //selling ANC profit stays in vault and goes to nAsset holders on withdraw
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "distribute_rewards"),
+            ("skipped", "unwinding"),
+        ]));
+    }
+
     let config: Config = load_config(deps.storage)?;
     let stable_coin_balance_before_sell_anc =
         load_stable_balance_before_selling_anc(deps.as_ref().storage)?;
@@ -609,44 +974,278 @@ pub fn distribute_rewards(deps: DepsMut, env: Env) -> StdResult<Response> {
         &env.contract.address,
         config.stable_denom.clone(),
     )?;
-    let aterra_balance =
-        query_token_balance(deps.as_ref(), &config.aterra_token, &env.contract.address);
-
-    let aterra_state = query_aterra_state(deps.as_ref(), &config.anchor_market_contract)?;
-    let borrower_info: BorrowerInfoResponse = query_borrower_info(
+    let selling_anc_profit: Uint256 = stable_coin_balance
+        .checked_sub(stable_coin_balance_before_sell_anc)
+        .unwrap_or_default()
+        .into();
+    let keeper_reward = honest_work_keeper_reward(deps.branch(), &env, selling_anc_profit)?;
+    let honest_work_reward = keeper_reward
+        .as_ref()
+        .map(|(_, reward)| *reward)
+        .unwrap_or_default();
+    let reserve_top_up =
+        top_up_rebalance_reward_reserve(deps.branch(), selling_anc_profit - honest_work_reward)?;
+    //keeper rewards are not a part of profit
+    let stable_coin_balance =
+        Uint256::from(stable_coin_balance) - honest_work_reward - reserve_top_up;
+    let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
+    let (response, distributed_rewards) = distribute_rewards_response(
         deps.as_ref(),
-        &config.anchor_market_contract,
-        &env.contract.address,
+        &env,
+        &config,
+        &tax_info,
+        stable_coin_balance,
+        stable_coin_balance_before_sell_anc.into(),
+    )?;
+    if !distributed_rewards.stables_to_compound.is_zero() {
+        //only bought bAsset is locked, see 'lock_compounded_basset'
+        let basset_balance =
+            query_token_balance(deps.as_ref(), &config.basset_token, &env.contract.address);
+        store_basset_balance_before_compounding(deps.storage, &basset_balance)?;
+    }
+    record_epoch(
+        deps.branch(),
+        &env,
+        &config,
+        selling_anc_profit,
+        honest_work_reward,
+        &distributed_rewards,
     )?;
-    let borrowed_amount = borrower_info.loan_amount;
+
+    match keeper_reward {
+        Some((keeper, reward)) => Ok(response
+            .add_message(keeper_reward_msg(
+                &config,
+                &keeper,
+                tax_info.subtract_tax(reward),
+            ))
+            .add_attribute("keeper_reward", reward.to_string())),
+        None => Ok(response),
+    }
+}
+
+/// Adds HonestWork results to ledger and saves them as new epoch record
+fn record_epoch(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    stables_realised: Uint256,
+    keeper_reward: Uint256,
+    distributed_rewards: &DistributedRewards,
+) -> StdResult<()> {
+    let loan_amount = config
+        .market_adapter()
+        .loan_info(deps.as_ref(), &env.contract.address)?
+        .loan_amount;
+
+    let ledger = update_ledger(deps.storage, |ledger| {
+        ledger.observe_loan(loan_amount);
+        ledger.stables_realised += stables_realised;
+        ledger.keeper_rewards += keeper_reward;
+        ledger.deposited_to_anc += distributed_rewards.deposited_to_anc;
+        ledger.stables_to_psi += distributed_rewards.stables_to_psi;
+        ledger.psi_bought += distributed_rewards.psi_bought;
+        ledger.stables_to_compound += distributed_rewards.stables_to_compound;
+    })?;
+
+    push_epoch_record(
+        deps.storage,
+        &EpochRecord {
+            time: env.block.time.seconds(),
+            height: env.block.height,
+            anc_sold: ledger.epoch_anc_sold,
+            stables_realised,
+            keeper_reward,
+            deposited_to_anc: distributed_rewards.deposited_to_anc,
+            stables_to_psi: distributed_rewards.stables_to_psi,
+            psi_bought: distributed_rewards.psi_bought,
+            interest_paid: ledger.epoch_interest_paid,
+            loan_amount,
+        },
+    )?;
+    Ok(())
+}
+
+/// Messages to split ANC selling profit between buying Psi and depositing to Anchor.
+/// Do not change contract state, so it is used by simulation too
+pub(crate) fn distribute_rewards_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    tax_info: &TaxInfo,
+    stable_coin_balance: Uint256,
+    stable_coin_balance_before_sell_anc: Uint256,
+) -> StdResult<(Response, DistributedRewards)> {
+    let yield_position = config
+        .venue_adapter()
+        .position(deps, &env.contract.address)?;
+    let borrowed_amount = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?
+        .loan_amount;
 
     let action_with_profit = split_profit_to_handle_interest(
         borrowed_amount,
-        aterra_balance.into(),
-        aterra_state.exchange_rate,
-        stable_coin_balance.into(),
-        stable_coin_balance_before_sell_anc.into(),
+        yield_position.share_amount,
+        yield_position.exchange_rate,
+        stable_coin_balance,
+        stable_coin_balance_before_sell_anc,
         config.over_loan_balance_value,
     );
+    let (action_with_profit, compounding) =
+        plan_compounding(deps, config, tax_info, action_with_profit)?;
+
+    let router_config = load_swap_router_config(deps.storage)?;
+    let psi_swap = match action_with_profit.psi_offer_amount(tax_info) {
+        Some(psi_offer_amount) => query_reward_swap(
+            deps,
+            &config.psi_stable_swap_contract,
+            router_config.psi_router_route(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: config.stable_denom.clone(),
+                },
+                amount: psi_offer_amount,
+            },
+            config.psi_stable_max_spread,
+            config.psi_stable_twap_oracle.as_ref(),
+        )?,
+        None => None,
+    };
 
-    let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
+    let action_with_profit = match psi_swap {
+        Some(_) => action_with_profit,
+        None => action_with_profit.deposit_instead_of_buying_psi(),
+    };
+    let mut distributed_rewards =
+        action_with_profit.distributed_rewards(tax_info, psi_swap.as_ref());
+    let compounding = match compounding {
+        Some(compounding) => compounding,
+        None => {
+            return Ok((
+                action_with_profit.to_response(config, tax_info, psi_swap.as_ref())?,
+                distributed_rewards,
+            ))
+        }
+    };
 
-    action_with_profit.to_response(&config, &tax_info)
+    let response = match action_with_profit {
+        //whole profit is compounded
+        ActionWithProfit::Nothing => Response::new().add_attribute("action", "distribute_rewards"),
+        _ => action_with_profit.to_response(config, tax_info, psi_swap.as_ref())?,
+    };
+    distributed_rewards.stables_to_compound = compounding.offer_amount.into();
+    Ok((
+        response
+            .add_messages(compounding.to_messages(config, env)?)
+            .add_attribute("compound", compounding.offer_amount.to_string()),
+        distributed_rewards,
+    ))
+}
+
+/// Splits stables for buying Psi with compounding, if it is enabled.
+/// Action is not changed if bAsset price impact is too high
+fn plan_compounding(
+    deps: Deps,
+    config: &Config,
+    tax_info: &TaxInfo,
+    action_with_profit: ActionWithProfit,
+) -> StdResult<(ActionWithProfit, Option<Compounding>)> {
+    let compounding_config = load_compounding_config(deps.storage)?;
+    let basset_stable_swap_contract = match compounding_config.basset_stable_swap_contract {
+        Some(ref basset_stable_swap_contract) if compounding_config.is_enabled() => {
+            basset_stable_swap_contract
+        }
+        _ => return Ok((action_with_profit, None)),
+    };
+
+    let (split_action, to_compound) =
+        action_with_profit.split_compounding(compounding_config.compound_share);
+    let offer_amount: Uint128 = tax_info.subtract_tax(to_compound).into();
+    if offer_amount.is_zero() {
+        return Ok((action_with_profit, None));
+    }
+
+    let basset_swap = query_reward_swap(
+        deps,
+        basset_stable_swap_contract,
+        None,
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: config.stable_denom.clone(),
+            },
+            amount: offer_amount,
+        },
+        compounding_config.max_spread,
+        None,
+    )?;
+    match basset_swap {
+        Some(basset_swap) => Ok((
+            split_action,
+            Some(Compounding {
+                offer_amount,
+                basset_swap,
+            }),
+        )),
+        None => Ok((action_with_profit, None)),
+    }
 }
 
-pub fn claim_remainded_stables(deps: Deps, env: Env) -> StdResult<Response> {
+/// Locks bAsset bought on rewards distribution. bAsset sent to vault by someone else
+/// is not a part of nAsset share price, so it is left on balance
+pub fn lock_compounded_basset(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    let config = load_config(deps.storage)?;
+    let basset_balance =
+        query_token_balance(deps.as_ref(), &config.basset_token, &env.contract.address);
+    let basset_balance_before = load_basset_balance_before_compounding(deps.storage)?;
+    let basset_bought: Uint256 = basset_balance
+        .checked_sub(basset_balance_before)
+        .unwrap_or_default()
+        .into();
+    if basset_bought.is_zero() {
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "lock_compounded_basset"),
+            ("skipped", "zero_basset"),
+        ]));
+    }
+
+    update_ledger(deps.storage, |ledger| {
+        ledger.basset_compounded += basset_bought;
+    })?;
+
+    Ok(Response::new()
+        .add_messages(
+            config
+                .market_adapter()
+                .lock_collateral(&config.basset_token, basset_bought)?,
+        )
+        .add_attributes(vec![
+            ("action", "lock_compounded_basset"),
+            ("basset_amount", &basset_bought.to_string()),
+        ]))
+}
+
+pub fn claim_remainded_stables(deps: Deps, env: Env) -> ContractResult<Response> {
+    assert_not_paused(deps, "claim_remainder", |pause| pause.claim_remainder)?;
+    //remainder belongs to nAsset holders
+    if load_unwinding(deps.storage)? {
+        return Err(ContractError::Unwinding);
+    }
     let config: Config = load_config(deps.storage)?;
-    let borrower_info: BorrowerInfoResponse =
-        query_borrower_info(deps, &config.anchor_market_contract, &env.contract.address)?;
-    let borrowed_amount = borrower_info.loan_amount;
+    let borrowed_amount = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?
+        .loan_amount;
 
     if !borrowed_amount.is_zero() {
         Err(StdError::generic_err(format!(
             "wait until there will be 0 loan amount (no bAsset stakers), current loan: {}",
             borrowed_amount
-        )))
+        ))
+        .into())
     } else {
-        let aterra_balance = query_token_balance(deps, &config.aterra_token, &env.contract.address);
+        let yield_venue = config.venue_adapter();
+        let aterra_balance = yield_venue.share_balance(deps, &env.contract.address)?;
 
         if aterra_balance.is_zero() {
             buy_psi_on_remainded_stable_coins(deps, env, config)
@@ -655,15 +1254,7 @@ pub fn claim_remainded_stables(deps: Deps, env: Env) -> StdResult<Response> {
             //if there are too many borrowers
             Ok(Response::new()
                 .add_submessage(SubMsg::reply_always(
-                    WasmMsg::Execute {
-                        contract_addr: config.aterra_token.to_string(),
-                        msg: to_binary(&Cw20ExecuteMsg::Send {
-                            contract: config.anchor_market_contract.to_string(),
-                            amount: aterra_balance,
-                            msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {})?,
-                        })?,
-                        funds: vec![],
-                    },
+                    yield_venue.redeem(aterra_balance)?,
                     SubmsgIds::RedeemStableOnRemainder.id(),
                 ))
                 .add_attributes(vec![
@@ -681,7 +1272,7 @@ pub fn buy_psi_on_remainded_stable_coins(
     deps: Deps,
     env: Env,
     config: Config,
-) -> StdResult<Response> {
+) -> ContractResult<Response> {
     let stable_coin_balance = query_balance(
         &deps.querier,
         &env.contract.address,
@@ -701,20 +1292,34 @@ pub fn buy_psi_on_remainded_stable_coins(
             amount: stable_coin_to_buy_psi,
         };
 
+        let router_config = load_swap_router_config(deps.storage)?;
+        let psi_swap = query_reward_swap(
+            deps,
+            &config.psi_stable_swap_contract,
+            router_config.psi_router_route(),
+            swap_asset,
+            config.psi_stable_max_spread,
+            config.psi_stable_twap_oracle.as_ref(),
+        )?;
+        let psi_swap = match psi_swap {
+            Some(psi_swap) => psi_swap,
+            //keep stables, ClaimRemainder can be called again later
+            None => {
+                return Ok(Response::new().add_attributes(vec![
+                    ("action", "distribute_remainded_rewards"),
+                    ("skipped", "price_impact_too_high"),
+                ]))
+            }
+        };
+
         Ok(Response::new()
-            .add_message(WasmMsg::Execute {
-                contract_addr: config.psi_stable_swap_contract.to_string(),
-                msg: to_binary(&AstroportExecuteMsg::Swap {
-                    offer_asset: swap_asset,
-                    max_spread: None,
-                    belief_price: None,
-                    to: Some(config.governance_contract.to_string()),
-                })?,
-                funds: vec![Coin {
+            .add_message(psi_swap.native_swap_msg(
+                Coin {
                     denom: config.stable_denom.clone(),
                     amount: stable_coin_to_buy_psi,
-                }],
-            })
+                },
+                Some(config.governance_contract.to_string()),
+            )?)
             .add_attributes(vec![
                 ("action", "distribute_remainded_rewards"),
                 ("bying_psi", &stable_coin_to_buy_psi.to_string()),
@@ -722,6 +1327,639 @@ pub fn buy_psi_on_remainded_stable_coins(
     }
 }
 
+/// Executor: governance
+pub fn start_unwind(deps: DepsMut, env: Env, config: &Config) -> ContractResult<Response> {
+    store_unwinding(deps.storage, &true)?;
+    //keeper rewards are not paid while unwinding, so reserved stables
+    //are shared between nAsset holders with the rest of balance
+    store_rebalance_reward_reserve(deps.storage, &Uint256::zero())?;
+    unwind_logic(deps, env, config)
+}
+
+/// Next unwinding step, called on every Rebalance:
+/// repay loan until it is zero, then redeem all aUST
+fn unwind_logic(deps: DepsMut, env: Env, config: &Config) -> ContractResult<Response> {
+    let (response, repaying_loan_state) = unwind_response(deps.as_ref(), &env, config)?;
+    if let Some(repaying_loan_state) = repaying_loan_state {
+        store_repaying_loan_state(deps.storage, &repaying_loan_state)?;
+    }
+    Ok(response)
+}
+
+/// Unwinding step messages and loan repayment state to store (if loan is repaid)
+pub(crate) fn unwind_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+) -> StdResult<(Response, Option<RepayingLoanState>)> {
+    let unwind_progress = UnwindProgress::query(deps, env, config)?;
+
+    if !unwind_progress.loan_amount.is_zero() {
+        let mut repaying_loan_state = RepayingLoanState {
+            to_repay_amount: unwind_progress.loan_amount,
+            aim_buffer_size: Uint256::zero(),
+            ..RepayingLoanState::default()
+        };
+        let response = repay_response(deps, env, config, &mut repaying_loan_state)?;
+        return Ok((
+            response.add_attribute("unwind", "repay_loan"),
+            Some(repaying_loan_state),
+        ));
+    }
+
+    if !unwind_progress.aterra_balance.is_zero() {
+        let response = Response::new()
+            .add_message(
+                config
+                    .venue_adapter()
+                    .redeem(unwind_progress.aterra_balance)?,
+            )
+            .add_attributes(vec![
+                ("action", "unwind"),
+                (
+                    "selling_aterra",
+                    &unwind_progress.aterra_balance.to_string(),
+                ),
+            ]);
+        return Ok((response, None));
+    }
+
+    Ok((
+        Response::new().add_attributes(vec![("action", "unwind"), ("status", "completed")]),
+        None,
+    ))
+}
+
+/// Executor: yourself
+/// Withdraw first request from queue. If it fails (e.g. not enough liquidity
+/// to repay loan) request stays in queue, cause submessage state is reverted
+pub fn process_pending_withdrawal(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    assert_not_paused(deps.as_ref(), "withdraw", |pause| pause.withdraw)?;
+
+    let (withdrawal_id, pending_withdrawal) = match pop_pending_withdrawal(deps.storage)? {
+        Some(pending) => pending,
+        None => {
+            return Ok(Response::new().add_attributes(vec![
+                ("action", "process_withdrawal"),
+                ("skipped", "queue_empty"),
+            ]))
+        }
+    };
+    let is_queue_empty = load_withdrawal_queue_state(deps.storage)?.is_empty();
+
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps.as_ref(), &env, &config)?;
+    let response = if vault_balances
+        .basset_to_withdraw(pending_withdrawal.nasset_amount)
+        .is_zero()
+    {
+        //request became worth nothing and would fail forever, give nAsset back and move on
+        Response::new()
+            .add_message(WasmMsg::Execute {
+                contract_addr: config.nasset_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: pending_withdrawal.recipient.to_string(),
+                    amount: pending_withdrawal.nasset_amount.into(),
+                })?,
+                funds: vec![],
+            })
+            .add_attributes(vec![
+                ("action", "process_withdrawal"),
+                ("skipped", "zero_basset_to_withdraw"),
+            ])
+    } else {
+        withdraw_basset(
+            deps,
+            env.clone(),
+            config,
+            pending_withdrawal.recipient,
+            pending_withdrawal.nasset_amount,
+            None,
+            None,
+        )?
+    }
+    .add_attribute("withdrawal_id", withdrawal_id.to_string());
+
+    if is_queue_empty {
+        Ok(response)
+    } else {
+        //process next one in separate submessage, so its fail do not revert this one
+        Ok(response.add_submessage(process_withdrawal_submsg(&env)?))
+    }
+}
+
+/// Add withdrawal queue processing to Rebalance and HonestWork responses
+pub fn with_pending_withdrawals(deps: Deps, env: &Env, response: Response) -> StdResult<Response> {
+    if load_withdrawal_queue_state(deps.storage)?.is_empty() {
+        return Ok(response);
+    }
+
+    Ok(response.add_submessage(process_withdrawal_submsg(env)?))
+}
+
+fn process_withdrawal_submsg(env: &Env) -> StdResult<SubMsg> {
+    Ok(SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::Yourself {
+                yourself_msg: YourselfMsg::ProcessWithdrawal {},
+            })?,
+            funds: vec![],
+        },
+        SubmsgIds::ProcessWithdrawal.id(),
+    ))
+}
+
+/// Farmer share of stables left after unwinding
+fn unwound_stables_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    farmer: &Addr,
+    nasset_amount: Uint256,
+    nasset_supply: Uint256,
+) -> ContractResult<Response> {
+    if !UnwindProgress::query(deps, env, config)?.is_completed() {
+        return Err(ContractError::UnwindNotCompleted);
+    }
+
+    let stable_coin_balance: Uint256 = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?
+    .into();
+    let stables_share =
+        stable_coin_balance * nasset_amount / Decimal256::from_uint256(nasset_supply);
+    let tax_info = get_tax_info(deps, &config.stable_denom)?;
+    let stables_to_send = tax_info.subtract_tax(stables_share);
+
+    let response = Response::new().add_attribute("stables_amount", stables_to_send.to_string());
+    if stables_to_send.is_zero() {
+        return Ok(response);
+    }
+
+    Ok(response.add_message(BankMsg::Send {
+        to_address: farmer.to_string(),
+        amount: vec![Coin {
+            denom: config.stable_denom.clone(),
+            amount: stables_to_send.into(),
+        }],
+    }))
+}
+
+pub fn emergency_deleverage(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    let deleverage_config = load_emergency_deleverage_config(deps.storage)?;
+    let basset_stable_swap_contract = match &deleverage_config.basset_stable_swap_contract {
+        Some(swap_contract) if deleverage_config.is_enabled() => swap_contract,
+        _ => return Err(ContractError::EmergencyDeleverageDisabled),
+    };
+
+    let config: Config = load_config(deps.storage)?;
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps.as_ref(), &env.contract.address)?;
+    let loan_amount = config
+        .market_adapter()
+        .loan_info(deps.as_ref(), &env.contract.address)?
+        .loan_amount;
+
+    let strategy_config =
+        query_strategy_config(deps.as_ref(), &config.basset_vault_strategy_contract)?;
+    //same median price as strategy uses, refuse to sell collateral on stale one
+    let basset_price =
+        query_strategy_fresh_price(deps.as_ref(), &config.basset_vault_strategy_contract)?;
+
+    //stables that can be used to repay loan without selling collateral
+    let stable_coin_balance = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?;
+    let yield_venue = config.venue_adapter();
+    let yield_position = yield_venue.position(deps.as_ref(), &env.contract.address)?;
+    let redeemable_stables =
+        yield_venue.available_liquidity(deps.as_ref(), &config.stable_denom)?;
+    let available_stables =
+        Uint256::from(stable_coin_balance) + yield_position.value().min(redeemable_stables);
+
+    let basset_to_sell = calc_basset_to_sell_on_emergency(
+        loan_amount,
+        basset_in_custody,
+        basset_price,
+        available_stables,
+        deleverage_config.trigger_ltv,
+        strategy_config.basset_max_ltv,
+        deleverage_config.max_basset_amount,
+    );
+    if basset_to_sell.is_zero() {
+        return Err(ContractError::EmergencyDeleverageNotNeeded);
+    }
+
+    let swap_limits = query_swap_limits(
+        deps.as_ref(),
+        basset_stable_swap_contract,
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: config.basset_token.clone(),
+            },
+            amount: basset_to_sell.into(),
+        },
+        deleverage_config.max_spread,
+        None,
+    )?;
+    let swap_limits = match swap_limits {
+        Some(swap_limits) => swap_limits,
+        None => {
+            return Ok(Response::new().add_attributes(vec![
+                ("action", "emergency_deleverage"),
+                ("skipped", "price_impact_too_high"),
+            ]))
+        }
+    };
+
+    store_stable_balance_before_selling_basset(deps.storage, &stable_coin_balance)?;
+
+    Ok(Response::new()
+        .add_messages(
+            config
+                .market_adapter()
+                .unlock_collateral(&config.basset_token, basset_to_sell)?,
+        )
+        .add_messages(vec![
+            WasmMsg::Execute {
+                contract_addr: config.basset_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: basset_stable_swap_contract.to_string(),
+                    amount: basset_to_sell.into(),
+                    msg: to_binary(&AstroportCw20HookMsg::Swap {
+                        belief_price: Some(swap_limits.belief_price),
+                        max_spread: Some(swap_limits.max_spread),
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            },
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::Yourself {
+                    yourself_msg: YourselfMsg::RepayAfterDeleverage {},
+                })?,
+                funds: vec![],
+            },
+        ])
+        .add_attributes(vec![
+            ("action", "emergency_deleverage"),
+            ("basset_sold", &basset_to_sell.to_string()),
+        ]))
+}
+
+pub fn repay_after_deleverage(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    let config: Config = load_config(deps.storage)?;
+    let stable_coin_balance_before_sell_basset =
+        load_stable_balance_before_selling_basset(deps.storage)?;
+    let stable_coin_balance = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?;
+    if stable_coin_balance <= stable_coin_balance_before_sell_basset {
+        return Err(StdError::generic_err("no stables received for bAsset").into());
+    }
+
+    let loan_amount = config
+        .market_adapter()
+        .loan_info(deps.as_ref(), &env.contract.address)?
+        .loan_amount;
+    let tax_info = get_tax_info(deps.as_ref(), &config.stable_denom)?;
+    let repay_amount = loan_amount.min(
+        tax_info
+            .subtract_tax((stable_coin_balance - stable_coin_balance_before_sell_basset).into()),
+    );
+    update_ledger(deps.storage, |ledger| ledger.loan_repaid(repay_amount))?;
+
+    Ok(Response::new()
+        .add_message(
+            config
+                .market_adapter()
+                .repay(&config.stable_denom, repay_amount)?,
+        )
+        .add_attributes(vec![
+            ("action", "repay_after_deleverage"),
+            ("loan_amount", &repay_amount.to_string()),
+        ]))
+}
+
+pub fn update_emergency_deleverage_config(
+    deps: DepsMut,
+    config: &Config,
+    basset_stable_swap_contract_addr: Option<String>,
+    trigger_ltv: Option<Decimal256>,
+    max_basset_amount: Option<Uint256>,
+    max_spread: Option<Decimal256>,
+) -> ContractResult<Response> {
+    let mut deleverage_config = load_emergency_deleverage_config(deps.storage)?;
+
+    if let Some(ref basset_stable_swap_contract_addr) = basset_stable_swap_contract_addr {
+        deleverage_config.basset_stable_swap_contract =
+            Some(deps.api.addr_validate(basset_stable_swap_contract_addr)?);
+    }
+
+    if let Some(trigger_ltv) = trigger_ltv {
+        //selling collateral after Anchor liquidation makes no sense
+        let strategy_config =
+            query_strategy_config(deps.as_ref(), &config.basset_vault_strategy_contract)?;
+        if trigger_ltv.is_zero() || trigger_ltv >= strategy_config.basset_max_ltv {
+            return Err(ContractError::InappropriateValue);
+        }
+        deleverage_config.trigger_ltv = trigger_ltv;
+    }
+
+    if let Some(max_basset_amount) = max_basset_amount {
+        deleverage_config.max_basset_amount = max_basset_amount;
+    }
+
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+        deleverage_config.max_spread = max_spread;
+    }
+
+    store_emergency_deleverage_config(deps.storage, &deleverage_config)?;
+    Ok(Response::default())
+}
+
+pub fn update_swap_router_config(
+    deps: DepsMut,
+    config: &Config,
+    router_contract_addr: Option<String>,
+    anc_route: Option<Vec<SwapOperation>>,
+    psi_route: Option<Vec<SwapOperation>>,
+) -> ContractResult<Response> {
+    let mut router_config = load_swap_router_config(deps.storage)?;
+
+    if let Some(ref router_contract_addr) = router_contract_addr {
+        router_config.router_contract = Some(deps.api.addr_validate(router_contract_addr)?);
+    }
+
+    let stable_asset = AssetInfo::NativeToken {
+        denom: config.stable_denom.clone(),
+    };
+    if let Some(anc_route) = anc_route {
+        let anc_asset = AssetInfo::Token {
+            contract_addr: config.anchor_token.clone(),
+        };
+        validate_swap_route(deps.as_ref(), &anc_route, &anc_asset, &stable_asset)?;
+        router_config.anc_route = anc_route;
+    }
+
+    if let Some(psi_route) = psi_route {
+        let psi_asset = AssetInfo::Token {
+            contract_addr: config.psi_token.clone(),
+        };
+        validate_swap_route(deps.as_ref(), &psi_route, &stable_asset, &psi_asset)?;
+        router_config.psi_route = psi_route;
+    }
+
+    store_swap_router_config(deps.storage, &router_config)?;
+    Ok(Response::default())
+}
+
+pub fn update_compounding_config(
+    deps: DepsMut,
+    basset_stable_swap_contract_addr: Option<String>,
+    compound_share: Option<Decimal256>,
+    max_spread: Option<Decimal256>,
+) -> ContractResult<Response> {
+    let mut compounding_config = load_compounding_config(deps.storage)?;
+
+    if let Some(ref basset_stable_swap_contract_addr) = basset_stable_swap_contract_addr {
+        compounding_config.basset_stable_swap_contract =
+            Some(deps.api.addr_validate(basset_stable_swap_contract_addr)?);
+    }
+
+    if let Some(compound_share) = compound_share {
+        if compound_share > Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        compounding_config.compound_share = compound_share;
+    }
+
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+        compounding_config.max_spread = max_spread;
+    }
+
+    store_compounding_config(deps.storage, &compounding_config)?;
+    Ok(Response::default())
+}
+
+pub fn update_keeper_config(
+    deps: DepsMut,
+    honest_work_reward_rate: Option<Decimal256>,
+    rebalance_reward: Option<Uint256>,
+    reward_cap: Option<Uint256>,
+    min_interval: Option<u64>,
+) -> ContractResult<Response> {
+    let mut keeper_config = load_keeper_config(deps.storage)?;
+
+    if let Some(honest_work_reward_rate) = honest_work_reward_rate {
+        if honest_work_reward_rate >= Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        keeper_config.honest_work_reward_rate = honest_work_reward_rate;
+    }
+
+    if let Some(rebalance_reward) = rebalance_reward {
+        keeper_config.rebalance_reward = rebalance_reward;
+    }
+
+    if let Some(reward_cap) = reward_cap {
+        keeper_config.reward_cap = reward_cap;
+    }
+
+    if let Some(min_interval) = min_interval {
+        keeper_config.min_interval = min_interval;
+    }
+
+    store_keeper_config(deps.storage, &keeper_config)?;
+    Ok(Response::default())
+}
+
+pub fn update_yield_venue(
+    deps: DepsMut,
+    env: Env,
+    mut config: Config,
+    yield_venue: YieldVenueKind,
+) -> ContractResult<Response> {
+    if config.yield_venue == yield_venue {
+        return Err(ContractError::InappropriateValue);
+    }
+    if load_unwinding(deps.storage)? {
+        return Err(ContractError::Unwinding);
+    }
+
+    let current_venue = config.venue_adapter();
+    let position = current_venue.position(deps.as_ref(), &env.contract.address)?;
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "update_yield_venue"),
+        ("redeemed_shares", &position.share_amount.to_string()),
+    ]);
+    if !position.share_amount.is_zero() {
+        let position_value = position.value();
+        let available_liquidity =
+            current_venue.available_liquidity(deps.as_ref(), &config.stable_denom)?;
+        if position_value > available_liquidity {
+            return Err(ContractError::NotEnoughYieldVenueLiquidity {
+                wanted: position_value,
+                available: available_liquidity,
+            });
+        }
+        //redeemed stables are deposited to new venue right after
+        response = response.add_messages(vec![
+            current_venue.redeem(position.share_amount)?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::Yourself {
+                    yourself_msg: YourselfMsg::DepositToYieldVenue {},
+                })?,
+                funds: vec![],
+            }),
+        ]);
+    }
+
+    config.yield_venue = yield_venue;
+    store_config(deps.storage, &config)?;
+
+    Ok(response)
+}
+
+pub fn update_guardian(deps: DepsMut, guardian_addr: Option<String>) -> ContractResult<Response> {
+    match guardian_addr {
+        Some(ref guardian_addr) => {
+            store_guardian(deps.storage, &deps.api.addr_validate(guardian_addr)?)?
+        }
+        None => remove_guardian(deps.storage),
+    }
+
+    Ok(Response::default())
+}
+
+pub fn set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: &Config,
+    deposit: Option<bool>,
+    withdraw: Option<bool>,
+    borrow: Option<bool>,
+    honest_work: Option<bool>,
+    claim_remainder: Option<bool>,
+) -> ContractResult<Response> {
+    let guardian = load_guardian(deps.storage)?;
+    if Some(&info.sender) != guardian.as_ref() && info.sender != config.governance_contract {
+        return Err(StdError::generic_err("unauthorized").into());
+    }
+
+    let mut pause_state = load_pause_state(deps.storage)?;
+    if let Some(deposit) = deposit {
+        pause_state.deposit = deposit;
+    }
+
+    if let Some(withdraw) = withdraw {
+        pause_state.withdraw = withdraw;
+    }
+
+    if let Some(borrow) = borrow {
+        pause_state.borrow = borrow;
+    }
+
+    if let Some(honest_work) = honest_work {
+        pause_state.honest_work = honest_work;
+    }
+
+    if let Some(claim_remainder) = claim_remainder {
+        pause_state.claim_remainder = claim_remainder;
+    }
+
+    store_pause_state(deps.storage, &pause_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_pause"),
+        ("deposit", &pause_state.deposit.to_string()),
+        ("withdraw", &pause_state.withdraw.to_string()),
+        ("borrow", &pause_state.borrow.to_string()),
+        ("honest_work", &pause_state.honest_work.to_string()),
+        ("claim_remainder", &pause_state.claim_remainder.to_string()),
+    ]))
+}
+
+fn assert_not_paused(
+    deps: Deps,
+    operation: &str,
+    is_paused: fn(&PauseState) -> bool,
+) -> ContractResult<()> {
+    if is_paused(&load_pause_state(deps.storage)?) {
+        return Err(ContractError::Paused {
+            operation: operation.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_max_spread(max_spread: Decimal256) -> ContractResult<()> {
+    if max_spread.is_zero() || max_spread >= Decimal256::one() {
+        return Err(ContractError::InappropriateValue);
+    }
+
+    Ok(())
+}
+
+/// Route should be empty (swap on pair) or a chain of operations
+/// from `offer_asset` to `ask_asset`
+fn validate_swap_route(
+    deps: Deps,
+    operations: &[SwapOperation],
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> ContractResult<()> {
+    let (first, last) = match (operations.first(), operations.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(()),
+    };
+    if &first.offer_asset_info() != offer_asset || &last.ask_asset_info() != ask_asset {
+        return Err(ContractError::InappropriateValue);
+    }
+
+    for pair in operations.windows(2) {
+        if pair[0].ask_asset_info() != pair[1].offer_asset_info() {
+            return Err(ContractError::InappropriateValue);
+        }
+    }
+
+    for operation in operations {
+        if let AssetInfo::Token { contract_addr } = operation.ask_asset_info() {
+            deps.api.addr_validate(contract_addr.as_str())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails if `deadline` (unix seconds) is already in the past
+fn assert_deadline(block: &BlockInfo, deadline: Option<u64>) -> ContractResult<()> {
+    if let Some(deadline) = deadline {
+        let current_time = get_time(block);
+        if current_time > deadline {
+            return Err(ContractError::DeadlineExceeded {
+                deadline,
+                current_time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn get_time(block: &BlockInfo) -> u64 {
     block.time.seconds()
 }
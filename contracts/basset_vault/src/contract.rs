@@ -12,17 +12,18 @@ use crate::{
     state::{
         config_set_nasset_token, config_set_psi_distributor, load_child_contracts_info,
         load_config, load_nasset_token_config_holder, store_child_contracts_info, store_config,
-        store_nasset_token_config_holder, update_loan_state_part_of_loan_repaid,
+        store_nasset_token_config_holder, update_ledger, update_loan_state_part_of_loan_repaid,
         ChildContractsInfo,
     },
-    SubmsgIds, TOO_HIGH_BORROW_DEMAND_ERR_MSG,
+    ContractResult, SubmsgIds, DEFAULT_SWAP_MAX_SPREAD_PERCENT,
 };
 use basset_vault::{
-    anchor::basset_custody::get_basset_in_custody,
     astroport_factory::{ExecuteMsg as AstroportFactoryExecuteMsg, PairType},
     basset_vault::{
-        AnyoneMsg, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg, QueryMsg, YourselfMsg,
+        AnyoneMsg, ExecuteMsg, GovernanceMsg, GuardianMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+        YourselfMsg,
     },
+    lending_market::LendingMarketKind,
     nasset_token::InstantiateMsg as NAssetTokenInstantiateMsg,
     nasset_token_config_holder::{
         AnyoneMsg as NAssetTokenConfigHolderAnyoneMsg,
@@ -32,7 +33,9 @@ use basset_vault::{
     nasset_token_rewards::InstantiateMsg as NAssetTokenRewardsInstantiateMsg,
     psi_distributor::InstantiateMsg as PsiDistributorInstantiateMsg,
     terraswap::AssetInfo,
+    yield_venue::YieldVenueKind,
 };
+use cosmwasm_bignumber::Decimal256;
 use cw20::MinterResponse;
 use protobuf::Message;
 use std::convert::TryFrom;
@@ -61,6 +64,12 @@ pub fn instantiate(
         over_loan_balance_value: msg.over_loan_balance_value,
         nasset_token: Addr::unchecked(""),
         psi_distributor: Addr::unchecked(""),
+        anc_stable_max_spread: Decimal256::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT),
+        psi_stable_max_spread: Decimal256::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT),
+        anc_stable_twap_oracle: None,
+        psi_stable_twap_oracle: None,
+        lending_market: LendingMarketKind::Anchor {},
+        yield_venue: YieldVenueKind::AnchorEarn {},
     };
     store_config(deps.storage, &config)?;
 
@@ -98,7 +107,7 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> ContractResult<Response> {
     let submessage_enum = SubmsgIds::try_from(msg.id)?;
     match submessage_enum {
         SubmsgIds::InitNAssetConfigHolder => {
@@ -310,25 +319,16 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
 
         SubmsgIds::RedeemStableOnRepayLoan => match msg.result {
             cosmwasm_std::ContractResult::Err(err_msg) => {
-                if err_msg
-                    .to_lowercase()
-                    .contains(TOO_HIGH_BORROW_DEMAND_ERR_MSG)
-                {
-                    //we need to repay loan a bit, before redeem stables
-                    commands::repay_logic_on_reply(deps, env)
-                } else {
-                    return Err(StdError::generic_err(format!(
-                        "fail to redeem stables, reason: {}",
-                        err_msg
-                    ))
-                    .into());
-                }
+                commands::repay_logic_on_redeem_error(deps, env, err_msg)
             }
             cosmwasm_std::ContractResult::Ok(_) => commands::repay_logic_on_reply(deps, env),
         },
 
         SubmsgIds::RepayLoan => {
-            let _ = update_loan_state_part_of_loan_repaid(deps.storage)?;
+            let repaying_loan_state = update_loan_state_part_of_loan_repaid(deps.storage)?;
+            update_ledger(deps.storage, |ledger| {
+                ledger.loan_repaid(repaying_loan_state.repaying_amount)
+            })?;
             Ok(Response::default())
         }
 
@@ -339,11 +339,25 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
             //we do not care about errors here, just send all your stables to governance
             commands::buy_psi_on_remainded_stable_coins(deps.as_ref(), env, config)
         }
+
+        //reply only on error, rebalance should not fail because of keeper reward
+        SubmsgIds::KeeperReward => commands::restore_rebalance_reward_reserve(deps),
+
+        //reply only on error, request stays in queue till next try
+        SubmsgIds::ProcessWithdrawal => Ok(Response::new().add_attributes(vec![
+            ("action", "process_withdrawal"),
+            ("skipped", "withdraw_failed"),
+        ])),
     }
 }
 
 #[entry_point]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> ContractResult<Response> {
     match msg {
         ExecuteMsg::Receive(msg) => commands::receive_cw20(deps, env, info, msg),
 
@@ -352,37 +366,67 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 let config = load_config(deps.storage)?;
 
                 // basset balance in custody contract
-                let basset_in_custody = get_basset_in_custody(
-                    deps.as_ref(),
-                    &config.anchor_custody_basset_contract,
-                    &env.contract.address.clone(),
+                let basset_in_custody = config
+                    .market_adapter()
+                    .collateral_amount(deps.as_ref(), &env.contract.address)?;
+
+                let response = commands::rebalance(
+                    deps.branch(),
+                    env.clone(),
+                    &config,
+                    basset_in_custody,
+                    None,
+                    //deposit rebalances vault by calling itself, it is not a keeper
+                    if info.sender == env.contract.address {
+                        None
+                    } else {
+                        Some(&info.sender)
+                    },
                 )?;
-
-                commands::rebalance(deps, env, &config, basset_in_custody, None)
+                Ok(commands::with_pending_withdrawals(
+                    deps.as_ref(),
+                    &env,
+                    response,
+                )?)
             }
 
-            AnyoneMsg::HonestWork {} => commands::claim_anc_rewards(deps, env),
+            AnyoneMsg::HonestWork {} => {
+                let response = commands::claim_anc_rewards(deps.branch(), env.clone(), info)?;
+                Ok(commands::with_pending_withdrawals(
+                    deps.as_ref(),
+                    &env,
+                    response,
+                )?)
+            }
 
             AnyoneMsg::ClaimRemainder {} => commands::claim_remainded_stables(deps.as_ref(), env),
 
             AnyoneMsg::AcceptGovernance {} => commands::accept_governance(deps, env, info),
+
+            AnyoneMsg::EmergencyDeleverage {} => commands::emergency_deleverage(deps, env),
         },
 
         ExecuteMsg::Yourself { yourself_msg } => {
             if info.sender != env.contract.address {
-                return Err(StdError::generic_err("unauthorized"));
+                return Err(StdError::generic_err("unauthorized").into());
             }
 
             match yourself_msg {
                 YourselfMsg::SwapAnc {} => commands::swap_anc(deps, env),
                 YourselfMsg::DisributeRewards {} => commands::distribute_rewards(deps, env),
+                YourselfMsg::ProcessWithdrawal {} => {
+                    commands::process_pending_withdrawal(deps, env)
+                }
+                YourselfMsg::RepayAfterDeleverage {} => commands::repay_after_deleverage(deps, env),
+                YourselfMsg::DepositToYieldVenue {} => commands::deposit_to_yield_venue(deps, env),
+                YourselfMsg::LockCompoundedBasset {} => commands::lock_compounded_basset(deps, env),
             }
         }
 
         ExecuteMsg::Governance { governance_msg } => {
             let config: Config = load_config(deps.storage)?;
             if info.sender != config.governance_contract {
-                return Err(StdError::generic_err("unauthorized"));
+                return Err(StdError::generic_err("unauthorized").into());
             }
 
             match governance_msg {
@@ -396,6 +440,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     basset_vault_strategy_contract_addr,
                     claiming_rewards_delay,
                     over_loan_balance_value,
+                    anc_stable_max_spread,
+                    psi_stable_max_spread,
+                    anc_stable_twap_oracle_addr,
+                    psi_stable_twap_oracle_addr,
                 } => commands::update_config(
                     deps,
                     config,
@@ -408,6 +456,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     basset_vault_strategy_contract_addr,
                     claiming_rewards_delay,
                     over_loan_balance_value,
+                    anc_stable_max_spread,
+                    psi_stable_max_spread,
+                    anc_stable_twap_oracle_addr,
+                    psi_stable_twap_oracle_addr,
                 ),
 
                 GovernanceMsg::UpdateGovernanceContract {
@@ -419,6 +471,89 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     gov_addr,
                     seconds_to_wait_for_accept_gov_tx,
                 ),
+
+                GovernanceMsg::UpdateKeeperConfig {
+                    honest_work_reward_rate,
+                    rebalance_reward,
+                    reward_cap,
+                    min_interval,
+                } => commands::update_keeper_config(
+                    deps,
+                    honest_work_reward_rate,
+                    rebalance_reward,
+                    reward_cap,
+                    min_interval,
+                ),
+
+                GovernanceMsg::UpdateGuardian { guardian_addr } => {
+                    commands::update_guardian(deps, guardian_addr)
+                }
+
+                GovernanceMsg::UpdateEmergencyDeleverageConfig {
+                    basset_stable_swap_contract_addr,
+                    trigger_ltv,
+                    max_basset_amount,
+                    max_spread,
+                } => commands::update_emergency_deleverage_config(
+                    deps,
+                    &config,
+                    basset_stable_swap_contract_addr,
+                    trigger_ltv,
+                    max_basset_amount,
+                    max_spread,
+                ),
+
+                GovernanceMsg::Unwind {} => commands::start_unwind(deps, env, &config),
+
+                GovernanceMsg::UpdateYieldVenue { yield_venue } => {
+                    commands::update_yield_venue(deps, env, config, yield_venue)
+                }
+
+                GovernanceMsg::UpdateSwapRouterConfig {
+                    router_contract_addr,
+                    anc_route,
+                    psi_route,
+                } => commands::update_swap_router_config(
+                    deps,
+                    &config,
+                    router_contract_addr,
+                    anc_route,
+                    psi_route,
+                ),
+
+                GovernanceMsg::UpdateCompoundingConfig {
+                    basset_stable_swap_contract_addr,
+                    compound_share,
+                    max_spread,
+                } => commands::update_compounding_config(
+                    deps,
+                    basset_stable_swap_contract_addr,
+                    compound_share,
+                    max_spread,
+                ),
+            }
+        }
+
+        ExecuteMsg::Guardian { guardian_msg } => {
+            let config: Config = load_config(deps.storage)?;
+
+            match guardian_msg {
+                GuardianMsg::SetPause {
+                    deposit,
+                    withdraw,
+                    borrow,
+                    honest_work,
+                    claim_remainder,
+                } => commands::set_pause(
+                    deps,
+                    info,
+                    &config,
+                    deposit,
+                    withdraw,
+                    borrow,
+                    honest_work,
+                    claim_remainder,
+                ),
             }
         }
     }
@@ -431,6 +566,41 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Rebalance {} => to_binary(&queries::query_rebalance(deps, env)?),
         QueryMsg::ChildContractsCodeId {} => to_binary(&queries::child_contracts_code_id(deps)?),
         QueryMsg::IsRewardsClaimable {} => to_binary(&queries::is_rewards_claimable(deps, env)?),
+        QueryMsg::ConvertToShares { basset_amount } => {
+            to_binary(&queries::convert_to_shares(deps, env, basset_amount)?)
+        }
+        QueryMsg::ConvertToAssets { nasset_amount } => {
+            to_binary(&queries::convert_to_assets(deps, env, nasset_amount)?)
+        }
+        QueryMsg::PreviewDeposit { basset_amount } => {
+            to_binary(&queries::preview_deposit(deps, env, basset_amount)?)
+        }
+        QueryMsg::PreviewWithdraw { nasset_amount } => {
+            to_binary(&queries::preview_withdraw(deps, env, nasset_amount)?)
+        }
+        QueryMsg::MaxDeposit {} => to_binary(&queries::max_deposit(deps, env)?),
+        QueryMsg::MaxWithdraw { address } => to_binary(&queries::max_withdraw(deps, env, address)?),
+        QueryMsg::TotalAssets {} => to_binary(&queries::total_assets(deps, env)?),
+        QueryMsg::PositionHealth {} => to_binary(&queries::position_health(deps, env)?),
+        QueryMsg::KeeperConfig {} => to_binary(&queries::keeper_config(deps)?),
+        QueryMsg::PauseStatus {} => to_binary(&queries::pause_status(deps)?),
+        QueryMsg::UnwindStatus {} => to_binary(&queries::unwind_status(deps, env)?),
+        QueryMsg::PendingWithdrawals { start_after, limit } => {
+            to_binary(&queries::pending_withdrawals(deps, start_after, limit)?)
+        }
+        QueryMsg::RepaymentPlan {} => to_binary(&queries::repayment_plan(deps, env)?),
+        QueryMsg::EmergencyDeleverageConfig {} => {
+            to_binary(&queries::emergency_deleverage_config(deps)?)
+        }
+        QueryMsg::SwapRouterConfig {} => to_binary(&queries::swap_router_config(deps)?),
+        QueryMsg::CompoundingConfig {} => to_binary(&queries::compounding_config(deps)?),
+        QueryMsg::SimulateRebalance {} => to_binary(&queries::simulate_rebalance(deps, env)?),
+        QueryMsg::SimulateHonestWork {} => to_binary(&queries::simulate_honest_work(deps, env)?),
+        QueryMsg::Stats {} => to_binary(&queries::stats(deps)?),
+        QueryMsg::Epochs { start_after, limit } => {
+            to_binary(&queries::epochs(deps, start_after, limit)?)
+        }
+        QueryMsg::EstimatedApr {} => to_binary(&queries::estimated_apr(deps, env)?),
     }
 }
 
@@ -0,0 +1,51 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Inappropriate value")]
+    InappropriateValue,
+
+    #[error("Operation is paused: {operation}")]
+    Paused { operation: String },
+
+    #[error("Vault is unwinding")]
+    Unwinding,
+
+    #[error("Vault unwinding is not completed yet")]
+    UnwindNotCompleted,
+
+    #[error("Emergency deleverage is disabled")]
+    EmergencyDeleverageDisabled,
+
+    #[error("Emergency deleverage is not needed")]
+    EmergencyDeleverageNotNeeded,
+
+    #[error("Deadline exceeded: deadline {deadline}, current time {current_time}")]
+    DeadlineExceeded { deadline: u64, current_time: u64 },
+
+    #[error("Not enough liquidity in yield venue: wanted {wanted}, available {available}")]
+    NotEnoughYieldVenueLiquidity { wanted: Uint256, available: Uint256 },
+
+    #[error("Deposit is too small: zero nAsset to mint")]
+    ZeroNAssetToMint,
+
+    #[error("Withdrawal is too small: zero bAsset to withdraw")]
+    ZeroBAssetToWithdraw,
+
+    #[error("Too little nAsset to mint: minimum {min_nasset_out}, got {nasset_to_mint}")]
+    TooLittleNAssetOut {
+        min_nasset_out: Uint256,
+        nasset_to_mint: Uint256,
+    },
+
+    #[error("Too little bAsset to withdraw: minimum {min_basset_out}, got {basset_to_withdraw}")]
+    TooLittleBAssetOut {
+        min_basset_out: Uint256,
+        basset_to_withdraw: Uint256,
+    },
+}
@@ -1,8 +1,10 @@
 use cosmwasm_std::StdError;
+use error::ContractError;
 use std::convert::TryFrom;
 
 mod commands;
 pub mod contract;
+pub mod error;
 mod queries;
 mod reply_response;
 pub mod state;
@@ -12,10 +14,15 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
-//withdrawing from Anchor Deposit error
-pub const TOO_HIGH_BORROW_DEMAND_ERR_MSG: &str = "borrow demand too high";
+type ContractResult<T> = Result<T, ContractError>;
+
 pub const MIN_ANC_REWARDS_TO_CLAIM: u64 = 100_000_000u64;
 pub const MAX_SECS_DELAY_BETWEEN_ANC_CLAIM: u64 = 86_400;
+//price impact allowed on ANC -> UST and UST -> PSI swaps, until governance change it
+pub const DEFAULT_SWAP_MAX_SPREAD_PERCENT: u64 = 1;
+//nAsset minted to vault itself (locked forever) on first deposit,
+//so nAsset supply can't be reduced to a few units to inflate share price
+pub const MINIMUM_NASSET_LIQUIDITY: u64 = 1_000;
 
 pub enum SubmsgIds {
     InitNAssetConfigHolder,
@@ -27,6 +34,8 @@ pub enum SubmsgIds {
     RepayLoan,
     Borrowing,
     RedeemStableOnRemainder,
+    KeeperReward,
+    ProcessWithdrawal,
 }
 
 impl TryFrom<u64> for SubmsgIds {
@@ -49,6 +58,8 @@ impl TryFrom<u64> for SubmsgIds {
             x if x == SubmsgIds::RedeemStableOnRemainder.id() => {
                 Ok(SubmsgIds::RedeemStableOnRemainder)
             }
+            x if x == SubmsgIds::KeeperReward.id() => Ok(SubmsgIds::KeeperReward),
+            x if x == SubmsgIds::ProcessWithdrawal.id() => Ok(SubmsgIds::ProcessWithdrawal),
             unknown => Err(StdError::generic_err(format!(
                 "unknown reply message id: {}",
                 unknown
@@ -69,6 +80,8 @@ impl SubmsgIds {
             SubmsgIds::RepayLoan => 6,
             SubmsgIds::Borrowing => 7,
             SubmsgIds::RedeemStableOnRemainder => 8,
+            SubmsgIds::KeeperReward => 9,
+            SubmsgIds::ProcessWithdrawal => 10,
         }
     }
 }
@@ -1,20 +1,45 @@
 use basset_vault::{
-    anchor::basset_custody::get_basset_in_custody,
-    anchor::market::{
-        query_borrower_info, query_market_config, query_market_state, BorrowerInfoResponse,
-        ConfigResponse as AnchorMarketConfigResponse, StateResponse as AnchorMarketStateResponse,
-    },
+    anchor::market::{query_borrow_rate, query_market_config, query_market_state},
+    anchor::oracle::query_price,
+    anchor::overseer::query_deposit_rate,
+    astroport_pair::query_spot_price,
     basset_vault::{
-        ChildContractsInfoResponse, ConfigResponse, IsRewardsClaimableResponse, RebalanceResponse,
+        ChildContractsInfoResponse, CompoundingConfigResponse, ConfigResponse,
+        ConvertToAssetsResponse, ConvertToSharesResponse, EmergencyDeleverageConfigResponse,
+        EpochResponse, EpochsResponse, EstimatedAprResponse, IsRewardsClaimableResponse,
+        KeeperConfigResponse, MaxDepositResponse, MaxWithdrawResponse, PauseStatusResponse,
+        PendingWithdrawalResponse, PendingWithdrawalsResponse, PositionHealthResponse,
+        PreviewDepositResponse, PreviewWithdrawResponse, RebalanceResponse, RepaymentPlanResponse,
+        SimulateHonestWorkResponse, SimulateRebalanceResponse, StatsResponse,
+        SwapRouterConfigResponse, TotalAssetsResponse, UnwindStatusResponse,
     },
-    basset_vault_strategy::{query_borrower_action, BorrowerActionResponse},
-    querier::query_balance,
+    basset_vault_strategy::{query_borrower_action, query_strategy_config, BorrowerActionResponse},
+    psi_distributor::{query_psi_distributor_config, RewardsDistribution},
+    querier::{query_balance, query_token_balance},
+    terraswap::{Asset, AssetInfo},
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Deps, Env, StdResult};
+use cosmwasm_std::{attr, Deps, Env, Response, StdResult};
 
-use crate::state::{load_child_contracts_info, load_config};
-use crate::{state::Config, utils::is_anc_rewards_claimable};
+use crate::state::{
+    load_aim_buffer_size, load_child_contracts_info, load_compounding_config, load_config,
+    load_emergency_deleverage_config, load_epoch_records, load_guardian, load_keeper_config,
+    load_last_honest_work_reward_seconds, load_last_rebalance_reward_seconds, load_ledger,
+    load_pause_state, load_pending_withdrawals, load_rebalance_reward_reserve,
+    load_swap_router_config, load_unwinding, load_withdrawal_queue_state,
+};
+use crate::{
+    commands::{
+        borrow_logic, distribute_rewards_response, repay_response, unwind_response,
+        with_pending_withdrawals,
+    },
+    state::{Config, RepayingLoanState},
+    tax_querier::get_tax_info,
+    utils::{
+        calc_after_borrow_action, calc_estimated_apr, floor_to_uint, is_anc_rewards_claimable,
+        plan_loan_repayment, query_reward_swap, UnwindProgress, VaultBalances,
+    },
+};
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = load_config(deps.storage)?;
@@ -35,6 +60,12 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         claiming_rewards_delay: config.claiming_rewards_delay,
         over_loan_balance_value: config.over_loan_balance_value,
         psi_distributor_addr: config.psi_distributor.to_string(),
+        anc_stable_max_spread: config.anc_stable_max_spread,
+        psi_stable_max_spread: config.psi_stable_max_spread,
+        anc_stable_twap_oracle_addr: config.anc_stable_twap_oracle.map(|addr| addr.to_string()),
+        psi_stable_twap_oracle_addr: config.psi_stable_twap_oracle.map(|addr| addr.to_string()),
+        lending_market: config.lending_market,
+        yield_venue: config.yield_venue,
     })
 }
 
@@ -42,15 +73,14 @@ pub fn query_rebalance(deps: Deps, env: Env) -> StdResult<RebalanceResponse> {
     let config: Config = load_config(deps.storage)?;
 
     // basset balance in custody contract
-    let basset_in_custody = get_basset_in_custody(
-        deps,
-        &config.anchor_custody_basset_contract,
-        &env.contract.address.clone(),
-    )?;
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps, &env.contract.address)?;
 
-    let borrower_info: BorrowerInfoResponse =
-        query_borrower_info(deps, &config.anchor_market_contract, &env.contract.address)?;
-    let borrowed_ust = borrower_info.loan_amount;
+    let loan_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
+    let borrowed_ust = loan_info.loan_amount;
 
     let borrower_action = query_borrower_action(
         deps,
@@ -59,11 +89,20 @@ pub fn query_rebalance(deps: Deps, env: Env) -> StdResult<RebalanceResponse> {
         basset_in_custody,
     )?;
 
+    to_rebalance_response(deps, &config, borrower_action)
+}
+
+fn to_rebalance_response(
+    deps: Deps,
+    config: &Config,
+    borrower_action: BorrowerActionResponse,
+) -> StdResult<RebalanceResponse> {
     let response = match borrower_action {
-        BorrowerActionResponse::Nothing {} => RebalanceResponse::Nothing {},
+        BorrowerActionResponse::Nothing { .. } => RebalanceResponse::Nothing {},
         BorrowerActionResponse::Repay {
             amount,
             advised_buffer_size,
+            ..
         } => RebalanceResponse::Repay {
             amount,
             advised_buffer_size,
@@ -71,20 +110,13 @@ pub fn query_rebalance(deps: Deps, env: Env) -> StdResult<RebalanceResponse> {
         BorrowerActionResponse::Borrow {
             amount,
             advised_buffer_size,
+            ..
         } => {
-            let anchor_market_state = query_market_state(deps, &config.anchor_market_contract)?;
-            let anchor_market_balance = query_balance(
-                &deps.querier,
-                &config.anchor_market_contract,
-                config.stable_denom,
-            )?;
-            let anchor_market_config = query_market_config(deps, &config.anchor_market_contract)?;
-            let is_borrowing_possible = assert_max_borrow_factor(
-                anchor_market_config,
-                anchor_market_state,
-                anchor_market_balance.into(),
+            let is_borrowing_possible = config.market_adapter().is_borrowing_possible(
+                deps,
+                &config.stable_denom,
                 amount,
-            );
+            )?;
 
             RebalanceResponse::Borrow {
                 amount,
@@ -97,32 +129,6 @@ pub fn query_rebalance(deps: Deps, env: Env) -> StdResult<RebalanceResponse> {
     Ok(response)
 }
 
-//copypasted from anchor_market contract
-fn assert_max_borrow_factor(
-    market_config: AnchorMarketConfigResponse,
-    market_state: AnchorMarketStateResponse,
-    market_balance: Uint256,
-    borrow_amount: Uint256,
-) -> bool {
-    let current_balance = Decimal256::from_uint256(market_balance);
-    let borrow_amount = Decimal256::from_uint256(borrow_amount);
-
-    // Assert max borrow factor
-    if market_state.total_liabilities + borrow_amount
-        > (current_balance + market_state.total_liabilities - market_state.total_reserves)
-            * market_config.max_borrow_factor
-    {
-        return false;
-    }
-
-    // Assert available balance
-    if borrow_amount + market_state.total_reserves > current_balance {
-        return false;
-    }
-
-    return true;
-}
-
 pub fn child_contracts_code_id(deps: Deps) -> StdResult<ChildContractsInfoResponse> {
     let child_contracts_info = load_child_contracts_info(deps.storage)?;
     Ok(ChildContractsInfoResponse {
@@ -139,8 +145,9 @@ pub fn child_contracts_code_id(deps: Deps) -> StdResult<ChildContractsInfoRespon
 
 pub fn is_rewards_claimable(deps: Deps, env: Env) -> StdResult<IsRewardsClaimableResponse> {
     let config: Config = load_config(deps.storage)?;
-    let borrower_info =
-        query_borrower_info(deps, &config.anchor_market_contract, &env.contract.address)?;
+    let borrower_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
 
     let is_rewards_claimable = is_anc_rewards_claimable(deps, &env, borrower_info.pending_rewards)?;
 
@@ -151,3 +158,684 @@ pub fn is_rewards_claimable(deps: Deps, env: Env) -> StdResult<IsRewardsClaimabl
         current_height: 0,       //legacy field
     })
 }
+
+pub fn convert_to_shares(
+    deps: Deps,
+    env: Env,
+    basset_amount: Uint256,
+) -> StdResult<ConvertToSharesResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+
+    let nasset_amount = if vault_balances.is_frozen() {
+        Uint256::zero()
+    } else {
+        vault_balances.nasset_to_mint(basset_amount)
+    };
+
+    Ok(ConvertToSharesResponse { nasset_amount })
+}
+
+pub fn convert_to_assets(
+    deps: Deps,
+    env: Env,
+    nasset_amount: Uint256,
+) -> StdResult<ConvertToAssetsResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+
+    Ok(ConvertToAssetsResponse {
+        basset_amount: vault_balances.basset_to_withdraw(nasset_amount),
+    })
+}
+
+pub fn preview_deposit(
+    deps: Deps,
+    env: Env,
+    basset_amount: Uint256,
+) -> StdResult<PreviewDepositResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+    vault_balances.assert_not_frozen()?;
+
+    Ok(PreviewDepositResponse {
+        nasset_amount: vault_balances.nasset_to_mint(basset_amount),
+    })
+}
+
+pub fn preview_withdraw(
+    deps: Deps,
+    env: Env,
+    nasset_amount: Uint256,
+) -> StdResult<PreviewWithdrawResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+    vault_balances.assert_not_frozen()?;
+
+    Ok(PreviewWithdrawResponse {
+        basset_amount: vault_balances.basset_to_withdraw(nasset_amount),
+    })
+}
+
+pub fn max_deposit(deps: Deps, env: Env) -> StdResult<MaxDepositResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+
+    let basset_amount = if vault_balances.is_frozen() {
+        Uint256::zero()
+    } else {
+        //cw20 amounts are Uint128
+        Uint256::from(u128::MAX)
+    };
+
+    Ok(MaxDepositResponse { basset_amount })
+}
+
+pub fn max_withdraw(deps: Deps, env: Env, address: String) -> StdResult<MaxWithdrawResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if vault_balances.is_frozen() {
+        return Ok(MaxWithdrawResponse {
+            nasset_amount: Uint256::zero(),
+            basset_amount: Uint256::zero(),
+        });
+    }
+
+    let nasset_amount: Uint256 = query_token_balance(deps, &config.nasset_token, &address).into();
+
+    Ok(MaxWithdrawResponse {
+        nasset_amount,
+        basset_amount: vault_balances.basset_to_withdraw(nasset_amount),
+    })
+}
+
+pub fn total_assets(deps: Deps, env: Env) -> StdResult<TotalAssetsResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let vault_balances = VaultBalances::query(deps, &env, &config)?;
+
+    let unsolicited_basset: Uint256 =
+        query_token_balance(deps, &config.basset_token, &env.contract.address).into();
+
+    Ok(TotalAssetsResponse {
+        basset_in_custody: vault_balances.basset_in_custody,
+        nasset_supply: vault_balances.nasset_supply,
+        unsolicited_basset,
+    })
+}
+
+pub fn position_health(deps: Deps, env: Env) -> StdResult<PositionHealthResponse> {
+    let config: Config = load_config(deps.storage)?;
+
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps, &env.contract.address)?;
+    let loan_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
+    let loan_amount = loan_info.loan_amount;
+
+    let strategy_config = query_strategy_config(deps, &config.basset_vault_strategy_contract)?;
+    let oracle_price = query_price(
+        deps,
+        &deps.api.addr_validate(&strategy_config.oracle_contract)?,
+        strategy_config.basset_token.clone(),
+        strategy_config.stable_denom.clone(),
+    )?;
+
+    let max_borrow_amount: Uint256 =
+        basset_in_custody * oracle_price.rate * strategy_config.basset_max_ltv;
+    let current_ltv = if max_borrow_amount.is_zero() {
+        None
+    } else {
+        Some(Decimal256::from_uint256(loan_amount) / Decimal256::from_uint256(max_borrow_amount))
+    };
+
+    // loan_amount = basset_in_custody * liquidation_price * basset_max_ltv
+    let liquidation_price = if loan_amount.is_zero() || basset_in_custody.is_zero() {
+        None
+    } else {
+        Some(
+            Decimal256::from_uint256(loan_amount)
+                / (Decimal256::from_uint256(basset_in_custody) * strategy_config.basset_max_ltv),
+        )
+    };
+
+    let yield_position = config
+        .venue_adapter()
+        .position(deps, &env.contract.address)?;
+    let aterra_value = yield_position.value();
+    let stable_balance: Uint256 = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?
+    .into();
+
+    let coverage_ratio = if loan_amount.is_zero() {
+        None
+    } else {
+        Some(
+            Decimal256::from_uint256(aterra_value + stable_balance)
+                / Decimal256::from_uint256(loan_amount),
+        )
+    };
+
+    Ok(PositionHealthResponse {
+        basset_in_custody,
+        loan_amount,
+        basset_price: oracle_price.rate,
+        price_last_updated_base: oracle_price.last_updated_base,
+        price_last_updated_quote: oracle_price.last_updated_quote,
+        max_borrow_amount,
+        current_ltv,
+        borrow_ltv_min: strategy_config.borrow_ltv_min,
+        borrow_ltv_aim: strategy_config.borrow_ltv_aim,
+        borrow_ltv_max: strategy_config.borrow_ltv_max,
+        liquidation_price,
+        aterra_balance: yield_position.share_amount,
+        aterra_exchange_rate: yield_position.exchange_rate,
+        aterra_value,
+        stable_balance,
+        //not stored until first borrow
+        aim_buffer_size: load_aim_buffer_size(deps.storage).unwrap_or_default(),
+        coverage_ratio,
+        over_loan_balance_value: config.over_loan_balance_value,
+    })
+}
+
+pub fn keeper_config(deps: Deps) -> StdResult<KeeperConfigResponse> {
+    let keeper_config = load_keeper_config(deps.storage)?;
+
+    Ok(KeeperConfigResponse {
+        honest_work_reward_rate: keeper_config.honest_work_reward_rate,
+        rebalance_reward: keeper_config.rebalance_reward,
+        reward_cap: keeper_config.reward_cap,
+        min_interval: keeper_config.min_interval,
+        last_honest_work_reward_time: load_last_honest_work_reward_seconds(deps.storage)?,
+        last_rebalance_reward_time: load_last_rebalance_reward_seconds(deps.storage)?,
+        rebalance_reward_reserve: load_rebalance_reward_reserve(deps.storage)?,
+    })
+}
+
+pub fn pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    let pause_state = load_pause_state(deps.storage)?;
+
+    Ok(PauseStatusResponse {
+        guardian_addr: load_guardian(deps.storage)?.map(|addr| addr.to_string()),
+        deposit: pause_state.deposit,
+        withdraw: pause_state.withdraw,
+        borrow: pause_state.borrow,
+        honest_work: pause_state.honest_work,
+        claim_remainder: pause_state.claim_remainder,
+    })
+}
+
+pub fn unwind_status(deps: Deps, env: Env) -> StdResult<UnwindStatusResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let unwinding = load_unwinding(deps.storage)?;
+    let unwind_progress = UnwindProgress::query(deps, &env, &config)?;
+
+    Ok(UnwindStatusResponse {
+        unwinding,
+        completed: unwinding && unwind_progress.is_completed(),
+        loan_amount: unwind_progress.loan_amount,
+        aterra_balance: unwind_progress.aterra_balance,
+    })
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn pending_withdrawals(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingWithdrawalsResponse> {
+    let queue_state = load_withdrawal_queue_state(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let requests = load_pending_withdrawals(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, pending_withdrawal)| PendingWithdrawalResponse {
+            id,
+            recipient: pending_withdrawal.recipient.to_string(),
+            nasset_amount: pending_withdrawal.nasset_amount,
+        })
+        .collect();
+
+    Ok(PendingWithdrawalsResponse {
+        total_nasset_amount: queue_state.total_nasset_amount,
+        pending_count: queue_state.len(),
+        requests,
+    })
+}
+
+pub fn stats(deps: Deps) -> StdResult<StatsResponse> {
+    let ledger = load_ledger(deps.storage)?;
+    Ok(StatsResponse {
+        anc_claimed: ledger.anc_claimed,
+        anc_sold: ledger.anc_sold,
+        stables_realised: ledger.stables_realised,
+        keeper_rewards: ledger.keeper_rewards,
+        deposited_to_anc: ledger.deposited_to_anc,
+        stables_to_psi: ledger.stables_to_psi,
+        psi_bought: ledger.psi_bought,
+        interest_paid: ledger.interest_paid,
+        stables_to_compound: ledger.stables_to_compound,
+        basset_compounded: ledger.basset_compounded,
+        rebalances: ledger.rebalances,
+        epochs: ledger.epochs,
+    })
+}
+
+pub fn epochs(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<EpochsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let epochs = load_epoch_records(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, epoch_record)| EpochResponse {
+            id,
+            time: epoch_record.time,
+            height: epoch_record.height,
+            anc_sold: epoch_record.anc_sold,
+            stables_realised: epoch_record.stables_realised,
+            keeper_reward: epoch_record.keeper_reward,
+            deposited_to_anc: epoch_record.deposited_to_anc,
+            stables_to_psi: epoch_record.stables_to_psi,
+            psi_bought: epoch_record.psi_bought,
+            interest_paid: epoch_record.interest_paid,
+            loan_amount: epoch_record.loan_amount,
+        })
+        .collect();
+
+    Ok(EpochsResponse { epochs })
+}
+
+pub fn repayment_plan(deps: Deps, env: Env) -> StdResult<RepaymentPlanResponse> {
+    let config: Config = load_config(deps.storage)?;
+
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps, &env.contract.address)?;
+    let loan_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
+    let borrower_action = query_borrower_action(
+        deps,
+        &config.basset_vault_strategy_contract,
+        loan_info.loan_amount,
+        basset_in_custody,
+    )?;
+
+    let (to_repay_amount, aim_buffer_size) = match borrower_action {
+        BorrowerActionResponse::Repay {
+            amount,
+            advised_buffer_size,
+            ..
+        } => (amount, advised_buffer_size),
+        _ => {
+            return Ok(RepaymentPlanResponse {
+                to_repay_amount: Uint256::zero(),
+                aim_buffer_size: Uint256::zero(),
+                aterra_to_redeem: Uint256::zero(),
+                expected_stables: Uint256::zero(),
+                repay_amount: Uint256::zero(),
+                is_complete: true,
+            });
+        }
+    };
+
+    let yield_venue = config.venue_adapter();
+    let aterra_balance = yield_venue.share_balance(deps, &env.contract.address)?;
+    let aterra_exchange_rate = yield_venue.exchange_rate(deps)?;
+    let stable_coin_balance = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?;
+    let redeemable_stables = yield_venue.available_liquidity(deps, &config.stable_denom)?;
+    let tax_info = get_tax_info(deps, &config.stable_denom)?;
+
+    let repay_plan = plan_loan_repayment(
+        stable_coin_balance.into(),
+        aterra_balance,
+        aterra_exchange_rate,
+        redeemable_stables,
+        to_repay_amount,
+        aim_buffer_size,
+        &tax_info,
+    );
+
+    Ok(RepaymentPlanResponse {
+        to_repay_amount,
+        aim_buffer_size,
+        aterra_to_redeem: repay_plan.aterra_to_redeem,
+        expected_stables: repay_plan.expected_stables,
+        repay_amount: repay_plan.repay_amount,
+        is_complete: repay_plan.is_complete,
+    })
+}
+
+pub fn emergency_deleverage_config(deps: Deps) -> StdResult<EmergencyDeleverageConfigResponse> {
+    let deleverage_config = load_emergency_deleverage_config(deps.storage)?;
+    Ok(EmergencyDeleverageConfigResponse {
+        enabled: deleverage_config.is_enabled(),
+        basset_stable_swap_contract_addr: deleverage_config
+            .basset_stable_swap_contract
+            .map(|addr| addr.to_string()),
+        trigger_ltv: deleverage_config.trigger_ltv,
+        max_basset_amount: deleverage_config.max_basset_amount,
+        max_spread: deleverage_config.max_spread,
+    })
+}
+
+pub fn compounding_config(deps: Deps) -> StdResult<CompoundingConfigResponse> {
+    let compounding_config = load_compounding_config(deps.storage)?;
+    Ok(CompoundingConfigResponse {
+        enabled: compounding_config.is_enabled(),
+        basset_stable_swap_contract_addr: compounding_config
+            .basset_stable_swap_contract
+            .map(|addr| addr.to_string()),
+        compound_share: compounding_config.compound_share,
+        max_spread: compounding_config.max_spread,
+    })
+}
+
+pub fn swap_router_config(deps: Deps) -> StdResult<SwapRouterConfigResponse> {
+    let router_config = load_swap_router_config(deps.storage)?;
+    Ok(SwapRouterConfigResponse {
+        router_contract_addr: router_config.router_contract.map(|addr| addr.to_string()),
+        anc_route: router_config.anc_route,
+        psi_route: router_config.psi_route,
+    })
+}
+
+pub fn simulate_rebalance(deps: Deps, env: Env) -> StdResult<SimulateRebalanceResponse> {
+    let config: Config = load_config(deps.storage)?;
+
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps, &env.contract.address)?;
+    let loan_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
+    let borrower_action = query_borrower_action(
+        deps,
+        &config.basset_vault_strategy_contract,
+        loan_info.loan_amount,
+        basset_in_custody,
+    )?;
+    let rebalance = to_rebalance_response(deps, &config, borrower_action.clone())?;
+
+    if load_unwinding(deps.storage)? {
+        let (response, _) = unwind_response(deps, &env, &config)?;
+        let response = with_pending_withdrawals(deps, &env, response)?;
+        return Ok(SimulateRebalanceResponse {
+            rebalance,
+            unwinding: true,
+            skipped: None,
+            keeper_reward: Uint256::zero(),
+            messages: response.messages.into_iter().map(|m| m.msg).collect(),
+            attributes: response.attributes,
+        });
+    }
+
+    let mut skipped = None;
+    let response = match borrower_action {
+        BorrowerActionResponse::Nothing { .. } => {
+            Response::new().add_attribute("action", "rebalance_not_needed")
+        }
+
+        BorrowerActionResponse::Borrow { .. } if load_pause_state(deps.storage)?.borrow => {
+            skipped = Some("borrow_paused".to_string());
+            Response::new()
+                .add_attributes(vec![("action", "rebalance"), ("skipped", "borrow_paused")])
+        }
+
+        BorrowerActionResponse::Borrow {
+            amount,
+            advised_buffer_size,
+            ..
+        } => {
+            //Anchor sends borrowed stables with tax
+            let tax_info = get_tax_info(deps, &config.stable_denom)?;
+            let stable_coin_balance: Uint256 = query_balance(
+                &deps.querier,
+                &env.contract.address,
+                config.stable_denom.clone(),
+            )?
+            .into();
+            let after_borrow_response = calc_after_borrow_action(
+                stable_coin_balance + tax_info.subtract_tax(amount),
+                advised_buffer_size,
+                &tax_info,
+            )
+            .to_response(&config)?;
+
+            borrow_logic(&config, amount, advised_buffer_size)?
+                .add_submessages(after_borrow_response.messages)
+                .add_attributes(after_borrow_response.attributes)
+        }
+
+        BorrowerActionResponse::Repay {
+            amount,
+            advised_buffer_size,
+            ..
+        } => {
+            let mut repaying_loan_state = RepayingLoanState {
+                to_repay_amount: amount,
+                aim_buffer_size: advised_buffer_size,
+                ..RepayingLoanState::default()
+            };
+            repay_response(deps, &env, &config, &mut repaying_loan_state)?
+        }
+    };
+
+    let keeper_reward = match (&rebalance, &skipped) {
+        (RebalanceResponse::Nothing {}, _) | (_, Some(_)) => Uint256::zero(),
+        _ => {
+            let keeper_config = load_keeper_config(deps.storage)?;
+            let last_reward_time = load_last_rebalance_reward_seconds(deps.storage)?;
+            if keeper_config.is_interval_passed(last_reward_time, env.block.time.seconds()) {
+                keeper_config.rebalance_reward_from(load_rebalance_reward_reserve(deps.storage)?)
+            } else {
+                Uint256::zero()
+            }
+        }
+    };
+
+    let response = with_pending_withdrawals(deps, &env, response)?;
+    Ok(SimulateRebalanceResponse {
+        rebalance,
+        unwinding: false,
+        skipped,
+        keeper_reward,
+        messages: response.messages.into_iter().map(|m| m.msg).collect(),
+        attributes: response.attributes,
+    })
+}
+
+pub fn simulate_honest_work(deps: Deps, env: Env) -> StdResult<SimulateHonestWorkResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let mut simulation_response = SimulateHonestWorkResponse {
+        claimable: false,
+        anc_amount: Uint256::zero(),
+        expected_stables: Uint256::zero(),
+        keeper_reward: Uint256::zero(),
+        messages: vec![],
+        attributes: vec![],
+    };
+
+    let borrower_info = config
+        .market_adapter()
+        .loan_info(deps, &env.contract.address)?;
+    if load_pause_state(deps.storage)?.honest_work
+        || !is_anc_rewards_claimable(deps, &env, borrower_info.pending_rewards)?
+    {
+        return Ok(simulation_response);
+    }
+    simulation_response.claimable = true;
+
+    //claimed rewards are added to ANC balance before swap
+    let anc_amount = floor_to_uint(borrower_info.pending_rewards)
+        + Uint256::from(query_token_balance(
+            deps,
+            &config.anchor_token,
+            &env.contract.address,
+        ));
+    simulation_response.anc_amount = anc_amount;
+
+    let router_config = load_swap_router_config(deps.storage)?;
+    let anc_swap = query_reward_swap(
+        deps,
+        &config.anc_stable_swap_contract,
+        router_config.anc_router_route(),
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: config.anchor_token.clone(),
+            },
+            amount: anc_amount.into(),
+        },
+        config.anc_stable_max_spread,
+        config.anc_stable_twap_oracle.as_ref(),
+    )?;
+    let anc_swap = match anc_swap {
+        Some(anc_swap) => anc_swap,
+        None => {
+            simulation_response.attributes = vec![
+                attr("action", "swap_anc"),
+                attr("skipped", "price_impact_too_high"),
+            ];
+            return Ok(simulation_response);
+        }
+    };
+    let expected_stables: Uint256 = anc_swap.return_amount().into();
+    simulation_response.expected_stables = expected_stables;
+
+    if load_unwinding(deps.storage)? {
+        simulation_response.attributes = vec![
+            attr("action", "distribute_rewards"),
+            attr("skipped", "unwinding"),
+        ];
+        return Ok(simulation_response);
+    }
+
+    let keeper_config = load_keeper_config(deps.storage)?;
+    let last_reward_time = load_last_honest_work_reward_seconds(deps.storage)?;
+    let keeper_reward =
+        if keeper_config.is_interval_passed(last_reward_time, env.block.time.seconds()) {
+            keeper_config.capped(expected_stables * keeper_config.honest_work_reward_rate)
+        } else {
+            Uint256::zero()
+        };
+    simulation_response.keeper_reward = keeper_reward;
+    let reserve_top_up = keeper_config.reserve_top_up(
+        load_rebalance_reward_reserve(deps.storage)?,
+        expected_stables - keeper_reward,
+    );
+
+    let stable_coin_balance: Uint256 = query_balance(
+        &deps.querier,
+        &env.contract.address,
+        config.stable_denom.clone(),
+    )?
+    .into();
+    let tax_info = get_tax_info(deps, &config.stable_denom)?;
+    //keeper rewards are not a part of profit
+    let (response, _) = distribute_rewards_response(
+        deps,
+        &env,
+        &config,
+        &tax_info,
+        stable_coin_balance + expected_stables - keeper_reward - reserve_top_up,
+        stable_coin_balance,
+    )?;
+    simulation_response.messages = response.messages.into_iter().map(|m| m.msg).collect();
+    simulation_response.attributes = response.attributes;
+
+    Ok(simulation_response)
+}
+
+pub fn estimated_apr(deps: Deps, env: Env) -> StdResult<EstimatedAprResponse> {
+    let config: Config = load_config(deps.storage)?;
+
+    let market_state = query_market_state(deps, &config.anchor_market_contract)?;
+    let market_config = query_market_config(deps, &config.anchor_market_contract)?;
+    let market_balance = query_balance(
+        &deps.querier,
+        &config.anchor_market_contract,
+        config.stable_denom.clone(),
+    )?;
+    let borrow_rate = query_borrow_rate(
+        deps,
+        &deps.api.addr_humanize(&market_config.interest_model)?,
+        market_balance.into(),
+        &market_state,
+    )?;
+    let deposit_rate = query_deposit_rate(deps, &config.anchor_overseer_contract)?;
+    let anc_price = query_spot_price(deps, &config.anc_stable_swap_contract, &config.anchor_token)?;
+    let psi_price = query_spot_price(deps, &config.psi_stable_swap_contract, &config.psi_token)?;
+
+    let strategy_config = query_strategy_config(deps, &config.basset_vault_strategy_contract)?;
+    let basset_in_custody = config
+        .market_adapter()
+        .collateral_amount(deps, &env.contract.address)?;
+    let oracle_price = query_price(
+        deps,
+        &deps.api.addr_validate(&strategy_config.oracle_contract)?,
+        strategy_config.basset_token.clone(),
+        strategy_config.stable_denom.clone(),
+    )?;
+    let collateral_value = basset_in_custody * oracle_price.rate;
+    let ltv = if collateral_value.is_zero() {
+        strategy_config.borrow_ltv_aim * strategy_config.basset_max_ltv
+    } else {
+        let loan_amount = config
+            .market_adapter()
+            .loan_info(deps, &env.contract.address)?
+            .loan_amount;
+        Decimal256::from_uint256(loan_amount) / Decimal256::from_uint256(collateral_value)
+    };
+
+    //psi_distributor compares its manual LTV with strategy aim LTV
+    let psi_distributor_config = query_psi_distributor_config(deps, &config.psi_distributor)?;
+    let protocol_fee = RewardsDistribution::protocol_fee(
+        strategy_config.borrow_ltv_aim,
+        psi_distributor_config.manual_ltv,
+        psi_distributor_config.fee_rate,
+    );
+
+    let estimated_apr = calc_estimated_apr(
+        deposit_rate,
+        borrow_rate,
+        market_state.anc_emission_rate,
+        market_state.total_liabilities,
+        anc_price,
+        ltv,
+        protocol_fee,
+    );
+    let psi_per_collateral_value = if psi_price.is_zero() {
+        Decimal256::zero()
+    } else {
+        estimated_apr.apr / psi_price
+    };
+
+    Ok(EstimatedAprResponse {
+        ltv,
+        deposit_apr: estimated_apr.deposit_apr,
+        borrow_apr: estimated_apr.borrow_apr,
+        distribution_apr: estimated_apr.distribution_apr,
+        anc_price,
+        psi_price,
+        deposit_yield: estimated_apr.deposit_yield,
+        distribution_yield: estimated_apr.distribution_yield,
+        borrow_cost: estimated_apr.borrow_cost,
+        nasset_holder_share: estimated_apr.nasset_holder_share,
+        apr: estimated_apr.apr,
+        psi_per_collateral_value,
+    })
+}
@@ -1,8 +1,13 @@
-use cw_storage_plus::Item;
+use cw_storage_plus::{Bound, Item, Map, U64Key};
 use serde::{Deserialize, Serialize};
 
+use basset_vault::astroport_router::SwapOperation;
+use basset_vault::lending_market::{LendingMarket, LendingMarketContracts, LendingMarketKind};
+use basset_vault::yield_venue::{YieldVenue, YieldVenueContracts, YieldVenueKind};
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage, Uint128};
+
+use crate::DEFAULT_SWAP_MAX_SPREAD_PERCENT;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Config {
@@ -22,6 +27,46 @@ pub struct Config {
     pub over_loan_balance_value: Decimal256,
     pub nasset_token: Addr,
     pub psi_distributor: Addr,
+    //missing in configs stored before they were introduced
+    #[serde(default = "default_swap_max_spread")]
+    pub anc_stable_max_spread: Decimal256,
+    #[serde(default = "default_swap_max_spread")]
+    pub psi_stable_max_spread: Decimal256,
+    //Astroport TWAP oracles with reference prices for reward swaps,
+    //missing in configs stored before they were introduced
+    #[serde(default)]
+    pub anc_stable_twap_oracle: Option<Addr>,
+    #[serde(default)]
+    pub psi_stable_twap_oracle: Option<Addr>,
+    //missing in configs stored before it was introduced
+    #[serde(default)]
+    pub lending_market: LendingMarketKind,
+    //missing in configs stored before it was introduced
+    #[serde(default)]
+    pub yield_venue: YieldVenueKind,
+}
+
+fn default_swap_max_spread() -> Decimal256 {
+    Decimal256::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT)
+}
+
+impl Config {
+    /// Adapter of money market where bAsset is locked and stables are borrowed
+    pub fn market_adapter(&self) -> Box<dyn LendingMarket> {
+        self.lending_market.adapter(LendingMarketContracts {
+            market: self.anchor_market_contract.clone(),
+            overseer: self.anchor_overseer_contract.clone(),
+            custody: self.anchor_custody_basset_contract.clone(),
+        })
+    }
+
+    /// Adapter of venue where borrowed stables are deposited
+    pub fn venue_adapter(&self) -> Box<dyn YieldVenue> {
+        self.yield_venue.adapter(YieldVenueContracts {
+            market: self.anchor_market_contract.clone(),
+            share_token: self.aterra_token.clone(),
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
@@ -31,6 +76,9 @@ pub struct RepayingLoanState {
     pub to_repay_amount: Uint256,
     pub repaying_amount: Uint256,
     pub aim_buffer_size: Uint256,
+    //stables expected from aterra redeeming, to tell liquidity shortfall on redeem error
+    #[serde(default)]
+    pub redeeming_stables: Uint256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
@@ -45,6 +93,222 @@ pub struct ChildContractsInfo {
     pub tax_rate: Decimal256,
 }
 
+/// Rewards for calling HonestWork and Rebalance.
+/// Default is zero rewards, i.e. disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct KeeperConfig {
+    pub honest_work_reward_rate: Decimal256,
+    pub rebalance_reward: Uint256,
+    pub reward_cap: Uint256,
+    pub min_interval: u64,
+}
+
+impl KeeperConfig {
+    pub fn capped(&self, reward: Uint256) -> Uint256 {
+        if reward > self.reward_cap {
+            self.reward_cap
+        } else {
+            reward
+        }
+    }
+
+    pub fn is_interval_passed(&self, last_reward_time: u64, current_time: u64) -> bool {
+        last_reward_time == 0 || current_time >= last_reward_time + self.min_interval
+    }
+
+    /// Rebalance reward can't be bigger than profit set aside for it
+    pub fn rebalance_reward_from(&self, reserve: Uint256) -> Uint256 {
+        std::cmp::min(self.capped(self.rebalance_reward), reserve)
+    }
+
+    /// Part of ANC selling profit to set aside, so reserve is enough for one Rebalance reward
+    pub fn reserve_top_up(&self, reserve: Uint256, profit: Uint256) -> Uint256 {
+        let reserve_target = self.capped(self.rebalance_reward);
+        if reserve >= reserve_target {
+            return Uint256::zero();
+        }
+
+        std::cmp::min(reserve_target - reserve, profit)
+    }
+}
+
+/// Selling collateral to repay loan when aUST can't be redeemed and LTV is close to liquidation.
+/// Default is disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct EmergencyDeleverageConfig {
+    pub basset_stable_swap_contract: Option<Addr>,
+    pub trigger_ltv: Decimal256,
+    pub max_basset_amount: Uint256,
+    pub max_spread: Decimal256,
+}
+
+impl EmergencyDeleverageConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.basset_stable_swap_contract.is_some()
+            && !self.trigger_ltv.is_zero()
+            && !self.max_basset_amount.is_zero()
+            && !self.max_spread.is_zero()
+    }
+}
+
+/// Buying bAsset on part of rewards and locking it as extra collateral.
+/// Default is disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CompoundingConfig {
+    pub basset_stable_swap_contract: Option<Addr>,
+    pub compound_share: Decimal256,
+    pub max_spread: Decimal256,
+}
+
+impl CompoundingConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.basset_stable_swap_contract.is_some()
+            && !self.compound_share.is_zero()
+            && !self.max_spread.is_zero()
+    }
+}
+
+/// DEX router routes for swapping rewards instead of pairs from config.
+/// Default is disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SwapRouterConfig {
+    pub router_contract: Option<Addr>,
+    //ANC -> stable
+    pub anc_route: Vec<SwapOperation>,
+    //stable -> Psi
+    pub psi_route: Vec<SwapOperation>,
+}
+
+impl SwapRouterConfig {
+    /// Router and route to sell ANC, None if ANC is sold on pair
+    pub fn anc_router_route(&self) -> Option<RouterRoute<'_>> {
+        self.router_route(&self.anc_route)
+    }
+
+    /// Router and route to buy Psi, None if Psi is bought on pair
+    pub fn psi_router_route(&self) -> Option<RouterRoute<'_>> {
+        self.router_route(&self.psi_route)
+    }
+
+    fn router_route<'a>(&'a self, operations: &'a [SwapOperation]) -> Option<RouterRoute<'a>> {
+        match self.router_contract {
+            Some(ref router_contract) if !operations.is_empty() => Some(RouterRoute {
+                router_contract,
+                operations,
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub struct RouterRoute<'a> {
+    pub router_contract: &'a Addr,
+    pub operations: &'a [SwapOperation],
+}
+
+/// Operations paused by guardian.
+/// Repaying loan is never paused
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PauseState {
+    pub deposit: bool,
+    pub withdraw: bool,
+    pub borrow: bool,
+    pub honest_work: bool,
+    pub claim_remainder: bool,
+}
+
+/// Withdrawal request with nAsset locked in vault,
+/// processed when there is enough liquidity to repay loan
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingWithdrawal {
+    pub recipient: Addr,
+    pub nasset_amount: Uint256,
+}
+
+/// FIFO queue of pending withdrawals: ids in [head, tail) are pending
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct WithdrawalQueueState {
+    pub head: u64,
+    pub tail: u64,
+    pub total_nasset_amount: Uint256,
+}
+
+impl WithdrawalQueueState {
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    pub fn len(&self) -> u64 {
+        self.tail - self.head
+    }
+}
+
+/// Cumulative vault accounting, for reporting only
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Ledger {
+    pub anc_claimed: Uint256,
+    pub anc_sold: Uint256,
+    /// stables received for sold ANC
+    pub stables_realised: Uint256,
+    pub keeper_rewards: Uint256,
+    pub deposited_to_anc: Uint256,
+    pub stables_to_psi: Uint256,
+    pub psi_bought: Uint256,
+    pub interest_paid: Uint256,
+    pub stables_to_compound: Uint256,
+    pub basset_compounded: Uint256,
+    pub rebalances: u64,
+    pub epochs: u64,
+    /// loan amount as vault sees it after own borrows and repayments,
+    /// loan growth above it is interest. None until loan is observed first time
+    pub tracked_loan_amount: Option<Uint256>,
+    /// sold ANC and interest since last epoch record
+    pub epoch_anc_sold: Uint256,
+    pub epoch_interest_paid: Uint256,
+}
+
+impl Ledger {
+    pub fn observe_loan(&mut self, loan_amount: Uint256) {
+        let interest = match self.tracked_loan_amount {
+            Some(tracked_loan_amount) if loan_amount > tracked_loan_amount => {
+                loan_amount - tracked_loan_amount
+            }
+            _ => Uint256::zero(),
+        };
+        self.interest_paid += interest;
+        self.epoch_interest_paid += interest;
+        self.tracked_loan_amount = Some(loan_amount);
+    }
+
+    pub fn loan_borrowed(&mut self, amount: Uint256) {
+        if let Some(tracked_loan_amount) = self.tracked_loan_amount {
+            self.tracked_loan_amount = Some(tracked_loan_amount + amount);
+        }
+    }
+
+    pub fn loan_repaid(&mut self, amount: Uint256) {
+        if let Some(tracked_loan_amount) = self.tracked_loan_amount {
+            self.tracked_loan_amount = Some(tracked_loan_amount - tracked_loan_amount.min(amount));
+        }
+    }
+}
+
+/// Results of one HonestWork: selling ANC and distributing profit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EpochRecord {
+    pub time: u64,
+    pub height: u64,
+    pub anc_sold: Uint256,
+    pub stables_realised: Uint256,
+    pub keeper_reward: Uint256,
+    pub deposited_to_anc: Uint256,
+    pub stables_to_psi: Uint256,
+    pub psi_bought: Uint256,
+    /// accrued since previous epoch
+    pub interest_paid: Uint256,
+    pub loan_amount: Uint256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct GovernanceUpdateState {
     pub new_governance_contract_addr: Addr,
@@ -76,15 +340,44 @@ static KEY_AIM_BUFFER_SIZE: Item<Uint256> = Item::new("aim_buf_size");
 static KEY_LAST_ANC_CLAIM_SECONDS: Item<u64> = Item::new("last_anc_claim_secs");
 
 static KEY_STABLE_BALANCE_BEFORE_SELL_ANC: Item<Uint128> = Item::new("balance_before_sell_anc");
+static KEY_BASSET_BALANCE_BEFORE_COMPOUNDING: Item<Uint128> =
+    Item::new("basset_balance_before_compounding");
 #[allow(dead_code)]
 static LEGACY_KEY_LAST_REWARDS_CLAIMING_HEIGHT: Item<u64> =
     Item::new("last_rewards_claiming_height");
+static KEY_EMERGENCY_DELEVERAGE_CONFIG: Item<EmergencyDeleverageConfig> =
+    Item::new("emergency_deleverage_config");
+static KEY_SWAP_ROUTER_CONFIG: Item<SwapRouterConfig> = Item::new("swap_router_config");
+static KEY_COMPOUNDING_CONFIG: Item<CompoundingConfig> = Item::new("compounding_config");
+static KEY_STABLE_BALANCE_BEFORE_SELL_BASSET: Item<Uint128> =
+    Item::new("balance_before_sell_basset");
+
 //need that only for instantiating
 static KEY_CHILD_CONTRACTS_INFO: Item<ChildContractsInfo> = Item::new("child_contracts_code_id");
 static KEY_NASSET_TOKEN_CONFIG_HOLDER: Item<Addr> = Item::new("nasset_token_config_holder");
 
 static KEY_GOVERNANCE_UPDATE: Item<GovernanceUpdateState> = Item::new("gov_update");
 
+static KEY_GUARDIAN: Item<Addr> = Item::new("guardian");
+static KEY_PAUSE: Item<PauseState> = Item::new("pause");
+static KEY_UNWINDING: Item<bool> = Item::new("unwinding");
+
+static KEY_WITHDRAWAL_QUEUE: Item<WithdrawalQueueState> = Item::new("withdrawal_queue");
+static PENDING_WITHDRAWALS: Map<U64Key, PendingWithdrawal> = Map::new("pending_withdrawals");
+
+static KEY_LEDGER: Item<Ledger> = Item::new("ledger");
+static EPOCHS: Map<U64Key, EpochRecord> = Map::new("epochs");
+
+static KEY_KEEPER_CONFIG: Item<KeeperConfig> = Item::new("keeper_config");
+//HonestWork caller, rewarded on DistributeRewards
+static KEY_HONEST_WORK_KEEPER: Item<Addr> = Item::new("honest_work_keeper");
+static KEY_LAST_HONEST_WORK_REWARD_SECONDS: Item<u64> = Item::new("last_honest_work_reward_secs");
+static KEY_LAST_REBALANCE_REWARD_SECONDS: Item<u64> = Item::new("last_rebalance_reward_secs");
+//ANC selling profit set aside to pay Rebalance callers
+static KEY_REBALANCE_REWARD_RESERVE: Item<Uint256> = Item::new("rebalance_reward_reserve");
+//Rebalance reward being sent, to put it back to reserve if sending fails
+static KEY_PENDING_REBALANCE_REWARD: Item<Uint256> = Item::new("pending_rebalance_reward");
+
 //need that only for instantiating
 static KEY_PSI_DISTRIBUTOR_INIT_INFO: Item<PsiDistributorInitInfo> =
     Item::new("psi_distributor_init_info");
@@ -164,6 +457,19 @@ pub fn store_stable_balance_before_selling_anc(
     KEY_STABLE_BALANCE_BEFORE_SELL_ANC.save(storage, balance)
 }
 
+pub fn load_basset_balance_before_compounding(storage: &dyn Storage) -> StdResult<Uint128> {
+    KEY_BASSET_BALANCE_BEFORE_COMPOUNDING
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_basset_balance_before_compounding(
+    storage: &mut dyn Storage,
+    balance: &Uint128,
+) -> StdResult<()> {
+    KEY_BASSET_BALANCE_BEFORE_COMPOUNDING.save(storage, balance)
+}
+
 pub fn load_child_contracts_info(storage: &dyn Storage) -> StdResult<ChildContractsInfo> {
     KEY_CHILD_CONTRACTS_INFO.load(storage)
 }
@@ -213,3 +519,265 @@ pub fn load_last_anc_claim_seconds(storage: &dyn Storage) -> StdResult<u64> {
         .may_load(storage)
         .map(|res| res.unwrap_or_default())
 }
+
+pub fn load_keeper_config(storage: &dyn Storage) -> StdResult<KeeperConfig> {
+    KEY_KEEPER_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_keeper_config(
+    storage: &mut dyn Storage,
+    keeper_config: &KeeperConfig,
+) -> StdResult<()> {
+    KEY_KEEPER_CONFIG.save(storage, keeper_config)
+}
+
+pub fn store_honest_work_keeper(storage: &mut dyn Storage, keeper: &Addr) -> StdResult<()> {
+    KEY_HONEST_WORK_KEEPER.save(storage, keeper)
+}
+
+/// Returns HonestWork caller and removes it, so it is rewarded only once
+pub fn take_honest_work_keeper(storage: &mut dyn Storage) -> StdResult<Option<Addr>> {
+    let keeper = KEY_HONEST_WORK_KEEPER.may_load(storage)?;
+    KEY_HONEST_WORK_KEEPER.remove(storage);
+    Ok(keeper)
+}
+
+pub fn load_last_honest_work_reward_seconds(storage: &dyn Storage) -> StdResult<u64> {
+    KEY_LAST_HONEST_WORK_REWARD_SECONDS
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_last_honest_work_reward_seconds(
+    storage: &mut dyn Storage,
+    reward_seconds: &u64,
+) -> StdResult<()> {
+    KEY_LAST_HONEST_WORK_REWARD_SECONDS.save(storage, reward_seconds)
+}
+
+pub fn load_last_rebalance_reward_seconds(storage: &dyn Storage) -> StdResult<u64> {
+    KEY_LAST_REBALANCE_REWARD_SECONDS
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_last_rebalance_reward_seconds(
+    storage: &mut dyn Storage,
+    reward_seconds: &u64,
+) -> StdResult<()> {
+    KEY_LAST_REBALANCE_REWARD_SECONDS.save(storage, reward_seconds)
+}
+
+pub fn load_rebalance_reward_reserve(storage: &dyn Storage) -> StdResult<Uint256> {
+    KEY_REBALANCE_REWARD_RESERVE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_rebalance_reward_reserve(
+    storage: &mut dyn Storage,
+    reserve: &Uint256,
+) -> StdResult<()> {
+    KEY_REBALANCE_REWARD_RESERVE.save(storage, reserve)
+}
+
+pub fn store_pending_rebalance_reward(
+    storage: &mut dyn Storage,
+    reward: &Uint256,
+) -> StdResult<()> {
+    KEY_PENDING_REBALANCE_REWARD.save(storage, reward)
+}
+
+/// Returns Rebalance reward being sent and removes it, so it is restored only once
+pub fn take_pending_rebalance_reward(storage: &mut dyn Storage) -> StdResult<Uint256> {
+    let reward = KEY_PENDING_REBALANCE_REWARD.may_load(storage)?;
+    KEY_PENDING_REBALANCE_REWARD.remove(storage);
+    Ok(reward.unwrap_or_default())
+}
+
+pub fn load_emergency_deleverage_config(
+    storage: &dyn Storage,
+) -> StdResult<EmergencyDeleverageConfig> {
+    KEY_EMERGENCY_DELEVERAGE_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_emergency_deleverage_config(
+    storage: &mut dyn Storage,
+    deleverage_config: &EmergencyDeleverageConfig,
+) -> StdResult<()> {
+    KEY_EMERGENCY_DELEVERAGE_CONFIG.save(storage, deleverage_config)
+}
+
+pub fn load_swap_router_config(storage: &dyn Storage) -> StdResult<SwapRouterConfig> {
+    KEY_SWAP_ROUTER_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_swap_router_config(
+    storage: &mut dyn Storage,
+    router_config: &SwapRouterConfig,
+) -> StdResult<()> {
+    KEY_SWAP_ROUTER_CONFIG.save(storage, router_config)
+}
+
+pub fn load_compounding_config(storage: &dyn Storage) -> StdResult<CompoundingConfig> {
+    KEY_COMPOUNDING_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_compounding_config(
+    storage: &mut dyn Storage,
+    compounding_config: &CompoundingConfig,
+) -> StdResult<()> {
+    KEY_COMPOUNDING_CONFIG.save(storage, compounding_config)
+}
+
+pub fn load_stable_balance_before_selling_basset(storage: &dyn Storage) -> StdResult<Uint128> {
+    KEY_STABLE_BALANCE_BEFORE_SELL_BASSET.load(storage)
+}
+
+pub fn store_stable_balance_before_selling_basset(
+    storage: &mut dyn Storage,
+    balance: &Uint128,
+) -> StdResult<()> {
+    KEY_STABLE_BALANCE_BEFORE_SELL_BASSET.save(storage, balance)
+}
+
+pub fn load_guardian(storage: &dyn Storage) -> StdResult<Option<Addr>> {
+    KEY_GUARDIAN.may_load(storage)
+}
+
+pub fn store_guardian(storage: &mut dyn Storage, guardian: &Addr) -> StdResult<()> {
+    KEY_GUARDIAN.save(storage, guardian)
+}
+
+pub fn remove_guardian(storage: &mut dyn Storage) {
+    KEY_GUARDIAN.remove(storage)
+}
+
+pub fn load_pause_state(storage: &dyn Storage) -> StdResult<PauseState> {
+    KEY_PAUSE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_pause_state(storage: &mut dyn Storage, pause_state: &PauseState) -> StdResult<()> {
+    KEY_PAUSE.save(storage, pause_state)
+}
+
+pub fn load_unwinding(storage: &dyn Storage) -> StdResult<bool> {
+    KEY_UNWINDING
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_unwinding(storage: &mut dyn Storage, unwinding: &bool) -> StdResult<()> {
+    KEY_UNWINDING.save(storage, unwinding)
+}
+
+pub fn load_withdrawal_queue_state(storage: &dyn Storage) -> StdResult<WithdrawalQueueState> {
+    KEY_WITHDRAWAL_QUEUE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn push_pending_withdrawal(
+    storage: &mut dyn Storage,
+    pending_withdrawal: &PendingWithdrawal,
+) -> StdResult<u64> {
+    let mut queue_state = load_withdrawal_queue_state(storage)?;
+    let id = queue_state.tail;
+    PENDING_WITHDRAWALS.save(storage, U64Key::from(id), pending_withdrawal)?;
+    queue_state.tail += 1;
+    queue_state.total_nasset_amount += pending_withdrawal.nasset_amount;
+    KEY_WITHDRAWAL_QUEUE.save(storage, &queue_state)?;
+    Ok(id)
+}
+
+pub fn pop_pending_withdrawal(
+    storage: &mut dyn Storage,
+) -> StdResult<Option<(u64, PendingWithdrawal)>> {
+    let mut queue_state = load_withdrawal_queue_state(storage)?;
+    if queue_state.is_empty() {
+        return Ok(None);
+    }
+
+    let id = queue_state.head;
+    let pending_withdrawal = PENDING_WITHDRAWALS.load(storage, U64Key::from(id))?;
+    PENDING_WITHDRAWALS.remove(storage, U64Key::from(id));
+    queue_state.head += 1;
+    queue_state.total_nasset_amount =
+        queue_state.total_nasset_amount - pending_withdrawal.nasset_amount;
+    KEY_WITHDRAWAL_QUEUE.save(storage, &queue_state)?;
+    Ok(Some((id, pending_withdrawal)))
+}
+
+pub fn load_pending_withdrawals(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: usize,
+) -> StdResult<Vec<(u64, PendingWithdrawal)>> {
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+    PENDING_WITHDRAWALS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, pending_withdrawal) = item?;
+            let mut id_bytes = [0u8; 8];
+            id_bytes.copy_from_slice(&key);
+            Ok((u64::from_be_bytes(id_bytes), pending_withdrawal))
+        })
+        .collect()
+}
+
+pub fn load_ledger(storage: &dyn Storage) -> StdResult<Ledger> {
+    KEY_LEDGER
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn update_ledger<A: FnOnce(&mut Ledger)>(
+    storage: &mut dyn Storage,
+    action: A,
+) -> StdResult<Ledger> {
+    let mut ledger = load_ledger(storage)?;
+    action(&mut ledger);
+    KEY_LEDGER.save(storage, &ledger)?;
+    Ok(ledger)
+}
+
+/// Saves epoch record with next id and resets ledger epoch counters
+pub fn push_epoch_record(storage: &mut dyn Storage, epoch_record: &EpochRecord) -> StdResult<u64> {
+    let mut ledger = load_ledger(storage)?;
+    let id = ledger.epochs;
+    EPOCHS.save(storage, U64Key::from(id), epoch_record)?;
+    ledger.epochs += 1;
+    ledger.epoch_anc_sold = Uint256::zero();
+    ledger.epoch_interest_paid = Uint256::zero();
+    KEY_LEDGER.save(storage, &ledger)?;
+    Ok(id)
+}
+
+pub fn load_epoch_records(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: usize,
+) -> StdResult<Vec<(u64, EpochRecord)>> {
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+    EPOCHS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, epoch_record) = item?;
+            let mut id_bytes = [0u8; 8];
+            id_bytes.copy_from_slice(&key);
+            Ok((u64::from_be_bytes(id_bytes), epoch_record))
+        })
+        .collect()
+}
@@ -1,17 +1,23 @@
-use crate::{tax_querier::TaxInfo, utils::ActionWithProfit};
+use crate::{
+    tax_querier::TaxInfo,
+    utils::{ActionWithProfit, DistributedRewards, RewardSwap, RouterSwap, SwapLimits},
+};
 
 use super::sdk::Sdk;
 use crate::{
     state::load_config,
     tests::sdk::{
-        ANCHOR_MARKET_CONTRACT, PSI_DISTRIBUTOR_CONTRACT, PSI_STABLE_SWAP_CONTRACT, STABLE_DENOM,
+        ANCHOR_MARKET_CONTRACT, PSI_DISTRIBUTOR_CONTRACT, PSI_STABLE_SWAP_CONTRACT, PSI_TOKEN,
+        ROUTER_CONTRACT, STABLE_DENOM,
     },
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{to_binary, Coin, Response, Uint128, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, Response, Uint128, WasmMsg};
+use std::str::FromStr;
 
 use basset_vault::{
     astroport_pair::ExecuteMsg as AstroportExecuteMsg,
+    astroport_router::{ExecuteMsg as AstroportRouterExecuteMsg, SwapOperation},
     psi_distributor::{
         AnyoneMsg as PsiDistributorAnyoneMsg, ExecuteMsg as PsiDistributorExecuteMsg,
     },
@@ -29,7 +35,9 @@ fn action_with_profit_nothing() {
     };
 
     let action_with_profit = ActionWithProfit::Nothing;
-    let response = action_with_profit.to_response(&config, &tax_info).unwrap();
+    let response = action_with_profit
+        .to_response(&config, &tax_info, None)
+        .unwrap();
     assert_eq!(
         response,
         Response::new().add_attributes(vec![
@@ -52,7 +60,17 @@ fn action_with_profit_buy_psi() {
     let action_with_profit = ActionWithProfit::BuyPsi {
         amount: buy_psi_amount,
     };
-    let response = action_with_profit.to_response(&config, &tax_info).unwrap();
+    let psi_swap = RewardSwap::Pair {
+        pair_contract: Addr::unchecked(PSI_STABLE_SWAP_CONTRACT),
+        limits: SwapLimits {
+            belief_price: Decimal::from_str("0.5").unwrap(),
+            max_spread: Decimal::percent(1),
+            return_amount: Uint128::new(9_000),
+        },
+    };
+    let response = action_with_profit
+        .to_response(&config, &tax_info, Some(&psi_swap))
+        .unwrap();
 
     let swap_asset = Asset {
         info: AssetInfo::NativeToken {
@@ -66,8 +84,8 @@ fn action_with_profit_buy_psi() {
                 contract_addr: PSI_STABLE_SWAP_CONTRACT.to_string(),
                 msg: to_binary(&AstroportExecuteMsg::Swap {
                     offer_asset: swap_asset,
-                    max_spread: None,
-                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    belief_price: Some(Decimal::from_str("0.5").unwrap()),
                     to: Some(PSI_DISTRIBUTOR_CONTRACT.to_string()),
                 })
                 .unwrap(),
@@ -92,6 +110,64 @@ fn action_with_profit_buy_psi() {
     assert_eq!(response, expected_response);
 }
 
+#[test]
+fn action_with_profit_buy_psi_through_router() {
+    let sdk = Sdk::init();
+    let config = load_config(sdk.deps.as_ref().storage).unwrap();
+    let tax_info = TaxInfo {
+        rate: Decimal256::zero(),
+        cap: Uint256::zero(),
+    };
+
+    let buy_psi_amount = Uint256::from(2_000u64);
+    let action_with_profit = ActionWithProfit::BuyPsi {
+        amount: buy_psi_amount,
+    };
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: STABLE_DENOM.to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked(PSI_TOKEN),
+        },
+    }];
+    let psi_swap = RewardSwap::Router(RouterSwap {
+        router_contract: Addr::unchecked(ROUTER_CONTRACT),
+        operations: operations.clone(),
+        minimum_receive: Uint128::new(8_910),
+        return_amount: Uint128::new(9_000),
+    });
+    let response = action_with_profit
+        .to_response(&config, &tax_info, Some(&psi_swap))
+        .unwrap();
+
+    assert_eq!(
+        WasmMsg::Execute {
+            contract_addr: ROUTER_CONTRACT.to_string(),
+            msg: to_binary(&AstroportRouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(Uint128::new(8_910)),
+                to: Some(PSI_DISTRIBUTOR_CONTRACT.to_string()),
+            })
+            .unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: buy_psi_amount.into(),
+            }],
+        },
+        match &response.messages[0].msg {
+            CosmosMsg::Wasm(msg) => msg.clone(),
+            _ => panic!("unexpected message"),
+        }
+    );
+    assert_eq!(
+        Uint256::from(9_000u64),
+        action_with_profit
+            .distributed_rewards(&tax_info, Some(&psi_swap))
+            .psi_bought
+    );
+}
+
 #[test]
 fn action_with_profit_deposit_to_anc() {
     let sdk = Sdk::init();
@@ -105,7 +181,9 @@ fn action_with_profit_deposit_to_anc() {
     let action_with_profit = ActionWithProfit::DepositToAnc {
         amount: deposit_amount,
     };
-    let response = action_with_profit.to_response(&config, &tax_info).unwrap();
+    let response = action_with_profit
+        .to_response(&config, &tax_info, None)
+        .unwrap();
 
     let stable_coin_to_lending: Uint128 = tax_info.subtract_tax(deposit_amount).into();
     let expected_response = Response::new()
@@ -139,7 +217,17 @@ fn action_with_profit_split() {
         buy_psi: buy_psi_amount,
         deposit_to_anc: lending_amount,
     };
-    let response = action_with_profit.to_response(&config, &tax_info).unwrap();
+    let psi_swap = RewardSwap::Pair {
+        pair_contract: Addr::unchecked(PSI_STABLE_SWAP_CONTRACT),
+        limits: SwapLimits {
+            belief_price: Decimal::from_str("0.5").unwrap(),
+            max_spread: Decimal::percent(1),
+            return_amount: Uint128::new(9_000),
+        },
+    };
+    let response = action_with_profit
+        .to_response(&config, &tax_info, Some(&psi_swap))
+        .unwrap();
 
     let stable_coin_to_lending: Uint128 = tax_info.subtract_tax(lending_amount).into();
     let stable_coin_to_buy_psi: Uint128 = tax_info.subtract_tax(buy_psi_amount).into();
@@ -163,8 +251,8 @@ fn action_with_profit_split() {
                 contract_addr: PSI_STABLE_SWAP_CONTRACT.to_string(),
                 msg: to_binary(&AstroportExecuteMsg::Swap {
                     offer_asset: swap_asset,
-                    max_spread: None,
-                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    belief_price: Some(Decimal::from_str("0.5").unwrap()),
                     to: Some(PSI_DISTRIBUTOR_CONTRACT.to_string()),
                 })
                 .unwrap(),
@@ -188,4 +276,57 @@ fn action_with_profit_split() {
             ("deposit_to_anc", &stable_coin_to_lending.to_string()),
         ]);
     assert_eq!(response, expected_response);
+
+    assert_eq!(
+        DistributedRewards {
+            deposited_to_anc: stable_coin_to_lending.into(),
+            stables_to_psi: stable_coin_to_buy_psi.into(),
+            psi_bought: Uint256::from(9_000u64),
+            stables_to_compound: Uint256::zero(),
+        },
+        action_with_profit.distributed_rewards(&tax_info, Some(&psi_swap))
+    );
+}
+
+#[test]
+fn action_with_profit_buy_psi_without_swap_limits() {
+    let sdk = Sdk::init();
+    let config = load_config(sdk.deps.as_ref().storage).unwrap();
+    let tax_info = TaxInfo {
+        rate: Decimal256::zero(),
+        cap: Uint256::zero(),
+    };
+
+    let action_with_profit = ActionWithProfit::BuyPsi {
+        amount: Uint256::from(2_000u64),
+    };
+    let response = action_with_profit.to_response(&config, &tax_info, None);
+    assert!(response.is_err());
+}
+
+#[test]
+fn deposit_instead_of_buying_psi() {
+    assert_eq!(
+        ActionWithProfit::DepositToAnc {
+            amount: Uint256::from(2_000u64)
+        },
+        ActionWithProfit::BuyPsi {
+            amount: Uint256::from(2_000u64)
+        }
+        .deposit_instead_of_buying_psi()
+    );
+    assert_eq!(
+        ActionWithProfit::DepositToAnc {
+            amount: Uint256::from(3_000u64)
+        },
+        ActionWithProfit::Split {
+            buy_psi: Uint256::from(2_000u64),
+            deposit_to_anc: Uint256::from(1_000u64),
+        }
+        .deposit_instead_of_buying_psi()
+    );
+    assert_eq!(
+        ActionWithProfit::Nothing,
+        ActionWithProfit::Nothing.deposit_instead_of_buying_psi()
+    );
 }
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use crate::error::ContractError;
 use crate::state::load_config;
 use crate::tests::sdk::GOVERNANCE_CONTRACT;
 
@@ -7,7 +8,7 @@ use super::sdk::Sdk;
 use basset_vault::basset_vault::{ExecuteMsg, GovernanceMsg};
 use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Addr, StdError};
 
 #[test]
 fn fail_to_change_config_if_sender_is_not_governance() {
@@ -24,6 +25,10 @@ fn fail_to_change_config_if_sender_is_not_governance() {
             basset_vault_strategy_contract_addr: None,
             claiming_rewards_delay: None,
             over_loan_balance_value: None,
+            anc_stable_max_spread: None,
+            psi_stable_max_spread: None,
+            anc_stable_twap_oracle_addr: None,
+            psi_stable_twap_oracle_addr: None,
         },
     };
 
@@ -31,7 +36,7 @@ fn fail_to_change_config_if_sender_is_not_governance() {
     let info = mock_info("addr0010", &[]);
     let res = crate::contract::execute(sdk.deps.as_mut(), env, info, change_config_msg);
     assert!(res.is_err());
-    if let StdError::GenericErr { msg, .. } = res.err().unwrap() {
+    if let ContractError::Std(StdError::GenericErr { msg, .. }) = res.err().unwrap() {
         assert_eq!("unauthorized", msg);
     } else {
         panic!("wrong error");
@@ -51,6 +56,10 @@ fn success_to_change_config_if_sender_governance() {
     let new_basset_vault_strategy_contract_addr = "addr9988".to_string();
     let new_claiming_rewards_delay = 555;
     let new_over_loan_balance_value = Decimal256::from_str("1.88").unwrap();
+    let new_anc_stable_max_spread = Decimal256::from_str("0.02").unwrap();
+    let new_psi_stable_max_spread = Decimal256::from_str("0.03").unwrap();
+    let new_anc_stable_twap_oracle_addr = "addr9987".to_string();
+    let new_psi_stable_twap_oracle_addr = "addr9986".to_string();
 
     let change_config_msg = ExecuteMsg::Governance {
         governance_msg: GovernanceMsg::UpdateConfig {
@@ -67,6 +76,10 @@ fn success_to_change_config_if_sender_governance() {
             ),
             claiming_rewards_delay: Some(new_claiming_rewards_delay),
             over_loan_balance_value: Some(new_over_loan_balance_value),
+            anc_stable_max_spread: Some(new_anc_stable_max_spread),
+            psi_stable_max_spread: Some(new_psi_stable_max_spread),
+            anc_stable_twap_oracle_addr: Some(new_anc_stable_twap_oracle_addr.clone()),
+            psi_stable_twap_oracle_addr: Some(new_psi_stable_twap_oracle_addr.clone()),
         },
     };
 
@@ -102,4 +115,42 @@ fn success_to_change_config_if_sender_governance() {
     );
     assert_eq!(new_claiming_rewards_delay, config.claiming_rewards_delay);
     assert_eq!(new_over_loan_balance_value, config.over_loan_balance_value);
+    assert_eq!(new_anc_stable_max_spread, config.anc_stable_max_spread);
+    assert_eq!(new_psi_stable_max_spread, config.psi_stable_max_spread);
+    assert_eq!(
+        Some(Addr::unchecked(new_anc_stable_twap_oracle_addr)),
+        config.anc_stable_twap_oracle
+    );
+    assert_eq!(
+        Some(Addr::unchecked(new_psi_stable_twap_oracle_addr)),
+        config.psi_stable_twap_oracle
+    );
+}
+
+#[test]
+fn fail_to_set_max_spread_greater_than_one() {
+    let mut sdk = Sdk::init();
+
+    let change_config_msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateConfig {
+            psi_distributor_addr: None,
+            anchor_overseer_contract_addr: None,
+            anchor_market_contract_addr: None,
+            anchor_custody_basset_contract_addr: None,
+            anc_stable_swap_contract_addr: None,
+            psi_stable_swap_contract_addr: None,
+            basset_vault_strategy_contract_addr: None,
+            claiming_rewards_delay: None,
+            over_loan_balance_value: None,
+            anc_stable_max_spread: Some(Decimal256::from_str("1.5").unwrap()),
+            psi_stable_max_spread: None,
+            anc_stable_twap_oracle_addr: None,
+            psi_stable_twap_oracle_addr: None,
+        },
+    };
+
+    let env = mock_env();
+    let info = mock_info(GOVERNANCE_CONTRACT, &[]);
+    let res = crate::contract::execute(sdk.deps.as_mut(), env, info, change_config_msg);
+    assert_eq!(ContractError::InappropriateValue, res.err().unwrap());
 }
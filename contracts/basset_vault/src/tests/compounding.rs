@@ -0,0 +1,269 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::{
+    ANCHOR_CUSTODY_BASSET_CONTRACT, ANCHOR_OVERSEER_CONTRACT, BASSET_STABLE_SWAP_CONTRACT,
+    BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, OVER_LOAN_BALANCE_VALUE, STABLE_DENOM,
+};
+use basset_vault::astroport_pair::{ExecuteMsg as AstroportExecuteMsg, SimulationResponse};
+use basset_vault::basset_vault::{
+    CompoundingConfigResponse, ExecuteMsg, GovernanceMsg, QueryMsg, StatsResponse, YourselfMsg,
+};
+use basset_vault::querier::{AnchorCustodyCw20Msg, AnchorOverseerMsg};
+use basset_vault::terraswap::{Asset, AssetInfo};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{attr, to_binary, Coin, CosmosMsg, Decimal, Response, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use std::str::FromStr;
+
+fn update_compounding_config(
+    sdk: &mut Sdk,
+    sender: &str,
+    compound_share: Decimal256,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateCompoundingConfig {
+            basset_stable_swap_contract_addr: Some(BASSET_STABLE_SWAP_CONTRACT.to_string()),
+            compound_share: Some(compound_share),
+            max_spread: Some(Decimal256::percent(1)),
+        },
+    };
+    let info = mock_info(sender, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+fn lock_compounded_basset(sdk: &mut Sdk) -> Result<Response, ContractError> {
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Yourself {
+            yourself_msg: YourselfMsg::LockCompoundedBasset {},
+        },
+    )
+}
+
+/// Sells ANC, so that 6_000_000 stables are left to distribute
+/// and bAsset swap returns 100_000
+fn sell_anc_with_profit(sdk: &mut Sdk) {
+    //aUST covers loan, so all ANC selling profit goes to Psi
+    let stable_coin_balance = Uint128::new(5_000_000);
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap());
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(1_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(1_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(1_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_basset_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(100_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.send_swap_anc().unwrap();
+
+    sdk.set_stable_balance(Uint128::new(6_000_000));
+}
+
+#[test]
+fn fail_to_update_compounding_config_if_sender_is_not_governance() {
+    let mut sdk = Sdk::init();
+
+    let res = update_compounding_config(&mut sdk, "addr0001", Decimal256::percent(50));
+    assert!(res.is_err());
+}
+
+#[test]
+fn fail_to_set_compound_share_above_one() {
+    let mut sdk = Sdk::init();
+
+    let res = update_compounding_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(101));
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
+
+#[test]
+fn compounding_is_disabled_by_default() {
+    let mut sdk = Sdk::init();
+
+    let compounding_config: CompoundingConfigResponse =
+        sdk.query(QueryMsg::CompoundingConfig {}).unwrap();
+    assert!(!compounding_config.enabled);
+
+    update_compounding_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(50)).unwrap();
+    let compounding_config: CompoundingConfigResponse =
+        sdk.query(QueryMsg::CompoundingConfig {}).unwrap();
+    assert_eq!(
+        CompoundingConfigResponse {
+            enabled: true,
+            basset_stable_swap_contract_addr: Some(BASSET_STABLE_SWAP_CONTRACT.to_string()),
+            compound_share: Decimal256::percent(50),
+            max_spread: Decimal256::percent(1),
+        },
+        compounding_config
+    );
+}
+
+#[test]
+fn part_of_psi_rewards_buys_basset() {
+    let mut sdk = Sdk::init();
+    update_compounding_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(50)).unwrap();
+
+    sell_anc_with_profit(&mut sdk);
+    sdk.set_stable_balance(Uint128::new(6_000_000));
+    let response = sdk.send_distribute_rewards().unwrap();
+
+    let expected_basset_swap = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: BASSET_STABLE_SWAP_CONTRACT.to_string(),
+        msg: to_binary(&AstroportExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: STABLE_DENOM.to_string(),
+                },
+                amount: Uint128::new(3_000_000),
+            },
+            belief_price: Some(Decimal::from_str("30").unwrap()),
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+        })
+        .unwrap(),
+        funds: vec![Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: Uint128::new(3_000_000),
+        }],
+    });
+    let expected_lock = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Yourself {
+            yourself_msg: YourselfMsg::LockCompoundedBasset {},
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let messages: Vec<CosmosMsg> = response.messages.into_iter().map(|m| m.msg).collect();
+    //psi swap, psi distribution, bAsset swap, locking bAsset
+    assert_eq!(4, messages.len());
+    assert_eq!(expected_basset_swap, messages[2]);
+    assert_eq!(expected_lock, messages[3]);
+    assert!(response.attributes.contains(&attr("bying_psi", "3000000")));
+    assert!(response.attributes.contains(&attr("compound", "3000000")));
+
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(Uint256::from(3_000_000u64), stats.stables_to_psi);
+    assert_eq!(Uint256::from(3_000_000u64), stats.stables_to_compound);
+}
+
+#[test]
+fn whole_psi_rewards_are_compounded() {
+    let mut sdk = Sdk::init();
+    update_compounding_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::one()).unwrap();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap());
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(1_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(1_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_basset_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(100_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.send_swap_anc().unwrap();
+
+    sdk.set_stable_balance(Uint128::new(6_000_000));
+    let response = sdk.send_distribute_rewards().unwrap();
+
+    //bAsset swap and locking bAsset only
+    assert_eq!(2, response.messages.len());
+    assert_eq!(
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("compound", "6000000"),
+        ],
+        response.attributes
+    );
+}
+
+#[test]
+fn lock_compounded_basset_without_minting_nasset() {
+    let mut sdk = Sdk::init();
+
+    let basset_amount = Uint256::from(100_000u64);
+    sdk.set_basset_balance(basset_amount);
+    let response = lock_compounded_basset(&mut sdk).unwrap();
+
+    let messages: Vec<CosmosMsg> = response.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: BASSET_TOKEN_ADDR.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: ANCHOR_CUSTODY_BASSET_CONTRACT.to_string(),
+                    amount: basset_amount.into(),
+                    msg: to_binary(&AnchorCustodyCw20Msg::DepositCollateral {}).unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ANCHOR_OVERSEER_CONTRACT.to_string(),
+                msg: to_binary(&AnchorOverseerMsg::LockCollateral {
+                    collaterals: vec![(BASSET_TOKEN_ADDR.to_string(), basset_amount)],
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ],
+        messages
+    );
+
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(basset_amount, stats.basset_compounded);
+}
+
+#[test]
+fn skip_locking_zero_basset() {
+    let mut sdk = Sdk::init();
+
+    sdk.set_basset_balance(Uint256::zero());
+    let response = lock_compounded_basset(&mut sdk).unwrap();
+    assert!(response.messages.is_empty());
+}
+
+#[test]
+fn donated_basset_is_not_locked() {
+    let mut sdk = Sdk::init();
+    update_compounding_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(50)).unwrap();
+
+    //someone sent bAsset to vault before rewards distribution
+    let donated_basset = Uint256::from(40_000u64);
+    sdk.set_basset_balance(donated_basset);
+    sell_anc_with_profit(&mut sdk);
+    sdk.send_distribute_rewards().unwrap();
+
+    let basset_bought = Uint256::from(100_000u64);
+    sdk.set_basset_balance(donated_basset + basset_bought);
+    let response = lock_compounded_basset(&mut sdk).unwrap();
+    assert!(response
+        .attributes
+        .contains(&attr("basset_amount", basset_bought.to_string())));
+
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(basset_bought, stats.basset_compounded);
+}
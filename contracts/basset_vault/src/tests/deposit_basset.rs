@@ -1,10 +1,11 @@
 use super::sdk::Sdk;
+use crate::error::ContractError;
 use crate::tests::sdk::{
     ANCHOR_CUSTODY_BASSET_CONTRACT, ANCHOR_OVERSEER_CONTRACT, BASSET_TOKEN_ADDR, NASSET_TOKEN_ADDR,
 };
 use basset_vault::querier::AnchorCustodyCw20Msg;
 use cosmwasm_bignumber::Uint256;
-use cosmwasm_std::{testing::MOCK_CONTRACT_ADDR, CosmosMsg};
+use cosmwasm_std::{testing::mock_env, testing::MOCK_CONTRACT_ADDR, CosmosMsg};
 use cosmwasm_std::{to_binary, StdError, SubMsg, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 
@@ -54,7 +55,16 @@ fn deposit_basset() {
                     contract_addr: NASSET_TOKEN_ADDR.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Mint {
                         recipient: user_1_address.clone(),
-                        amount: deposit_1_amount.into(), //first depositer have same amount
+                        amount: Uint128::new(1_999_999_000), //first depositer have same amount minus locked liquidity
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })),
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: NASSET_TOKEN_ADDR.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Mint {
+                        recipient: MOCK_CONTRACT_ADDR.to_string(),
+                        amount: Uint128::new(1_000), //minimum liquidity locked forever
                     })
                     .unwrap(),
                     funds: vec![],
@@ -140,7 +150,7 @@ fn do_not_accept_deposit_if_nluna_supply_is_not_zero_but_bluna_in_custody_is_zer
 
     let response = sdk.user_deposit(&user_address, deposit_amount.into());
     assert!(response.is_err());
-    if let StdError::GenericErr { msg, .. } = response.err().unwrap() {
+    if let ContractError::Std(StdError::GenericErr { msg, .. }) = response.err().unwrap() {
         assert_eq!(
             "bAsset balance is zero, but nAsset supply is not! Freeze contract.",
             msg
@@ -175,7 +185,7 @@ fn deposit_basset_after_someone_transfer_some_bassets_directly_to_contract() {
                     contract_addr: BASSET_TOKEN_ADDR.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Send {
                         contract: ANCHOR_CUSTODY_BASSET_CONTRACT.to_string(),
-                        amount: total_basset_amount.into(),
+                        amount: deposit_amount.into(),
                         msg: to_binary(&AnchorCustodyCw20Msg::DepositCollateral {}).unwrap()
                     })
                     .unwrap(),
@@ -184,7 +194,7 @@ fn deposit_basset_after_someone_transfer_some_bassets_directly_to_contract() {
                 SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: ANCHOR_OVERSEER_CONTRACT.to_string(),
                     msg: to_binary(&AnchorOverseerMsg::LockCollateral {
-                        collaterals: vec![(BASSET_TOKEN_ADDR.to_string(), total_basset_amount)],
+                        collaterals: vec![(BASSET_TOKEN_ADDR.to_string(), deposit_amount)],
                     })
                     .unwrap(),
                     funds: vec![],
@@ -193,7 +203,16 @@ fn deposit_basset_after_someone_transfer_some_bassets_directly_to_contract() {
                     contract_addr: NASSET_TOKEN_ADDR.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Mint {
                         recipient: user_address.clone(),
-                        amount: total_basset_amount.into(), //first depositer have same amount
+                        amount: Uint128::new(1_999_999_000), //directly transfered bAsset is ignored
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })),
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: NASSET_TOKEN_ADDR.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Mint {
+                        recipient: MOCK_CONTRACT_ADDR.to_string(),
+                        amount: Uint128::new(1_000), //minimum liquidity locked forever
                     })
                     .unwrap(),
                     funds: vec![],
@@ -210,3 +229,119 @@ fn deposit_basset_after_someone_transfer_some_bassets_directly_to_contract() {
         );
     }
 }
+
+#[test]
+fn first_deposit_not_bigger_than_minimum_liquidity_is_refused() {
+    let mut sdk = Sdk::init();
+
+    let user_address = "addr9999".to_string();
+    let deposit_amount: Uint256 = 1_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    sdk.set_basset_balance(deposit_amount);
+
+    let response = sdk.user_deposit(&user_address, deposit_amount.into());
+    assert_eq!(response.err().unwrap(), ContractError::ZeroNAssetToMint);
+}
+
+#[test]
+fn deposit_fails_if_minted_nasset_less_than_min_nasset_out() {
+    let mut sdk = Sdk::init();
+
+    //first farmer come
+    let user_1_address = "addr9999".to_string();
+    let deposit_1_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    sdk.set_basset_balance(deposit_1_amount);
+    sdk.user_deposit(&user_1_address, deposit_1_amount.into())
+        .unwrap();
+
+    //bAsset in custody doubled, so one nAsset costs two bAssets now
+    let user_2_address = "addr6666".to_string();
+    let deposit_2_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(deposit_1_amount);
+    sdk.set_collateral_balance(deposit_1_amount + deposit_1_amount);
+    sdk.set_basset_balance(deposit_2_amount);
+
+    let response = sdk.user_deposit_guarded(
+        &user_2_address,
+        deposit_2_amount.into(),
+        Some(deposit_2_amount),
+        None,
+    );
+    assert_eq!(
+        response.err().unwrap(),
+        ContractError::TooLittleNAssetOut {
+            min_nasset_out: deposit_2_amount,
+            nasset_to_mint: Uint256::from(1_000_000_000u64),
+        }
+    );
+
+    //with acceptable min_nasset_out everything is fine
+    sdk.user_deposit_guarded(
+        &user_2_address,
+        deposit_2_amount.into(),
+        Some(Uint256::from(1_000_000_000u64)),
+        None,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deposit_fails_after_deadline() {
+    let mut sdk = Sdk::init();
+
+    let user_address = "addr9999".to_string();
+    let deposit_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    sdk.set_basset_balance(deposit_amount);
+
+    let current_time = mock_env().block.time.seconds();
+    let response = sdk.user_deposit_guarded(
+        &user_address,
+        deposit_amount.into(),
+        None,
+        Some(current_time - 1),
+    );
+    assert_eq!(
+        response.err().unwrap(),
+        ContractError::DeadlineExceeded {
+            deadline: current_time - 1,
+            current_time,
+        }
+    );
+
+    sdk.user_deposit_guarded(
+        &user_address,
+        deposit_amount.into(),
+        None,
+        Some(current_time),
+    )
+    .unwrap();
+}
+
+#[test]
+fn deposit_basset_on_behalf_of_recipient() {
+    let mut sdk = Sdk::init();
+
+    let router_address = "addr7777".to_string();
+    let user_address = "addr9999".to_string();
+    let deposit_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(deposit_amount);
+    sdk.set_collateral_balance(deposit_amount);
+    sdk.set_basset_balance(deposit_amount);
+
+    let response = sdk
+        .user_deposit_to(&router_address, deposit_amount.into(), &user_address)
+        .unwrap();
+    assert!(response
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: NASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: user_address,
+                amount: deposit_amount.into(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+}
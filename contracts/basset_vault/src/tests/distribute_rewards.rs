@@ -3,13 +3,15 @@ use crate::tests::sdk::{
     ANCHOR_MARKET_CONTRACT, ANCHOR_TOKEN, ANC_STABLE_SWAP_CONTRACT, OVER_LOAN_BALANCE_VALUE,
     PSI_DISTRIBUTOR_CONTRACT, PSI_STABLE_SWAP_CONTRACT, STABLE_DENOM,
 };
-use crate::MIN_ANC_REWARDS_TO_CLAIM;
+use crate::{DEFAULT_SWAP_MAX_SPREAD_PERCENT, MIN_ANC_REWARDS_TO_CLAIM};
 use basset_vault::basset_vault::{
     ExecuteMsg as BassetFarmerExecuteMsg, YourselfMsg as BassetFarmerYourselfMsg,
 };
 use basset_vault::terraswap::{Asset, AssetInfo};
 use basset_vault::{
-    astroport_pair::{Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg},
+    astroport_pair::{
+        Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg, SimulationResponse,
+    },
     psi_distributor::{
         AnyoneMsg as PsiDistributorAnyoneMsg, ExecuteMsg as PsiDistributorExecuteMsg,
     },
@@ -17,7 +19,7 @@ use basset_vault::{
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cosmwasm_std::{to_binary, Coin, Response, SubMsg, WasmMsg};
+use cosmwasm_std::{attr, to_binary, Coin, Decimal, Response, SubMsg, WasmMsg};
 use cosmwasm_std::{CosmosMsg, Uint128};
 use cw20::Cw20ExecuteMsg;
 use std::str::FromStr;
@@ -67,6 +69,11 @@ fn honest_work() {
     {
         sdk.set_stable_balance(stable_coin_balance);
         sdk.set_anc_balance(anc_balance);
+        sdk.set_anc_swap_simulation(SimulationResponse {
+            return_amount: Uint128::new(5_970),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::new(30),
+        });
         let swap_anc_response = sdk.send_swap_anc().unwrap();
         assert_eq!(
             swap_anc_response.messages,
@@ -77,8 +84,8 @@ fn honest_work() {
                         amount: anc_balance.into(),
                         contract: ANC_STABLE_SWAP_CONTRACT.to_string(),
                         msg: to_binary(&AstroportCw20HookMsg::Swap {
-                            belief_price: None,
-                            max_spread: None,
+                            belief_price: Some(Decimal::from_str("0.5").unwrap()),
+                            max_spread: Some(Decimal::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT)),
                             to: None,
                         })
                         .unwrap(),
@@ -114,6 +121,11 @@ fn honest_work() {
             + stable_coin_balance.into()
             + stable_coin_balance_from_selling_anc.into();
         let expected_rewards = total_ust_value - aim_stable_balance;
+        sdk.set_psi_swap_simulation(SimulationResponse {
+            return_amount: (expected_rewards * Uint256::from(2u64)).into(),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        });
         let distribute_rewards_response = sdk.send_distribute_rewards().unwrap();
         let swap_asset = Asset {
             info: AssetInfo::NativeToken {
@@ -128,8 +140,8 @@ fn honest_work() {
                     contract_addr: PSI_STABLE_SWAP_CONTRACT.to_string(),
                     msg: to_binary(&AstroportExecuteMsg::Swap {
                         offer_asset: swap_asset,
-                        max_spread: None,
-                        belief_price: None,
+                        max_spread: Some(Decimal::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT)),
+                        belief_price: Some(Decimal::from_str("0.5").unwrap()),
                         to: Some(PSI_DISTRIBUTOR_CONTRACT.to_string()),
                     })
                     .unwrap(),
@@ -150,3 +162,161 @@ fn honest_work() {
         );
     }
 }
+
+#[test]
+fn skip_anc_swap_on_high_price_impact() {
+    let mut sdk = Sdk::init();
+
+    sdk.set_stable_balance(Uint128::new(5_000_000));
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    //2% price impact with default 1% max spread
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(5_880),
+        spread_amount: Uint128::new(120),
+        commission_amount: Uint128::zero(),
+    });
+
+    let swap_anc_response = sdk.send_swap_anc().unwrap();
+    assert!(swap_anc_response.messages.is_empty());
+    assert_eq!(
+        swap_anc_response.attributes,
+        vec![
+            attr("action", "swap_anc"),
+            attr("skipped", "price_impact_too_high"),
+        ]
+    );
+}
+
+#[test]
+fn skip_anc_swap_if_price_is_far_from_twap() {
+    let mut sdk = Sdk::init();
+    sdk.set_twap_oracles();
+
+    sdk.set_stable_balance(Uint128::new(5_000_000));
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    //no price impact, but pool price was moved 3% down from TWAP one
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(5_820),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_anc_twap_return_amount(Uint256::from(6_000u64));
+
+    let swap_anc_response = sdk.send_swap_anc().unwrap();
+    assert!(swap_anc_response.messages.is_empty());
+    assert_eq!(
+        swap_anc_response.attributes,
+        vec![
+            attr("action", "swap_anc"),
+            attr("skipped", "price_impact_too_high"),
+        ]
+    );
+
+    //pool price is back within max spread
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(5_960),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    let swap_anc_response = sdk.send_swap_anc().unwrap();
+    assert_eq!(2, swap_anc_response.messages.len());
+}
+
+#[test]
+fn deposit_to_anchor_on_high_psi_price_impact() {
+    let mut sdk = Sdk::init();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    let stable_coin_balance_from_selling_anc = Uint128::new(1_000_000);
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(6_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.send_swap_anc().unwrap();
+
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_stable_balance(stable_coin_balance + stable_coin_balance_from_selling_anc);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal256::zero().into(), 0);
+
+    let aim_stable_balance: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value;
+    let total_ust_value: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value
+        + stable_coin_balance.into()
+        + stable_coin_balance_from_selling_anc.into();
+    let expected_rewards = total_ust_value - aim_stable_balance;
+    //10% price impact with default 1% max spread
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(900),
+        spread_amount: Uint128::new(100),
+        commission_amount: Uint128::zero(),
+    });
+
+    let distribute_rewards_response = sdk.send_distribute_rewards().unwrap();
+    assert_eq!(
+        distribute_rewards_response.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+            msg: to_binary(&AnchorMarketMsg::DepositStable {}).unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: expected_rewards.into(),
+            }],
+        }))]
+    );
+}
+
+#[test]
+fn deposit_to_anchor_if_psi_price_is_far_from_twap() {
+    let mut sdk = Sdk::init();
+    sdk.set_twap_oracles();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    let stable_coin_balance_from_selling_anc = Uint128::new(1_000_000);
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(6_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_anc_twap_return_amount(Uint256::from(6_000u64));
+    sdk.send_swap_anc().unwrap();
+
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_stable_balance(stable_coin_balance + stable_coin_balance_from_selling_anc);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal256::zero().into(), 0);
+
+    let aim_stable_balance: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value;
+    let total_ust_value: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value
+        + stable_coin_balance.into()
+        + stable_coin_balance_from_selling_anc.into();
+    let expected_rewards = total_ust_value - aim_stable_balance;
+    //no price impact, but PSI is 10% more expensive than by TWAP
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(1_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_psi_twap_return_amount(Uint256::from(1_100u64));
+
+    let distribute_rewards_response = sdk.send_distribute_rewards().unwrap();
+    assert_eq!(
+        distribute_rewards_response.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+            msg: to_binary(&AnchorMarketMsg::DepositStable {}).unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: expected_rewards.into(),
+            }],
+        }))]
+    );
+}
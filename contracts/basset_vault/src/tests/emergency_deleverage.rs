@@ -0,0 +1,231 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::{
+    ANCHOR_CUSTODY_BASSET_CONTRACT, ANCHOR_MARKET_CONTRACT, ANCHOR_OVERSEER_CONTRACT,
+    BASSET_STABLE_SWAP_CONTRACT, BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, STABLE_DENOM,
+};
+use basset_vault::astroport_pair::{Cw20HookMsg as AstroportCw20HookMsg, SimulationResponse};
+use basset_vault::basset_vault::{
+    EmergencyDeleverageConfigResponse, ExecuteMsg, GovernanceMsg, QueryMsg, YourselfMsg,
+};
+use basset_vault::basset_vault_strategy::{
+    ConfigResponse as StrategyConfigResponse, StalePricePolicy,
+};
+use basset_vault::querier::{AnchorCustodyMsg, AnchorMarketMsg, AnchorOverseerMsg};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{attr, to_binary, Coin, Decimal, Response, StdError, SubMsg, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use std::str::FromStr;
+
+const ORACLE_CONTRACT: &str = "addr0020";
+
+fn set_strategy_and_price(sdk: &mut Sdk) {
+    sdk.set_strategy_config(StrategyConfigResponse {
+        governance_contract: GOVERNANCE_CONTRACT.to_string(),
+        oracle_contract: ORACLE_CONTRACT.to_string(),
+        basset_token: BASSET_TOKEN_ADDR.to_string(),
+        stable_denom: STABLE_DENOM.to_string(),
+        borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+        borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+        borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+        basset_max_ltv: Decimal256::from_str("0.6").unwrap(),
+        buffer_part: Decimal256::from_str("0.018").unwrap(),
+        price_timeframe: 60,
+        stale_price_policy: StalePricePolicy::default(),
+    });
+    sdk.set_strategy_fresh_price(Decimal256::from_str("10").unwrap());
+}
+
+fn update_deleverage_config(
+    sdk: &mut Sdk,
+    sender: &str,
+    trigger_ltv: Decimal256,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateEmergencyDeleverageConfig {
+            basset_stable_swap_contract_addr: Some(BASSET_STABLE_SWAP_CONTRACT.to_string()),
+            trigger_ltv: Some(trigger_ltv),
+            max_basset_amount: Some(Uint256::from(50u64)),
+            max_spread: Some(Decimal256::percent(1)),
+        },
+    };
+    let info = mock_info(sender, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+//collateral value is 1_000, LTV is 0.58, emergency trigger is 0.55
+fn init_risky_position() -> Sdk {
+    let mut sdk = Sdk::init();
+    set_strategy_and_price(&mut sdk);
+    update_deleverage_config(
+        &mut sdk,
+        GOVERNANCE_CONTRACT,
+        Decimal256::from_str("0.55").unwrap(),
+    )
+    .unwrap();
+
+    sdk.set_collateral_balance(Uint256::from(100u64));
+    sdk.set_loan(Uint256::from(580u64));
+    sdk.set_stable_balance(Uint128::new(10));
+    sdk.set_aterra_balance(Uint256::zero());
+    sdk.set_aterra_exchange_rate(Decimal256::one());
+    sdk.set_basset_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(30),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    //no tax
+    sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    sdk
+}
+
+#[test]
+fn emergency_deleverage_disabled_by_default() {
+    let mut sdk = Sdk::init();
+
+    let config: EmergencyDeleverageConfigResponse =
+        sdk.query(QueryMsg::EmergencyDeleverageConfig {}).unwrap();
+    assert!(!config.enabled);
+
+    let res = sdk.emergency_deleverage();
+    assert_eq!(Err(ContractError::EmergencyDeleverageDisabled), res);
+}
+
+#[test]
+fn update_emergency_deleverage_config() {
+    let mut sdk = Sdk::init();
+    set_strategy_and_price(&mut sdk);
+
+    let res = update_deleverage_config(&mut sdk, "addr0001", Decimal256::percent(55));
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err("unauthorized"))),
+        res
+    );
+
+    //trigger should be lower than Anchor max LTV
+    let res = update_deleverage_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(60));
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    update_deleverage_config(&mut sdk, GOVERNANCE_CONTRACT, Decimal256::percent(55)).unwrap();
+    let config: EmergencyDeleverageConfigResponse =
+        sdk.query(QueryMsg::EmergencyDeleverageConfig {}).unwrap();
+    assert_eq!(
+        EmergencyDeleverageConfigResponse {
+            enabled: true,
+            basset_stable_swap_contract_addr: Some(BASSET_STABLE_SWAP_CONTRACT.to_string()),
+            trigger_ltv: Decimal256::percent(55),
+            max_basset_amount: Uint256::from(50u64),
+            max_spread: Decimal256::percent(1),
+        },
+        config
+    );
+}
+
+#[test]
+fn sell_collateral_and_repay_loan() {
+    let mut sdk = init_risky_position();
+
+    //LTV 0.58 is between trigger 0.55 and Anchor max LTV 0.6
+    //(580 - 550 - 10) / (10 * (1 - 0.55)) = 4.44, but Anchor lets unlock only
+    //(1_000 * 0.6 - 580) / (10 * 0.6) = 3.33 while loan is not repaid
+    let basset_to_sell = Uint256::from(3u64);
+    let borrow_limit = (Uint256::from(100u64) - basset_to_sell)
+        * Decimal256::from_str("10").unwrap()
+        * Decimal256::from_str("0.6").unwrap();
+    assert!(Uint256::from(580u64) <= borrow_limit);
+    let response = sdk.emergency_deleverage().unwrap();
+    assert_eq!(
+        response.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: ANCHOR_OVERSEER_CONTRACT.to_string(),
+                msg: to_binary(&AnchorOverseerMsg::UnlockCollateral {
+                    collaterals: vec![(BASSET_TOKEN_ADDR.to_string(), basset_to_sell)],
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: ANCHOR_CUSTODY_BASSET_CONTRACT.to_string(),
+                msg: to_binary(&AnchorCustodyMsg::WithdrawCollateral {
+                    amount: Some(basset_to_sell),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: BASSET_TOKEN_ADDR.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: BASSET_STABLE_SWAP_CONTRACT.to_string(),
+                    amount: basset_to_sell.into(),
+                    msg: to_binary(&AstroportCw20HookMsg::Swap {
+                        belief_price: Some(Decimal::from_str("0.1").unwrap()),
+                        max_spread: Some(Decimal::percent(1)),
+                        to: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Yourself {
+                    yourself_msg: YourselfMsg::RepayAfterDeleverage {},
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+    assert!(response.attributes.contains(&attr("basset_sold", "3")));
+
+    //bAsset swapped to 30 UST
+    sdk.set_stable_balance(Uint128::new(40));
+    let response = sdk.send_repay_after_deleverage().unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+            msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: Uint128::new(30),
+            }],
+        })]
+    );
+}
+
+#[test]
+fn emergency_deleverage_only_if_aterra_can_not_be_redeemed() {
+    let mut sdk = init_risky_position();
+    sdk.set_aterra_balance(Uint256::from(30u64));
+
+    //loan can be repaid by redeeming aterra on Rebalance
+    let res = sdk.emergency_deleverage();
+    assert_eq!(Err(ContractError::EmergencyDeleverageNotNeeded), res);
+
+    //Anchor has no liquidity to redeem aterra
+    sdk.set_market_liquidity(Uint128::zero(), Decimal256::zero());
+    let response = sdk.emergency_deleverage().unwrap();
+    assert!(response.attributes.contains(&attr("basset_sold", "3")));
+}
+
+#[test]
+fn emergency_deleverage_not_needed_below_trigger() {
+    let mut sdk = init_risky_position();
+    sdk.set_loan(Uint256::from(500u64));
+
+    let res = sdk.emergency_deleverage();
+    assert_eq!(Err(ContractError::EmergencyDeleverageNotNeeded), res);
+}
+
+#[test]
+fn emergency_deleverage_refused_on_stale_price() {
+    let mut sdk = init_risky_position();
+    sdk.set_strategy_price_is_stale();
+
+    let res = sdk.emergency_deleverage();
+    assert!(res.unwrap_err().to_string().contains("price is stale"));
+}
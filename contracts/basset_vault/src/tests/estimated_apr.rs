@@ -0,0 +1,96 @@
+use super::sdk::Sdk;
+use crate::tests::sdk::{BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, STABLE_DENOM};
+use basset_vault::anchor::oracle::PriceResponse;
+use basset_vault::astroport_pair::SimulationResponse;
+use basset_vault::basset_vault::{EstimatedAprResponse, QueryMsg};
+use basset_vault::basset_vault_strategy::{
+    ConfigResponse as StrategyConfigResponse, StalePricePolicy,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Uint128;
+use std::str::FromStr;
+
+const ORACLE_CONTRACT: &str = "addr0020";
+
+fn set_market(sdk: &mut Sdk) {
+    sdk.set_strategy_config(StrategyConfigResponse {
+        governance_contract: GOVERNANCE_CONTRACT.to_string(),
+        oracle_contract: ORACLE_CONTRACT.to_string(),
+        basset_token: BASSET_TOKEN_ADDR.to_string(),
+        stable_denom: STABLE_DENOM.to_string(),
+        borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+        borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+        borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+        basset_max_ltv: Decimal256::from_str("0.6").unwrap(),
+        buffer_part: Decimal256::from_str("0.018").unwrap(),
+        price_timeframe: 60,
+        stale_price_policy: StalePricePolicy::default(),
+    });
+    let current_time = mock_env().block.time.seconds();
+    sdk.set_oracle_price(PriceResponse {
+        rate: Decimal256::from_str("10").unwrap(),
+        last_updated_base: current_time,
+        last_updated_quote: current_time,
+    });
+    sdk.set_anchor_rates(
+        Decimal256::from_str("0.00000005").unwrap(),
+        Decimal256::from_str("0.00000004").unwrap(),
+    );
+    sdk.set_anc_emission(
+        Decimal256::from_str("1000000000").unwrap(),
+        Decimal256::from_str("20").unwrap(),
+    );
+    //1 ANC = 2 UST
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(2_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    //1 Psi = 0.5 UST
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(500_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+}
+
+#[test]
+fn estimated_apr_by_current_ltv() {
+    let mut sdk = Sdk::init();
+    set_market(&mut sdk);
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+    sdk.set_loan(Uint256::from(5_000u64));
+
+    let estimated_apr: EstimatedAprResponse = sdk.query(QueryMsg::EstimatedApr {}).unwrap();
+    assert_eq!(
+        EstimatedAprResponse {
+            //5_000 / (1_000 * 10)
+            ltv: Decimal256::from_str("0.5").unwrap(),
+            deposit_apr: Decimal256::from_str("0.1862724").unwrap(),
+            borrow_apr: Decimal256::from_str("0.2328405").unwrap(),
+            distribution_apr: Decimal256::from_str("0.1862724").unwrap(),
+            anc_price: Decimal256::from_str("2").unwrap(),
+            psi_price: Decimal256::from_str("0.5").unwrap(),
+            deposit_yield: Decimal256::from_str("0.0931362").unwrap(),
+            distribution_yield: Decimal256::from_str("0.0931362").unwrap(),
+            borrow_cost: Decimal256::from_str("0.11642025").unwrap(),
+            //protocol fee is (0.8 - 0.6) * 0.5
+            nasset_holder_share: Decimal256::from_str("0.9").unwrap(),
+            apr: Decimal256::from_str("0.062866935").unwrap(),
+            psi_per_collateral_value: Decimal256::from_str("0.12573387").unwrap(),
+        },
+        estimated_apr
+    );
+}
+
+#[test]
+fn estimated_apr_uses_aim_ltv_without_collateral() {
+    let mut sdk = Sdk::init();
+    set_market(&mut sdk);
+    sdk.set_collateral_balance(Uint256::zero());
+
+    let estimated_apr: EstimatedAprResponse = sdk.query(QueryMsg::EstimatedApr {}).unwrap();
+    //0.8 * 0.6
+    assert_eq!(Decimal256::from_str("0.48").unwrap(), estimated_apr.ltv);
+}
@@ -12,11 +12,15 @@ use crate::{
         NASSET_TOKEN_REWARDS_CODE_ID, OVER_LOAN_BALANCE_VALUE, PSI_DISTRIBUTOR_CODE_ID,
         PSI_DISTRIBUTOR_CONTRACT, PSI_STABLE_SWAP_CONTRACT, PSI_TOKEN, STABLE_DENOM, TAX_RATE,
     },
+    DEFAULT_SWAP_MAX_SPREAD_PERCENT,
 };
 
 use super::sdk::Sdk;
+use basset_vault::lending_market::LendingMarketKind;
+use basset_vault::yield_venue::YieldVenueKind;
 use cosmwasm_bignumber::Decimal256;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{to_vec, Addr, Storage};
+use serde::Serialize;
 use std::str::FromStr;
 
 #[test]
@@ -43,6 +47,12 @@ fn proper_initialization() {
             over_loan_balance_value: Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap(),
             nasset_token: Addr::unchecked(NASSET_TOKEN_ADDR),
             psi_distributor: Addr::unchecked(PSI_DISTRIBUTOR_CONTRACT),
+            anc_stable_max_spread: Decimal256::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT),
+            psi_stable_max_spread: Decimal256::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT),
+            anc_stable_twap_oracle: None,
+            psi_stable_twap_oracle: None,
+            lending_market: LendingMarketKind::Anchor {},
+            yield_venue: YieldVenueKind::AnchorEarn {},
         }
     );
 
@@ -71,3 +81,55 @@ fn proper_initialization() {
         }
     )
 }
+
+//layout of config stored by first vault version
+#[derive(Serialize)]
+struct ConfigV1 {
+    governance_contract: Addr,
+    anchor_token: Addr,
+    anchor_overseer_contract: Addr,
+    anchor_market_contract: Addr,
+    anchor_custody_basset_contract: Addr,
+    anc_stable_swap_contract: Addr,
+    psi_stable_swap_contract: Addr,
+    basset_token: Addr,
+    aterra_token: Addr,
+    psi_token: Addr,
+    basset_vault_strategy_contract: Addr,
+    stable_denom: String,
+    claiming_rewards_delay: u64,
+    over_loan_balance_value: Decimal256,
+    nasset_token: Addr,
+    psi_distributor: Addr,
+}
+
+#[test]
+fn config_stored_by_previous_version_is_loaded() {
+    let mut sdk = Sdk::init();
+    let config = load_config(sdk.deps.as_ref().storage).unwrap();
+
+    let config_v1 = ConfigV1 {
+        governance_contract: config.governance_contract.clone(),
+        anchor_token: config.anchor_token.clone(),
+        anchor_overseer_contract: config.anchor_overseer_contract.clone(),
+        anchor_market_contract: config.anchor_market_contract.clone(),
+        anchor_custody_basset_contract: config.anchor_custody_basset_contract.clone(),
+        anc_stable_swap_contract: config.anc_stable_swap_contract.clone(),
+        psi_stable_swap_contract: config.psi_stable_swap_contract.clone(),
+        basset_token: config.basset_token.clone(),
+        aterra_token: config.aterra_token.clone(),
+        psi_token: config.psi_token.clone(),
+        basset_vault_strategy_contract: config.basset_vault_strategy_contract.clone(),
+        stable_denom: config.stable_denom.clone(),
+        claiming_rewards_delay: config.claiming_rewards_delay,
+        over_loan_balance_value: config.over_loan_balance_value,
+        nasset_token: config.nasset_token.clone(),
+        psi_distributor: config.psi_distributor.clone(),
+    };
+    sdk.deps
+        .storage
+        .set(b"config", &to_vec(&config_v1).unwrap());
+
+    //new fields get their instantiate defaults
+    assert_eq!(config, load_config(sdk.deps.as_ref().storage).unwrap());
+}
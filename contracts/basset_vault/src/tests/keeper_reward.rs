@@ -0,0 +1,415 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::state::store_rebalance_reward_reserve;
+use crate::tests::sdk::{
+    GOVERNANCE_CONTRACT, OVER_LOAN_BALANCE_VALUE, PSI_STABLE_SWAP_CONTRACT, STABLE_DENOM,
+};
+use crate::{SubmsgIds, DEFAULT_SWAP_MAX_SPREAD_PERCENT, MIN_ANC_REWARDS_TO_CLAIM};
+use basset_vault::astroport_pair::{ExecuteMsg as AstroportExecuteMsg, SimulationResponse};
+use basset_vault::basset_vault::{
+    AnyoneMsg, ExecuteMsg, GovernanceMsg, KeeperConfigResponse, QueryMsg,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use basset_vault::terraswap::{Asset, AssetInfo};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, Response, SubMsg, Uint128, WasmMsg,
+};
+use std::str::FromStr;
+
+//sender of Anyone messages in Sdk
+const KEEPER: &str = "addr9999";
+
+fn update_keeper_config(
+    sdk: &mut Sdk,
+    honest_work_reward_rate: Option<Decimal256>,
+    rebalance_reward: Option<Uint256>,
+    reward_cap: Option<Uint256>,
+    min_interval: Option<u64>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateKeeperConfig {
+            honest_work_reward_rate,
+            rebalance_reward,
+            reward_cap,
+            min_interval,
+        },
+    };
+    let info = mock_info(GOVERNANCE_CONTRACT, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+fn keeper_reward_msg(amount: u128) -> CosmosMsg {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: KEEPER.to_string(),
+        amount: vec![Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: Uint128::new(amount),
+        }],
+    })
+}
+
+#[test]
+fn keeper_rewards_disabled_by_default() {
+    let sdk = Sdk::init();
+
+    let keeper_config: KeeperConfigResponse = sdk.query(QueryMsg::KeeperConfig {}).unwrap();
+    assert_eq!(
+        KeeperConfigResponse {
+            honest_work_reward_rate: Decimal256::zero(),
+            rebalance_reward: Uint256::zero(),
+            reward_cap: Uint256::zero(),
+            min_interval: 0,
+            last_honest_work_reward_time: 0,
+            last_rebalance_reward_time: 0,
+            rebalance_reward_reserve: Uint256::zero(),
+        },
+        keeper_config
+    );
+}
+
+#[test]
+fn fail_to_set_keeper_reward_rate_greater_than_one() {
+    let mut sdk = Sdk::init();
+
+    let res = update_keeper_config(&mut sdk, Some(Decimal256::one()), None, None, None);
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
+
+#[test]
+fn fail_to_update_keeper_config_if_sender_is_not_governance() {
+    let mut sdk = Sdk::init();
+
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateKeeperConfig {
+            honest_work_reward_rate: None,
+            rebalance_reward: Some(Uint256::from(100u64)),
+            reward_cap: None,
+            min_interval: None,
+        },
+    };
+    let info = mock_info(KEEPER, &[]);
+    let res = crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg);
+    assert!(res.is_err());
+}
+
+#[test]
+fn rebalance_reward_paid_once_per_interval() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(1_500u64)),
+        Some(100),
+    )
+    .unwrap();
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(2_000u64)).unwrap();
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        Some(&SubMsg::reply_on_error(
+            keeper_reward_msg(1_500),
+            SubmsgIds::KeeperReward.id()
+        )),
+        response.messages.last()
+    );
+
+    //interval is not passed yet
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(1, response.messages.len());
+
+    let keeper_config: KeeperConfigResponse = sdk.query(QueryMsg::KeeperConfig {}).unwrap();
+    assert_eq!(
+        mock_env().block.time.seconds(),
+        keeper_config.last_rebalance_reward_time
+    );
+    assert_eq!(
+        Uint256::from(500u64),
+        keeper_config.rebalance_reward_reserve
+    );
+}
+
+#[test]
+fn rebalance_reward_limited_by_reserve() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(2_000u64)),
+        None,
+    )
+    .unwrap();
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+
+    //no profit set aside yet, borrowed stables are not spent on rewards
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(1, response.messages.len());
+
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(700u64)).unwrap();
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        Some(&SubMsg::reply_on_error(
+            keeper_reward_msg(700),
+            SubmsgIds::KeeperReward.id()
+        )),
+        response.messages.last()
+    );
+}
+
+#[test]
+fn no_rebalance_reward_for_deposit() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(2_000u64)),
+        None,
+    )
+    .unwrap();
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(2_000u64)).unwrap();
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+
+    //deposit sends Rebalance to vault itself
+    let msg = ExecuteMsg::Anyone {
+        anyone_msg: AnyoneMsg::Rebalance {},
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let response = crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(1, response.messages.len());
+
+    let keeper_config: KeeperConfigResponse = sdk.query(QueryMsg::KeeperConfig {}).unwrap();
+    assert_eq!(0, keeper_config.last_rebalance_reward_time);
+    assert_eq!(
+        Uint256::from(2_000u64),
+        keeper_config.rebalance_reward_reserve
+    );
+}
+
+#[test]
+fn rebalance_reward_reserve_topped_up_from_anc_selling_profit() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(2_000u64)),
+        None,
+    )
+    .unwrap();
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(500u64)).unwrap();
+
+    sdk.set_anc_pending_rewards(Decimal256::from_uint256(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.user_send_honest_work().unwrap();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(6_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.send_swap_anc().unwrap();
+
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_stable_balance(stable_coin_balance + Uint128::new(1_000_000));
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal::zero(), 0);
+
+    //1_500 of profit tops reserve up to one reward, it is not distributed
+    let reserve_top_up = Uint256::from(1_500u64);
+    let total_ust_value: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value
+        + stable_coin_balance.into()
+        + Uint256::from(1_000_000u64);
+    let aim_stable_balance: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value;
+    let expected_rewards = total_ust_value - aim_stable_balance - reserve_top_up;
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: (expected_rewards * Uint256::from(2u64)).into(),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+
+    let response = sdk.send_distribute_rewards().unwrap();
+    assert_eq!(
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: PSI_STABLE_SWAP_CONTRACT.to_string(),
+            msg: to_binary(&AstroportExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: STABLE_DENOM.to_string(),
+                    },
+                    amount: expected_rewards.into(),
+                },
+                max_spread: Some(Decimal::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT)),
+                belief_price: Some(Decimal::from_str("0.5").unwrap()),
+                to: Some(crate::tests::sdk::PSI_DISTRIBUTOR_CONTRACT.to_string()),
+            })
+            .unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: expected_rewards.into(),
+            }],
+        })),
+        response.messages[0]
+    );
+
+    let keeper_config: KeeperConfigResponse = sdk.query(QueryMsg::KeeperConfig {}).unwrap();
+    assert_eq!(
+        Uint256::from(2_000u64),
+        keeper_config.rebalance_reward_reserve
+    );
+}
+
+#[test]
+fn no_rebalance_reward_if_rebalance_not_needed() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(2_000u64)),
+        None,
+    )
+    .unwrap();
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    let response = sdk.rebalance().unwrap();
+    assert!(response.messages.is_empty());
+}
+
+#[test]
+fn honest_work_reward_paid_from_anc_selling_profit() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        Some(Decimal256::percent(10)),
+        None,
+        Some(Uint256::from(50_000u64)),
+        None,
+    )
+    .unwrap();
+
+    sdk.set_anc_pending_rewards(Decimal256::from_uint256(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.user_send_honest_work().unwrap();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    let stable_coin_balance_from_selling_anc = Uint128::new(1_000_000);
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_anc_balance(Uint256::from(3_000u64));
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(6_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.send_swap_anc().unwrap();
+
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_stable_balance(stable_coin_balance + stable_coin_balance_from_selling_anc);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal::zero(), 0);
+
+    //10% of 1_000_000 is more than cap
+    let keeper_reward = Uint256::from(50_000u64);
+    let total_ust_value: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value
+        + stable_coin_balance.into()
+        + stable_coin_balance_from_selling_anc.into();
+    let aim_stable_balance: Uint256 = Uint256::from(stable_coin_balance) * over_loan_balance_value;
+    let expected_rewards = total_ust_value - aim_stable_balance - keeper_reward;
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: (expected_rewards * Uint256::from(2u64)).into(),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+
+    let response = sdk.send_distribute_rewards().unwrap();
+    assert_eq!(
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: PSI_STABLE_SWAP_CONTRACT.to_string(),
+            msg: to_binary(&AstroportExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: STABLE_DENOM.to_string(),
+                    },
+                    amount: expected_rewards.into(),
+                },
+                max_spread: Some(Decimal::percent(DEFAULT_SWAP_MAX_SPREAD_PERCENT)),
+                belief_price: Some(Decimal::from_str("0.5").unwrap()),
+                to: Some(crate::tests::sdk::PSI_DISTRIBUTOR_CONTRACT.to_string()),
+            })
+            .unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: expected_rewards.into(),
+            }],
+        })),
+        response.messages[0]
+    );
+    assert_eq!(
+        Some(&SubMsg::new(keeper_reward_msg(50_000))),
+        response.messages.last()
+    );
+
+    //keeper is rewarded only once
+    let response = sdk.send_distribute_rewards().unwrap();
+    assert!(!response
+        .messages
+        .contains(&SubMsg::new(keeper_reward_msg(50_000))));
+}
+
+#[test]
+fn rebalance_reward_returns_to_reserve_if_send_failed() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        None,
+        Some(Uint256::from(2_000u64)),
+        Some(Uint256::from(1_500u64)),
+        None,
+    )
+    .unwrap();
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(2_000u64)).unwrap();
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+
+    sdk.rebalance().unwrap();
+    let reply_msg = Reply {
+        id: SubmsgIds::KeeperReward.id(),
+        result: cosmwasm_std::ContractResult::Err("insufficient funds".to_string()),
+    };
+    crate::contract::reply(sdk.deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let keeper_config: KeeperConfigResponse = sdk.query(QueryMsg::KeeperConfig {}).unwrap();
+    assert_eq!(
+        Uint256::from(2_000u64),
+        keeper_config.rebalance_reward_reserve
+    );
+}
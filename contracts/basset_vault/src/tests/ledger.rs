@@ -0,0 +1,157 @@
+use super::sdk::Sdk;
+use crate::tests::sdk::OVER_LOAN_BALANCE_VALUE;
+use crate::MIN_ANC_REWARDS_TO_CLAIM;
+use basset_vault::astroport_pair::SimulationResponse;
+use basset_vault::basset_vault::{EpochResponse, EpochsResponse, QueryMsg, StatsResponse};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{attr, Uint128};
+use std::str::FromStr;
+
+fn honest_work(sdk: &mut Sdk, stable_coin_balance_after_selling_anc: Uint128) {
+    sdk.set_anc_pending_rewards(Decimal256::from_uint256(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.user_send_honest_work().unwrap();
+
+    sdk.set_anc_balance(Uint256::from(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.send_swap_anc().unwrap();
+
+    sdk.set_stable_balance(stable_coin_balance_after_selling_anc);
+    sdk.send_distribute_rewards().unwrap();
+}
+
+#[test]
+fn honest_work_is_recorded_as_epoch() {
+    let mut sdk = Sdk::init();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal256::zero().into(), 0);
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(1_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(2_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+
+    honest_work(&mut sdk, Uint128::new(6_000_000));
+
+    //aterra covers loan, so all stables are spent on Psi
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(
+        StatsResponse {
+            anc_claimed: Uint256::from(MIN_ANC_REWARDS_TO_CLAIM),
+            anc_sold: Uint256::from(MIN_ANC_REWARDS_TO_CLAIM),
+            stables_realised: Uint256::from(1_000_000u64),
+            keeper_rewards: Uint256::zero(),
+            deposited_to_anc: Uint256::zero(),
+            stables_to_psi: Uint256::from(6_000_000u64),
+            psi_bought: Uint256::from(2_000_000u64),
+            interest_paid: Uint256::zero(),
+            stables_to_compound: Uint256::zero(),
+            basset_compounded: Uint256::zero(),
+            rebalances: 0,
+            epochs: 1,
+        },
+        stats
+    );
+
+    //loan grows because of interest
+    sdk.set_loan(Uint256::from(5_100_000u64));
+    sdk.set_stable_balance(stable_coin_balance);
+    honest_work(&mut sdk, Uint128::new(6_000_000));
+
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(Uint256::from(100_000u64), stats.interest_paid);
+    assert_eq!(2, stats.epochs);
+
+    let epochs: EpochsResponse = sdk
+        .query(QueryMsg::Epochs {
+            start_after: Some(0),
+            limit: None,
+        })
+        .unwrap();
+    let env = mock_env();
+    assert_eq!(1, epochs.epochs.len());
+    assert_eq!(
+        EpochResponse {
+            id: 1,
+            time: env.block.time.seconds(),
+            height: env.block.height,
+            anc_sold: Uint256::from(MIN_ANC_REWARDS_TO_CLAIM),
+            stables_realised: Uint256::from(1_000_000u64),
+            keeper_reward: Uint256::zero(),
+            deposited_to_anc: epochs.epochs[0].deposited_to_anc,
+            stables_to_psi: epochs.epochs[0].stables_to_psi,
+            psi_bought: epochs.epochs[0].psi_bought,
+            interest_paid: Uint256::from(100_000u64),
+            loan_amount: Uint256::from(5_100_000u64),
+        },
+        epochs.epochs[0]
+    );
+
+    let epochs: EpochsResponse = sdk
+        .query(QueryMsg::Epochs {
+            start_after: None,
+            limit: Some(1),
+        })
+        .unwrap();
+    assert_eq!(1, epochs.epochs.len());
+    assert_eq!(0, epochs.epochs[0].id);
+    assert_eq!(Uint256::zero(), epochs.epochs[0].interest_paid);
+}
+
+#[test]
+fn rebalance_tracks_loan_interest() {
+    let mut sdk = Sdk::init();
+    sdk.set_tax(Decimal256::zero().into(), 0);
+    sdk.set_aterra_balance(Uint256::zero());
+    sdk.set_aterra_exchange_rate(Decimal256::one());
+    sdk.set_stable_balance(Uint128::new(2_000));
+    sdk.set_loan(Uint256::from(10_000u64));
+
+    sdk.set_borrower_action(BorrowerActionResponse::Repay {
+        amount: Uint256::from(1_000u64),
+        advised_buffer_size: Uint256::zero(),
+        price_is_stale: false,
+    });
+    let response = sdk.rebalance().unwrap();
+    assert!(response.attributes.contains(&attr("loan_amount", "1000")));
+    sdk.continue_repay_loan().unwrap();
+
+    //vault expects 9_000 loan, 100 is interest
+    sdk.set_loan(Uint256::from(9_100u64));
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(500u64),
+        advised_buffer_size: Uint256::zero(),
+        price_is_stale: false,
+    });
+    sdk.rebalance().unwrap();
+
+    //nothing to do, loan is not observed
+    sdk.set_loan(Uint256::from(9_650u64));
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+    sdk.rebalance().unwrap();
+
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(100u64),
+        advised_buffer_size: Uint256::zero(),
+        price_is_stale: false,
+    });
+    sdk.rebalance().unwrap();
+
+    let stats: StatsResponse = sdk.query(QueryMsg::Stats {}).unwrap();
+    assert_eq!(Uint256::from(150u64), stats.interest_paid);
+    assert_eq!(3, stats.rebalances);
+    assert_eq!(0, stats.epochs);
+}
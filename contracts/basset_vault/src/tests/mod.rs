@@ -2,16 +2,32 @@ mod action_with_profit;
 mod after_borrow_action;
 mod change_config;
 mod change_governance_addr;
+mod compounding;
 mod deposit_basset;
 mod distribute_rewards;
+mod emergency_deleverage;
+mod estimated_apr;
 mod instantiate;
+mod keeper_reward;
+mod ledger;
+mod pause;
+mod position_health;
 mod repay_loan;
 mod repay_loan_action;
 mod sdk;
+mod simulate;
+mod stale_price;
+mod swap_router;
+mod unwind;
+mod vault_queries;
 mod withdraw_basset;
+mod withdrawal_queue;
+mod yield_venue;
 
 use basset_vault::anchor::basset_custody::BorrowerInfo as AnchorBassetCustodyBorrowerInfo;
 use basset_vault::anchor::market::BorrowerInfoResponse as AnchorMarketBorrowerInfo;
+use basset_vault::anchor::market::ConfigResponse as AnchorMarketConfigResponse;
+use basset_vault::anchor::market::StateResponse as AnchorMarketStateResponse;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
@@ -48,6 +64,9 @@ pub struct WasmMockQuerier {
     tax_querier: TaxQuerier,
     token_querier: TokenQuerier,
     wasm_query_smart_responses: HashMap<String, HashMap<Binary, Binary>>,
+    wasm_query_smart_errors: HashMap<String, HashMap<Binary, String>>,
+    market_state: AnchorMarketStateResponse,
+    market_config: AnchorMarketConfigResponse,
 }
 
 #[derive(Clone, Default)]
@@ -154,6 +173,14 @@ impl WasmMockQuerier {
                     return borrower_info_res;
                 }
 
+                //Anchor use cosmwasm_storage::Singleton which add length prefix
+                if key.to_vec() == to_length_prefixed(b"state") {
+                    return SystemResult::Ok(ContractResult::from(to_binary(&self.market_state)));
+                }
+                if key.to_vec() == to_length_prefixed(b"config") {
+                    return SystemResult::Ok(ContractResult::from(to_binary(&self.market_config)));
+                }
+
                 let prefix_token_info = b"token_info";
                 let prefix_token_info_legacy = to_length_prefixed(b"token_info");
                 let prefix_balance = to_length_prefixed(b"balance");
@@ -212,6 +239,14 @@ impl WasmMockQuerier {
                 }
             }
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if let Some(error) = self
+                    .wasm_query_smart_errors
+                    .get(contract_addr)
+                    .and_then(|errors_map| errors_map.get(msg))
+                {
+                    return SystemResult::Ok(ContractResult::Err(error.clone()));
+                }
+
                 let response = match self.wasm_query_smart_responses.get(contract_addr) {
                     Some(responses_map) => {
                         if responses_map.len() == 1 {
@@ -219,7 +254,11 @@ impl WasmMockQuerier {
                             //cause 'key' is fake in that map
                             responses_map.values().next().unwrap()
                         } else {
-                            responses_map.get(msg).unwrap()
+                            //fallback to response with fake key
+                            responses_map
+                                .get(msg)
+                                .or_else(|| responses_map.get(&to_binary(&0u64).unwrap()))
+                                .unwrap()
                         }
                     }
                     None => {
@@ -333,7 +372,19 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             wasm_query_smart_responses: HashMap::new(),
+            wasm_query_smart_errors: HashMap::new(),
             tax_querier: TaxQuerier::default(),
+            market_state: AnchorMarketStateResponse {
+                total_liabilities: Decimal256::zero(),
+                total_reserves: Decimal256::zero(),
+                anc_emission_rate: Decimal256::zero(),
+            },
+            market_config: AnchorMarketConfigResponse {
+                max_borrow_factor: Decimal256::one(),
+                interest_model: MockApi::default()
+                    .addr_canonicalize(sdk::ANCHOR_INTEREST_MODEL_CONTRACT)
+                    .unwrap(),
+            },
         }
     }
 
@@ -365,6 +416,13 @@ impl WasmMockQuerier {
         }
         self.wasm_query_smart_responses = result_map;
     }
+
+    pub fn with_wasm_query_error(&mut self, contract_addr: &str, request: Binary, error: &str) {
+        self.wasm_query_smart_errors
+            .entry(contract_addr.to_string())
+            .or_default()
+            .insert(request, error.to_string());
+    }
     //
     pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
         self.tax_querier = TaxQuerier::new(rate, caps);
@@ -374,6 +432,10 @@ impl WasmMockQuerier {
         self.base.update_balance(addr, balance)
     }
 
+    pub fn with_market_state(&mut self, market_state: AnchorMarketStateResponse) {
+        self.market_state = market_state;
+    }
+
     pub fn with_locked_basset(
         &mut self,
         borrowers: &[(&String, &[(&String, &AnchorBassetCustodyBorrowerInfo)])],
@@ -0,0 +1,144 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::GOVERNANCE_CONTRACT;
+use basset_vault::basset_vault::{
+    AnyoneMsg, ExecuteMsg, GovernanceMsg, GuardianMsg, PauseStatusResponse, QueryMsg,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, Response, StdError, Uint128};
+
+const GUARDIAN: &str = "addr7777";
+
+fn set_guardian(sdk: &mut Sdk) {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateGuardian {
+            guardian_addr: Some(GUARDIAN.to_string()),
+        },
+    };
+    let info = mock_info(GOVERNANCE_CONTRACT, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn pause_all(sdk: &mut Sdk, sender: &str, paused: bool) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Guardian {
+        guardian_msg: GuardianMsg::SetPause {
+            deposit: Some(paused),
+            withdraw: Some(paused),
+            borrow: Some(paused),
+            honest_work: Some(paused),
+            claim_remainder: Some(paused),
+        },
+    };
+    let info = mock_info(sender, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+fn paused(operation: &str) -> ContractError {
+    ContractError::Paused {
+        operation: operation.to_string(),
+    }
+}
+
+#[test]
+fn nothing_paused_by_default() {
+    let sdk = Sdk::init();
+
+    let pause_status: PauseStatusResponse = sdk.query(QueryMsg::PauseStatus {}).unwrap();
+    assert_eq!(
+        PauseStatusResponse {
+            guardian_addr: None,
+            deposit: false,
+            withdraw: false,
+            borrow: false,
+            honest_work: false,
+            claim_remainder: false,
+        },
+        pause_status
+    );
+}
+
+#[test]
+fn fail_to_pause_if_sender_is_not_guardian_or_governance() {
+    let mut sdk = Sdk::init();
+    set_guardian(&mut sdk);
+
+    let res = pause_all(&mut sdk, "addr0001", true);
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err("unauthorized"))),
+        res
+    );
+
+    pause_all(&mut sdk, GUARDIAN, true).unwrap();
+    pause_all(&mut sdk, GOVERNANCE_CONTRACT, false).unwrap();
+}
+
+#[test]
+fn paused_operations_fail() {
+    let mut sdk = Sdk::init();
+    set_guardian(&mut sdk);
+    pause_all(&mut sdk, GUARDIAN, true).unwrap();
+
+    let pause_status: PauseStatusResponse = sdk.query(QueryMsg::PauseStatus {}).unwrap();
+    assert_eq!(
+        PauseStatusResponse {
+            guardian_addr: Some(GUARDIAN.to_string()),
+            deposit: true,
+            withdraw: true,
+            borrow: true,
+            honest_work: true,
+            claim_remainder: true,
+        },
+        pause_status
+    );
+
+    let res = sdk.user_deposit("addr0001", Uint128::new(1_000));
+    assert_eq!(Err(paused("deposit")), res);
+
+    let res = sdk.user_withdraw("addr0001", Uint128::new(1_000));
+    assert_eq!(Err(paused("withdraw")), res);
+
+    let res = sdk.user_send_honest_work();
+    assert_eq!(Err(paused("honest_work")), res);
+
+    let msg = ExecuteMsg::Anyone {
+        anyone_msg: AnyoneMsg::ClaimRemainder {},
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(Err(paused("claim_remainder")), res);
+}
+
+#[test]
+fn borrow_skipped_but_repay_allowed_when_borrow_paused() {
+    let mut sdk = Sdk::init();
+    pause_all(&mut sdk, GOVERNANCE_CONTRACT, true).unwrap();
+
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+    let response = sdk.rebalance().unwrap();
+    assert!(response.messages.is_empty());
+    assert_eq!(
+        vec![
+            attr("action", "rebalance"),
+            attr("skipped", "borrow_paused")
+        ],
+        response.attributes
+    );
+
+    sdk.set_stable_balance(Uint128::new(200));
+    sdk.set_aterra_balance(Uint256::from(200u64));
+    sdk.set_aterra_exchange_rate(Decimal256::one());
+    sdk.set_borrower_action(BorrowerActionResponse::Repay {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(50u64),
+        price_is_stale: false,
+    });
+    let response = sdk.rebalance().unwrap();
+    //redeem aterra and repay loan
+    assert_eq!(2, response.messages.len());
+}
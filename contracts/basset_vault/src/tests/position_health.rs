@@ -0,0 +1,95 @@
+use super::sdk::Sdk;
+use crate::tests::sdk::{BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, STABLE_DENOM};
+use basset_vault::anchor::oracle::PriceResponse;
+use basset_vault::basset_vault::{PositionHealthResponse, QueryMsg};
+use basset_vault::basset_vault_strategy::{
+    ConfigResponse as StrategyConfigResponse, StalePricePolicy,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Uint128;
+use std::str::FromStr;
+
+const ORACLE_CONTRACT: &str = "addr0020";
+
+fn set_strategy_and_oracle(sdk: &mut Sdk) {
+    sdk.set_strategy_config(StrategyConfigResponse {
+        governance_contract: GOVERNANCE_CONTRACT.to_string(),
+        oracle_contract: ORACLE_CONTRACT.to_string(),
+        basset_token: BASSET_TOKEN_ADDR.to_string(),
+        stable_denom: STABLE_DENOM.to_string(),
+        borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+        borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+        borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+        basset_max_ltv: Decimal256::from_str("0.6").unwrap(),
+        buffer_part: Decimal256::from_str("0.018").unwrap(),
+        price_timeframe: 60,
+        stale_price_policy: StalePricePolicy::default(),
+    });
+    let current_time = mock_env().block.time.seconds();
+    sdk.set_oracle_price(PriceResponse {
+        rate: Decimal256::from_str("10").unwrap(),
+        last_updated_base: current_time,
+        last_updated_quote: current_time,
+    });
+}
+
+#[test]
+fn position_health_snapshot() {
+    let mut sdk = Sdk::init();
+    set_strategy_and_oracle(&mut sdk);
+
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+    sdk.set_loan(Uint256::from(4_500u64));
+    sdk.set_aterra_balance(Uint256::from(4_000u64));
+    sdk.set_aterra_exchange_rate(Decimal256::from_str("1.1").unwrap());
+    sdk.set_stable_balance(Uint128::from(145u64));
+
+    let health: PositionHealthResponse = sdk.query(QueryMsg::PositionHealth {}).unwrap();
+
+    let current_time = mock_env().block.time.seconds();
+    assert_eq!(
+        PositionHealthResponse {
+            basset_in_custody: Uint256::from(1_000u64),
+            loan_amount: Uint256::from(4_500u64),
+            basset_price: Decimal256::from_str("10").unwrap(),
+            price_last_updated_base: current_time,
+            price_last_updated_quote: current_time,
+            //1_000 * 10 * 0.6
+            max_borrow_amount: Uint256::from(6_000u64),
+            //4_500 / 6_000
+            current_ltv: Some(Decimal256::from_str("0.75").unwrap()),
+            borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+            borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+            borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+            //4_500 / (1_000 * 0.6)
+            liquidation_price: Some(Decimal256::from_str("7.5").unwrap()),
+            aterra_balance: Uint256::from(4_000u64),
+            aterra_exchange_rate: Decimal256::from_str("1.1").unwrap(),
+            aterra_value: Uint256::from(4_400u64),
+            stable_balance: Uint256::from(145u64),
+            aim_buffer_size: Uint256::zero(),
+            //(4_400 + 145) / 4_500
+            coverage_ratio: Some(Decimal256::from_str("1.01").unwrap()),
+            over_loan_balance_value: Decimal256::from_str("1.01").unwrap(),
+        },
+        health
+    );
+}
+
+#[test]
+fn position_health_without_loan() {
+    let mut sdk = Sdk::init();
+    set_strategy_and_oracle(&mut sdk);
+
+    sdk.set_collateral_balance(Uint256::zero());
+    sdk.set_aterra_balance(Uint256::zero());
+    sdk.set_stable_balance(Uint128::zero());
+
+    let health: PositionHealthResponse = sdk.query(QueryMsg::PositionHealth {}).unwrap();
+
+    assert_eq!(Uint256::zero(), health.max_borrow_amount);
+    assert_eq!(None, health.current_ltv);
+    assert_eq!(None, health.liquidation_price);
+    assert_eq!(None, health.coverage_ratio);
+}
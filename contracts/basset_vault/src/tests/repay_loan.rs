@@ -1,4 +1,4 @@
-use crate::{state::load_repaying_loan_state, SubmsgIds};
+use crate::{error::ContractError, state::load_repaying_loan_state, SubmsgIds};
 
 use super::sdk::Sdk;
 use crate::tests::sdk::{ANCHOR_MARKET_CONTRACT, ATERRA_TOKEN, STABLE_DENOM};
@@ -9,11 +9,21 @@ use basset_vault::{
     querier::{AnchorMarketCw20Msg, AnchorMarketMsg},
     BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP,
 };
-use cosmwasm_std::{to_binary, Coin, Decimal, ReplyOn, Response, SubMsg, Uint128, WasmMsg};
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{
+    to_binary, Coin, Decimal, Reply, ReplyOn, Response, StdError, SubMsg, Uint128, WasmMsg,
+};
 use cw20::Cw20ExecuteMsg;
 use std::convert::Into;
 use std::str::FromStr;
 
+/// Liquidity is taken from market (5k at start) after repayment was planned,
+/// so redeeming fails. Each step takes a bit more
+fn take_market_liquidity(sdk: &mut Sdk, step: u8) {
+    let market_balance = 5_000u128 - 5 * step as u128;
+    sdk.set_market_liquidity(Uint128::new(market_balance), Decimal256::zero());
+}
+
 #[test]
 fn repay_loan_without_problems() {
     let mut sdk = Sdk::init();
@@ -30,6 +40,7 @@ fn repay_loan_without_problems() {
     sdk.set_borrower_action(BorrowerActionResponse::Repay {
         amount: loan_to_repay,
         advised_buffer_size,
+        price_is_stale: false,
     });
     sdk.set_aterra_exchange_rate(aterra_exchange_rate);
     sdk.set_tax(tax_rate.into(), 10u128);
@@ -37,45 +48,6 @@ fn repay_loan_without_problems() {
     // -= REBALANCE =-
     {
         let response = sdk.rebalance().unwrap();
-        assert_eq!(
-            response.messages,
-            vec![SubMsg {
-                msg: WasmMsg::Execute {
-                    contract_addr: ATERRA_TOKEN.to_string(),
-                    msg: to_binary(&Cw20ExecuteMsg::Send {
-                        contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                        amount: aterra_balance.into(),
-                        msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
-                    })
-                    .unwrap(),
-                    funds: vec![],
-                }
-                .into(),
-                gas_limit: None,
-                id: SubmsgIds::RedeemStableOnRepayLoan.id(),
-                reply_on: ReplyOn::Always,
-            }]
-        );
-        let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
-        assert_eq!(rapaying_state.iteration_index, 0);
-        assert_eq!(rapaying_state.to_repay_amount, loan_to_repay);
-        assert_eq!(rapaying_state.repaying_amount, Uint256::zero());
-        assert_eq!(rapaying_state.aim_buffer_size, advised_buffer_size);
-    }
-
-    // -= ANCHOR REDEEM SUCCESSFULL =-
-    {
-        //anchor send stables for selling aterra
-        // 10 is a cap tax
-        sdk.set_stable_balance(
-            (Uint256::from(stable_coin_balance) + aterra_balance * aterra_exchange_rate
-                - Uint256::from(10u64))
-            .into(),
-        );
-        //all aterra was redeemed
-        sdk.set_aterra_balance(Uint256::zero());
-
-        let response = sdk.aterra_redeem_success().unwrap();
 
         let repay_stable_coin = Coin {
             denom: STABLE_DENOM.to_string(),
@@ -86,23 +58,32 @@ fn repay_loan_without_problems() {
                 - Uint256::from(10u64))
             .into(),
         };
+        //redeem and repay planned in one pass
         assert_eq!(
             response.messages,
-            vec![SubMsg {
-                msg: WasmMsg::Execute {
-                    contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
-                    msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
-                    funds: vec![repay_stable_coin.clone()],
-                }
-                .into(),
-                gas_limit: None,
-                id: SubmsgIds::RepayLoan.id(),
-                reply_on: ReplyOn::Success,
-            }]
+            vec![
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: ATERRA_TOKEN.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: ANCHOR_MARKET_CONTRACT.to_string(),
+                        amount: aterra_balance.into(),
+                        msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+                        msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
+                        funds: vec![repay_stable_coin.clone()],
+                    },
+                    SubmsgIds::RepayLoan.id(),
+                ),
+            ]
         );
-
         let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
-        assert_eq!(rapaying_state.iteration_index, 1);
+        assert_eq!(rapaying_state.iteration_index, 0);
         assert_eq!(rapaying_state.to_repay_amount, loan_to_repay);
         assert_eq!(
             rapaying_state.repaying_amount,
@@ -128,6 +109,7 @@ fn repay_loan_avoid_to_sell_one_a_ust() {
     sdk.set_borrower_action(BorrowerActionResponse::Repay {
         amount: loan_to_repay,
         advised_buffer_size,
+        price_is_stale: false,
     });
     sdk.set_aterra_exchange_rate(aterra_exchange_rate);
     sdk.set_tax(tax_rate.into(), 1000000u128);
@@ -141,68 +123,37 @@ fn repay_loan_avoid_to_sell_one_a_ust() {
     // -= REBALANCE =-
     {
         let response = sdk.rebalance().unwrap();
+
+        let repay_stable_coin = Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: loan_to_repay.into(),
+        };
         assert_eq!(
             response.messages,
-            vec![SubMsg {
-                msg: WasmMsg::Execute {
+            vec![
+                SubMsg::new(WasmMsg::Execute {
                     contract_addr: ATERRA_TOKEN.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Send {
                         contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                        amount: aterra_to_sell.into(),
+                        amount: aterra_to_sell,
                         msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                     })
                     .unwrap(),
                     funds: vec![],
-                }
-                .into(),
-                gas_limit: None,
-                id: SubmsgIds::RedeemStableOnRepayLoan.id(),
-                reply_on: ReplyOn::Always,
-            }]
+                }),
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+                        msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
+                        funds: vec![repay_stable_coin.clone()],
+                    },
+                    SubmsgIds::RepayLoan.id(),
+                ),
+            ]
         );
         let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
         assert_eq!(rapaying_state.iteration_index, 0);
         assert_eq!(rapaying_state.to_repay_amount, loan_to_repay);
-        assert_eq!(rapaying_state.repaying_amount, Uint256::zero());
-        assert_eq!(rapaying_state.aim_buffer_size, advised_buffer_size);
-    }
-
-    // -= ANCHOR REDEEM SUCCESSFULL =-
-    {
-        //anchor send stables for selling aterra
-        // tax is 240 (send 239999)
-        sdk.set_stable_balance(
-            (Uint256::from(stable_coin_balance)
-                + Uint256::from(aterra_to_sell * Into::<Decimal>::into(aterra_exchange_rate))
-                - Uint256::from(240u64))
-            .into(),
-        );
-        sdk.set_aterra_balance(Uint256::from(468250u64 - 240479u64));
-
-        let response = sdk.aterra_redeem_success().unwrap();
-
-        let repay_stable_coin = Coin {
-            denom: STABLE_DENOM.to_string(),
-            amount: loan_to_repay.into(),
-        };
-        assert_eq!(
-            response.messages,
-            vec![SubMsg {
-                msg: WasmMsg::Execute {
-                    contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
-                    msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
-                    funds: vec![repay_stable_coin.clone()],
-                }
-                .into(),
-                gas_limit: None,
-                id: SubmsgIds::RepayLoan.id(),
-                reply_on: ReplyOn::Success,
-            }]
-        );
-
-        let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
-        assert_eq!(rapaying_state.iteration_index, 1);
-        assert_eq!(rapaying_state.to_repay_amount, loan_to_repay);
         assert_eq!(
             rapaying_state.repaying_amount,
             repay_stable_coin.amount.into()
@@ -223,6 +174,9 @@ fn repay_loan_fail_to_redeem_aterra() {
 
     //no tax
     sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    //Anchor liquidity is constrained (only 4k aterra can be redeemed),
+    //so loan is repaid step by step in reply handler
+    sdk.set_market_liquidity(Uint128::new(5_000), Decimal256::zero());
 
     // -= asking for REPAY =-
     {
@@ -231,6 +185,7 @@ fn repay_loan_fail_to_redeem_aterra() {
         sdk.set_borrower_action(BorrowerActionResponse::Repay {
             amount: to_repay_amount,
             advised_buffer_size: aim_buffer_size,
+            price_is_stale: false,
         });
         let response = sdk.rebalance().unwrap();
 
@@ -241,7 +196,8 @@ fn repay_loan_fail_to_redeem_aterra() {
                     contract_addr: ATERRA_TOKEN.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Send {
                         contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                        amount: aterra_balance.into(),
+                        //all redeemable aterra (5000/1.25 = 4k)
+                        amount: Uint128::from(4_000u64),
                         msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                     })
                     .unwrap(),
@@ -257,6 +213,8 @@ fn repay_loan_fail_to_redeem_aterra() {
 
     // -= REDEEM failed =-
     {
+        //someone took liquidity from market in the same block
+        sdk.set_market_liquidity(Uint128::new(4_000), Decimal256::zero());
         let response = sdk.aterra_redeed_failed().unwrap();
         //now contract should repay loan with buffer and try to redeem aterra for that amount
         assert_eq!(
@@ -281,8 +239,8 @@ fn repay_loan_fail_to_redeem_aterra() {
                         contract_addr: ATERRA_TOKEN.to_string(),
                         msg: to_binary(&Cw20ExecuteMsg::Send {
                             contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                            //sell aterra for same value as repaying long (4000*1.25 = 5k)
-                            amount: Uint128::from(4_000u64),
+                            //sell all redeemable aterra (4000/1.25 = 3.2k)
+                            amount: Uint128::from(3_200u64),
                             msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                         })
                         .unwrap(),
@@ -299,7 +257,9 @@ fn repay_loan_fail_to_redeem_aterra() {
 
     // -= REDEEM SUCCEEDED =-
     {
-        let updated_aterra_balance = aterra_balance - Uint256::from(4_000u64);
+        //repaid loan returns liquidity to market
+        sdk.set_market_liquidity(Uint128::new(10_000), Decimal256::zero());
+        let updated_aterra_balance = aterra_balance - Uint256::from(3_200u64);
         sdk.set_aterra_balance(updated_aterra_balance);
         sdk.continue_repay_loan().unwrap();
 
@@ -332,8 +292,8 @@ fn repay_loan_fail_to_redeem_aterra() {
                         contract_addr: ATERRA_TOKEN.to_string(),
                         msg: to_binary(&Cw20ExecuteMsg::Send {
                             contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                            //sell rest of aterra 3k (3000*1.25 = 3750)
-                            amount: Uint128::from(3_000u64),
+                            //sell rest of aterra 3.8k (3800*1.25 = 4750)
+                            amount: Uint128::from(3_800u64),
                             msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                         })
                         .unwrap(),
@@ -372,12 +332,16 @@ fn limited_recursion_depth_all_errors() {
 
     //no tax
     sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    //Anchor liquidity is constrained (only 4k aterra can be redeemed),
+    //so loan is repaid step by step in reply handler
+    sdk.set_market_liquidity(Uint128::new(5_000), Decimal256::zero());
 
     let to_repay_amount = Uint256::from(10_000u64);
     let aim_buffer_size = Uint256::from(5_000u64);
     sdk.set_borrower_action(BorrowerActionResponse::Repay {
         amount: to_repay_amount,
         advised_buffer_size: aim_buffer_size,
+        price_is_stale: false,
     });
 
     // -= REPAY =-
@@ -386,6 +350,7 @@ fn limited_recursion_depth_all_errors() {
     // -= ANCHOR REDEEM FAILED =-
     let start_from = 1;
     for repaying_index in start_from..BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP {
+        take_market_liquidity(&mut sdk, repaying_index);
         let response = sdk.aterra_redeed_failed().unwrap();
         //now contract should repay loan with buffer and try to redeem aterra for that amount
         assert_eq!(
@@ -410,8 +375,8 @@ fn limited_recursion_depth_all_errors() {
                         contract_addr: ATERRA_TOKEN.to_string(),
                         msg: to_binary(&Cw20ExecuteMsg::Send {
                             contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                            //sell aterra for same value as repaying long (4000*1.25 = 5k)
-                            amount: Uint128::from(4_000u64),
+                            //sell all redeemable aterra
+                            amount: Uint128::from(4_000u64 - 4 * repaying_index as u64),
                             msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                         })
                         .unwrap(),
@@ -428,6 +393,7 @@ fn limited_recursion_depth_all_errors() {
         assert_eq!(rapaying_state.iteration_index, repaying_index);
     }
 
+    take_market_liquidity(&mut sdk, BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP);
     let response = sdk.aterra_redeed_failed();
     assert!(response.is_err());
 }
@@ -444,12 +410,16 @@ fn limited_recursion_depth_repayed_something() {
 
     //no tax
     sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    //Anchor liquidity is constrained (only 4k aterra can be redeemed),
+    //so loan is repaid step by step in reply handler
+    sdk.set_market_liquidity(Uint128::new(5_000), Decimal256::zero());
 
     let to_repay_amount = Uint256::from(10_000u64);
     let aim_buffer_size = Uint256::from(5_000u64);
     sdk.set_borrower_action(BorrowerActionResponse::Repay {
         amount: to_repay_amount,
         advised_buffer_size: aim_buffer_size,
+        price_is_stale: false,
     });
 
     // -= REPAY =-
@@ -458,6 +428,7 @@ fn limited_recursion_depth_repayed_something() {
     // -= ANCHOR REDEEM FAILED =-
     let start_from = 2;
     for repaying_index in start_from..BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP {
+        take_market_liquidity(&mut sdk, repaying_index);
         let response = sdk.aterra_redeed_failed().unwrap();
         //now contract should repay loan with buffer and try to redeem aterra for that amount
         assert_eq!(
@@ -482,8 +453,8 @@ fn limited_recursion_depth_repayed_something() {
                         contract_addr: ATERRA_TOKEN.to_string(),
                         msg: to_binary(&Cw20ExecuteMsg::Send {
                             contract: ANCHOR_MARKET_CONTRACT.to_string(),
-                            //sell aterra for same value as repaying long (4000*1.25 = 5k)
-                            amount: Uint128::from(4_000u64),
+                            //sell all redeemable aterra
+                            amount: Uint128::from(4_000u64 - 4 * repaying_index as u64),
                             msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
                         })
                         .unwrap(),
@@ -502,6 +473,7 @@ fn limited_recursion_depth_repayed_something() {
     //one repaying is OK!
     sdk.continue_repay_loan().unwrap();
 
+    take_market_liquidity(&mut sdk, BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP);
     let response = sdk.aterra_redeed_failed();
     assert!(response.is_ok());
 }
@@ -518,12 +490,16 @@ fn reset_iteration_index() {
 
     //no tax
     sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    //Anchor liquidity is constrained (only 4k aterra can be redeemed),
+    //so loan is repaid step by step in reply handler
+    sdk.set_market_liquidity(Uint128::new(5_000), Decimal256::zero());
 
     let to_repay_amount = Uint256::from(10_000u64);
     let aim_buffer_size = Uint256::from(5_000u64);
     sdk.set_borrower_action(BorrowerActionResponse::Repay {
         amount: to_repay_amount,
         advised_buffer_size: aim_buffer_size,
+        price_is_stale: false,
     });
 
     // -= REPAY =-
@@ -531,11 +507,13 @@ fn reset_iteration_index() {
 
     // -= ANCHOR REDEEM FAILED =-
     let start_from = 1;
-    for _ in start_from..BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP {
+    for repaying_index in start_from..BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP {
+        take_market_liquidity(&mut sdk, repaying_index);
         let response = sdk.aterra_redeed_failed();
         assert!(response.is_ok());
     }
 
+    take_market_liquidity(&mut sdk, BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP);
     let response = sdk.aterra_redeed_failed();
     assert!(response.is_err());
     let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
@@ -549,3 +527,81 @@ fn reset_iteration_index() {
     let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
     assert_eq!(rapaying_state.iteration_index, 0);
 }
+
+#[test]
+fn repay_loan_from_buffer_if_market_has_no_liquidity_to_redeem_aterra() {
+    let mut sdk = Sdk::init();
+
+    let stable_coin_balance = Uint128::from(200u64);
+    let loan_to_repay = Uint256::from(10_000u64);
+    let advised_buffer_size = Uint256::from(50u64);
+
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_aterra_balance(Uint256::from(200u64));
+    sdk.set_borrower_action(BorrowerActionResponse::Repay {
+        amount: loan_to_repay,
+        advised_buffer_size,
+        price_is_stale: false,
+    });
+    sdk.set_aterra_exchange_rate(Decimal256::from_str("1.2").unwrap());
+    sdk.set_tax(Decimal::zero(), 0u128);
+    //all stables on market balance are reserves
+    sdk.set_market_liquidity(
+        Uint128::new(1_000),
+        Decimal256::from_uint256(Uint256::from(1_000u64)),
+    );
+
+    //do not send redeem message that will fail, repay loan from buffer instead
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg {
+            msg: WasmMsg::Execute {
+                contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+                msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
+                funds: vec![Coin {
+                    denom: STABLE_DENOM.to_string(),
+                    amount: stable_coin_balance,
+                }],
+            }
+            .into(),
+            gas_limit: None,
+            id: SubmsgIds::RepayLoan.id(),
+            reply_on: ReplyOn::Success,
+        }]
+    );
+}
+
+#[test]
+fn redeem_error_other_than_liquidity_is_returned() {
+    let mut sdk = Sdk::init();
+
+    sdk.set_stable_balance(Uint128::new(5_000));
+    sdk.set_aterra_exchange_rate(Decimal256::from_str("1.25").unwrap());
+    sdk.set_aterra_balance(Uint256::from(7_000u64));
+    sdk.set_tax(Decimal256::zero().into(), 99999999999u128);
+    sdk.set_market_liquidity(Uint128::new(5_000), Decimal256::zero());
+    sdk.set_borrower_action(BorrowerActionResponse::Repay {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(5_000u64),
+        price_is_stale: false,
+    });
+    sdk.rebalance().unwrap();
+
+    //market still has liquidity for planned redeem
+    let reply_msg = Reply {
+        id: SubmsgIds::RedeemStableOnRepayLoan.id(),
+        result: cosmwasm_std::ContractResult::Err("Insufficient funds".to_string()),
+    };
+    let response = crate::contract::reply(sdk.deps.as_mut(), mock_env(), reply_msg);
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err(
+            "Insufficient funds"
+        ))),
+        response
+    );
+
+    //no retry
+    let rapaying_state = load_repaying_loan_state(&sdk.deps.storage).unwrap();
+    assert_eq!(rapaying_state.iteration_index, 0);
+}
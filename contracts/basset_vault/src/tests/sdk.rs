@@ -1,5 +1,5 @@
 use crate::tests::mock_dependencies;
-use crate::TOO_HIGH_BORROW_DEMAND_ERR_MSG;
+use crate::ContractResult;
 use crate::{reply_response::MsgInstantiateContractResponse, SubmsgIds};
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
@@ -8,10 +8,11 @@ use cosmwasm_std::{
     Api, CosmosMsg, Decimal, OwnedDeps, Querier, Reply, ReplyOn, Storage, SubMsg,
     SubMsgExecutionResponse, WasmMsg,
 };
+use cosmwasm_std::{from_binary, Addr, Binary, StdResult};
 use cosmwasm_std::{to_binary, Coin, Empty, Event, Response, Uint128};
-use cosmwasm_std::{Addr, StdResult};
 use cw20::Cw20ReceiveMsg;
 use cw20::MinterResponse;
+use serde::de::DeserializeOwned;
 
 use basset_vault::basset_vault::YourselfMsg;
 use protobuf::Message;
@@ -21,14 +22,26 @@ use std::str::FromStr;
 
 use basset_vault::anchor::basset_custody::BorrowerInfo as AnchorBassetCustodyBorrowerInfo;
 use basset_vault::anchor::market::BorrowerInfoResponse as AnchorMarketBorrowerInfo;
+use basset_vault::anchor::market::{
+    BorrowRateResponse, StateResponse as AnchorMarketStateResponse,
+};
+use basset_vault::anchor::oracle::PriceResponse;
+use basset_vault::anchor::overseer::EpochStateResponse as AnchorOverseerEpochStateResponse;
 use basset_vault::astroport_factory::{ExecuteMsg as AstroportFactoryExecuteMsg, PairType};
+use basset_vault::astroport_pair::SimulationResponse;
+use basset_vault::astroport_router::SimulateSwapOperationsResponse;
 use basset_vault::basset_vault::Cw20HookMsg;
-use basset_vault::basset_vault_strategy::BorrowerActionResponse;
-use basset_vault::psi_distributor::InstantiateMsg as PsiDistributorInstantiateMsg;
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, ConfigResponse as StrategyConfigResponse,
+    FreshPriceResponse as StrategyFreshPriceResponse, QueryMsg as StrategyQueryMsg,
+};
+use basset_vault::psi_distributor::{
+    ConfigResponse as PsiDistributorConfigResponse, InstantiateMsg as PsiDistributorInstantiateMsg,
+};
 use basset_vault::querier::{AnchorMarketEpochStateResponse, AnchorMarketQueryMsg};
 use basset_vault::terraswap::AssetInfo;
 use basset_vault::{
-    basset_vault::ExecuteMsg,
+    basset_vault::{ExecuteMsg, GovernanceMsg, QueryMsg},
     nasset_token::InstantiateMsg as NAssetTokenInstantiateMsg,
     nasset_token_config_holder::{
         AnyoneMsg as NAssetTokenConfigHolderAnyoneMsg,
@@ -60,6 +73,11 @@ pub const ASTROPORT_FACTORY_CONTRACT_ADDR: &str = "addr0014";
 pub const BASSET_VAULT_STRATEGY_CONTRACT: &str = "addr0012";
 pub const COMMUNITY_POOL_CONTRACT_ADDR: &str = "addr0013";
 pub const NASSET_PSI_SWAP_CONTRACT_ADDR: &str = "addr0019";
+pub const BASSET_STABLE_SWAP_CONTRACT: &str = "addr0021";
+pub const ANCHOR_INTEREST_MODEL_CONTRACT: &str = "addr0022";
+pub const ROUTER_CONTRACT: &str = "addr0023";
+pub const ANC_STABLE_TWAP_ORACLE: &str = "addr0024";
+pub const PSI_STABLE_TWAP_ORACLE: &str = "addr0025";
 pub const CLAIMING_REWARDS_DELAY: u64 = 1000;
 pub const NASSET_TOKEN_CODE_ID: u64 = 10u64;
 pub const NASSET_TOKEN_CONFIG_HOLDER_CODE_ID: u64 = 11u64;
@@ -76,9 +94,22 @@ pub struct Sdk {
     anc_balance: Uint128,
     basset_balance: Uint128,
     nasset_supply: Uint128,
+    nasset_balances: Vec<(String, Uint128)>,
     aterra_exchange_rate: Decimal256,
     anc_pending_rewards: Decimal256,
     borrower_action: BorrowerActionResponse,
+    loan: Uint256,
+    strategy_config: Option<StrategyConfigResponse>,
+    oracle_price: Option<PriceResponse>,
+    strategy_fresh_price: Option<Decimal256>,
+    anc_swap_simulation: Option<SimulationResponse>,
+    psi_swap_simulation: Option<SimulationResponse>,
+    basset_swap_simulation: Option<SimulationResponse>,
+    router_simulation: Option<SimulateSwapOperationsResponse>,
+    anc_twap_return_amount: Option<Uint256>,
+    psi_twap_return_amount: Option<Uint256>,
+    borrow_rate: Decimal256,
+    deposit_rate: Decimal256,
 }
 
 impl Sdk {
@@ -112,6 +143,14 @@ impl Sdk {
         };
 
         let mut deps = mock_dependencies(&[]);
+        //enough liquidity to redeem any aterra amount used in tests
+        deps.querier.update_base_balance(
+            ANCHOR_MARKET_CONTRACT,
+            vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: Uint128::new(1_000_000_000_000_000_000),
+            }],
+        );
         Self::instantiate_basset_vault(
             &mut deps,
             msg.clone(),
@@ -127,9 +166,24 @@ impl Sdk {
             anc_balance: Uint128::zero(),
             basset_balance: Uint128::zero(),
             nasset_supply: Uint128::zero(),
+            nasset_balances: vec![],
             aterra_exchange_rate: Decimal256::zero(),
             anc_pending_rewards: Decimal256::zero(),
-            borrower_action: BorrowerActionResponse::Nothing {},
+            borrower_action: BorrowerActionResponse::Nothing {
+                price_is_stale: false,
+            },
+            loan: Uint256::zero(),
+            strategy_config: None,
+            oracle_price: None,
+            strategy_fresh_price: None,
+            anc_swap_simulation: None,
+            psi_swap_simulation: None,
+            basset_swap_simulation: None,
+            router_simulation: None,
+            anc_twap_return_amount: None,
+            psi_twap_return_amount: None,
+            borrow_rate: Decimal256::zero(),
+            deposit_rate: Decimal256::zero(),
         }
     }
 
@@ -456,6 +510,8 @@ impl Sdk {
 
     #[allow(dead_code)]
     pub fn set_loan(&mut self, value: Uint256) {
+        self.loan = value;
+        self.set_wasm_query_respones();
         self.deps.querier.with_loan(&[(
             &ANCHOR_MARKET_CONTRACT.to_string(),
             &[(
@@ -487,36 +543,230 @@ impl Sdk {
     }
 
     fn set_wasm_query_respones(&mut self) {
-        self.deps.querier.with_wasm_query_response(&[
+        let mut responses = vec![
             (
-                &BASSET_VAULT_STRATEGY_CONTRACT.to_string(),
-                &to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
-                &to_binary(&self.borrower_action).unwrap(),
+                BASSET_VAULT_STRATEGY_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, default response for this contract
+                to_binary(&self.borrower_action).unwrap(),
             ),
             (
-                &ANCHOR_MARKET_CONTRACT.to_string(),
-                &to_binary(&AnchorMarketQueryMsg::EpochState { block_height: None }).unwrap(),
-                &to_binary(&AnchorMarketEpochStateResponse {
+                ANCHOR_MARKET_CONTRACT.to_string(),
+                to_binary(&AnchorMarketQueryMsg::EpochState { block_height: None }).unwrap(),
+                to_binary(&AnchorMarketEpochStateResponse {
                     exchange_rate: self.aterra_exchange_rate,
                     aterra_supply: Uint256::from(1_000_000u64),
                 })
                 .unwrap(),
             ),
             (
-                &ANCHOR_MARKET_CONTRACT.to_string(),
-                &to_binary(&AnchorMarketQueryMsg::BorrowerInfo {
+                ANCHOR_INTEREST_MODEL_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(&BorrowRateResponse {
+                    rate: self.borrow_rate,
+                })
+                .unwrap(),
+            ),
+            (
+                ANCHOR_OVERSEER_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(&AnchorOverseerEpochStateResponse {
+                    deposit_rate: self.deposit_rate,
+                })
+                .unwrap(),
+            ),
+            (
+                PSI_DISTRIBUTOR_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(&PsiDistributorConfigResponse {
+                    psi_token_addr: PSI_TOKEN.to_string(),
+                    governance_contract_addr: GOVERNANCE_CONTRACT.to_string(),
+                    nasset_token_rewards_contract_addr: NASSET_TOKEN_REWARDS_CONTRACT.to_string(),
+                    community_pool_contract_addr: COMMUNITY_POOL_CONTRACT_ADDR.to_string(),
+                    basset_vault_strategy_contract_addr: BASSET_VAULT_STRATEGY_CONTRACT.to_string(),
+                    nasset_psi_swap_contract_addr: NASSET_PSI_SWAP_CONTRACT_ADDR.to_string(),
+                    manual_ltv: Decimal256::from_str(MANUAL_LTV).unwrap(),
+                    fee_rate: Decimal256::from_str(FEE_RATE).unwrap(),
+                    tax_rate: Decimal256::from_str(TAX_RATE).unwrap(),
+                })
+                .unwrap(),
+            ),
+            (
+                ANCHOR_MARKET_CONTRACT.to_string(),
+                to_binary(&AnchorMarketQueryMsg::BorrowerInfo {
                     borrower: MOCK_CONTRACT_ADDR.to_string(),
                     block_height: None,
                 })
                 .unwrap(),
-                &to_binary(&AnchorMarketBorrowerInfo {
+                to_binary(&AnchorMarketBorrowerInfo {
                     borrower: MOCK_CONTRACT_ADDR.to_string(),
-                    loan_amount: Uint256::zero(),
+                    loan_amount: self.loan,
                     pending_rewards: self.anc_pending_rewards,
                 })
                 .unwrap(),
             ),
-        ]);
+        ];
+
+        if let Some(strategy_config) = &self.strategy_config {
+            responses.push((
+                BASSET_VAULT_STRATEGY_CONTRACT.to_string(),
+                to_binary(&StrategyQueryMsg::Config {}).unwrap(),
+                to_binary(strategy_config).unwrap(),
+            ));
+        }
+
+        if let Some(rate) = self.strategy_fresh_price {
+            responses.push((
+                BASSET_VAULT_STRATEGY_CONTRACT.to_string(),
+                to_binary(&StrategyQueryMsg::FreshPrice {}).unwrap(),
+                to_binary(&StrategyFreshPriceResponse { rate }).unwrap(),
+            ));
+        }
+
+        if let (Some(strategy_config), Some(oracle_price)) =
+            (&self.strategy_config, &self.oracle_price)
+        {
+            responses.push((
+                strategy_config.oracle_contract.clone(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(oracle_price).unwrap(),
+            ));
+        }
+
+        if let Some(anc_swap_simulation) = &self.anc_swap_simulation {
+            responses.push((
+                ANC_STABLE_SWAP_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(anc_swap_simulation).unwrap(),
+            ));
+        }
+
+        if let Some(psi_swap_simulation) = &self.psi_swap_simulation {
+            responses.push((
+                PSI_STABLE_SWAP_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(psi_swap_simulation).unwrap(),
+            ));
+        }
+
+        if let Some(basset_swap_simulation) = &self.basset_swap_simulation {
+            responses.push((
+                BASSET_STABLE_SWAP_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(basset_swap_simulation).unwrap(),
+            ));
+        }
+
+        if let Some(router_simulation) = &self.router_simulation {
+            responses.push((
+                ROUTER_CONTRACT.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(router_simulation).unwrap(),
+            ));
+        }
+
+        if let Some(anc_twap_return_amount) = &self.anc_twap_return_amount {
+            responses.push((
+                ANC_STABLE_TWAP_ORACLE.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(anc_twap_return_amount).unwrap(),
+            ));
+        }
+
+        if let Some(psi_twap_return_amount) = &self.psi_twap_return_amount {
+            responses.push((
+                PSI_STABLE_TWAP_ORACLE.to_string(),
+                to_binary(&0u64).unwrap(), //fake key, cause only one msg for this contract
+                to_binary(psi_twap_return_amount).unwrap(),
+            ));
+        }
+
+        let responses: Vec<(&String, &Binary, &Binary)> = responses
+            .iter()
+            .map(|(contract, request, response)| (contract, request, response))
+            .collect();
+        self.deps.querier.with_wasm_query_response(&responses);
+    }
+
+    pub fn set_strategy_config(&mut self, value: StrategyConfigResponse) {
+        self.strategy_config = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_oracle_price(&mut self, value: PriceResponse) {
+        self.oracle_price = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_strategy_fresh_price(&mut self, rate: Decimal256) {
+        self.strategy_fresh_price = Some(rate);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_strategy_price_is_stale(&mut self) {
+        self.deps.querier.with_wasm_query_error(
+            BASSET_VAULT_STRATEGY_CONTRACT,
+            to_binary(&StrategyQueryMsg::FreshPrice {}).unwrap(),
+            "price is stale",
+        );
+    }
+
+    pub fn set_anc_swap_simulation(&mut self, value: SimulationResponse) {
+        self.anc_swap_simulation = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_psi_swap_simulation(&mut self, value: SimulationResponse) {
+        self.psi_swap_simulation = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_basset_swap_simulation(&mut self, value: SimulationResponse) {
+        self.basset_swap_simulation = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_router_simulation(&mut self, value: SimulateSwapOperationsResponse) {
+        self.router_simulation = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    /// Sets TWAP oracles for reward swaps in vault config
+    pub fn set_twap_oracles(&mut self) {
+        let msg = ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdateConfig {
+                psi_distributor_addr: None,
+                anchor_overseer_contract_addr: None,
+                anchor_market_contract_addr: None,
+                anchor_custody_basset_contract_addr: None,
+                anc_stable_swap_contract_addr: None,
+                psi_stable_swap_contract_addr: None,
+                basset_vault_strategy_contract_addr: None,
+                claiming_rewards_delay: None,
+                over_loan_balance_value: None,
+                anc_stable_max_spread: None,
+                psi_stable_max_spread: None,
+                anc_stable_twap_oracle_addr: Some(ANC_STABLE_TWAP_ORACLE.to_string()),
+                psi_stable_twap_oracle_addr: Some(PSI_STABLE_TWAP_ORACLE.to_string()),
+            },
+        };
+        let info = mock_info(GOVERNANCE_CONTRACT, &[]);
+        crate::contract::execute(self.deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    pub fn set_anc_twap_return_amount(&mut self, value: Uint256) {
+        self.anc_twap_return_amount = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_psi_twap_return_amount(&mut self, value: Uint256) {
+        self.psi_twap_return_amount = Some(value);
+        self.set_wasm_query_respones();
+    }
+
+    pub fn set_anchor_rates(&mut self, borrow_rate: Decimal256, deposit_rate: Decimal256) {
+        self.borrow_rate = borrow_rate;
+        self.deposit_rate = deposit_rate;
+        self.set_wasm_query_respones();
     }
 
     pub fn set_collateral_balance(&mut self, balance: Uint256) {
@@ -549,6 +799,13 @@ impl Sdk {
         self.set_token_balances();
     }
 
+    pub fn set_nasset_balance(&mut self, address: &str, value: Uint256) {
+        self.nasset_balances.retain(|(addr, _)| addr != address);
+        self.nasset_balances
+            .push((address.to_string(), value.into()));
+        self.set_token_balances();
+    }
+
     pub fn set_anc_pending_rewards(&mut self, value: Decimal256) {
         self.anc_pending_rewards = value;
         self.set_wasm_query_respones();
@@ -561,6 +818,11 @@ impl Sdk {
     }
 
     fn set_token_balances(&mut self) {
+        let nasset_balances: Vec<(&String, &Uint128)> = self
+            .nasset_balances
+            .iter()
+            .map(|(addr, balance)| (addr, balance))
+            .collect();
         self.deps.querier.with_token_balances(&[
             (
                 &ATERRA_TOKEN.to_string(),
@@ -574,10 +836,16 @@ impl Sdk {
                 &ANCHOR_TOKEN.to_string(),
                 &[(&MOCK_CONTRACT_ADDR.to_string(), &self.anc_balance)],
             ),
+            (&NASSET_TOKEN_ADDR.to_string(), &nasset_balances[..]),
         ]);
     }
 
-    pub fn rebalance(&mut self) -> StdResult<Response<Empty>> {
+    pub fn query<T: DeserializeOwned>(&self, msg: QueryMsg) -> StdResult<T> {
+        let binary = crate::contract::query(self.deps.as_ref(), mock_env(), msg)?;
+        from_binary(&binary)
+    }
+
+    pub fn rebalance(&mut self) -> ContractResult<Response<Empty>> {
         let rebalance_msg = basset_vault::basset_vault::AnyoneMsg::Rebalance {};
         let info = mock_info(&"addr9999".to_string(), &vec![]);
         crate::contract::execute(
@@ -590,6 +858,37 @@ impl Sdk {
         )
     }
 
+    pub fn set_market_liquidity(&mut self, market_balance: Uint128, total_reserves: Decimal256) {
+        self.deps.querier.update_base_balance(
+            ANCHOR_MARKET_CONTRACT,
+            vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: market_balance,
+            }],
+        );
+        self.deps
+            .querier
+            .with_market_state(AnchorMarketStateResponse {
+                total_liabilities: Decimal256::zero(),
+                total_reserves,
+                anc_emission_rate: Decimal256::zero(),
+            });
+    }
+
+    pub fn set_anc_emission(
+        &mut self,
+        total_liabilities: Decimal256,
+        anc_emission_rate: Decimal256,
+    ) {
+        self.deps
+            .querier
+            .with_market_state(AnchorMarketStateResponse {
+                total_liabilities,
+                total_reserves: Decimal256::zero(),
+                anc_emission_rate,
+            });
+    }
+
     pub fn set_stable_balance(&mut self, value: Uint128) {
         self.deps.querier.update_base_balance(
             MOCK_CONTRACT_ADDR,
@@ -600,7 +899,7 @@ impl Sdk {
         );
     }
 
-    pub fn aterra_redeem_success(&mut self) -> StdResult<Response<Empty>> {
+    pub fn aterra_redeem_success(&mut self) -> ContractResult<Response<Empty>> {
         let reply_msg = Reply {
             id: SubmsgIds::RedeemStableOnRepayLoan.id(),
             result: cosmwasm_std::ContractResult::Ok(SubMsgExecutionResponse {
@@ -613,19 +912,16 @@ impl Sdk {
         crate::contract::reply(self.deps.as_mut(), mock_env(), reply_msg)
     }
 
-    pub fn aterra_redeed_failed(&mut self) -> StdResult<Response<Empty>> {
+    pub fn aterra_redeed_failed(&mut self) -> ContractResult<Response<Empty>> {
         let reply_msg = Reply {
             id: SubmsgIds::RedeemStableOnRepayLoan.id(),
-            result: cosmwasm_std::ContractResult::Err(format!(
-                "fail to redeem aterra, cause: {}",
-                TOO_HIGH_BORROW_DEMAND_ERR_MSG,
-            )),
+            result: cosmwasm_std::ContractResult::Err("fail to redeem aterra".to_string()),
         };
 
         crate::contract::reply(self.deps.as_mut(), mock_env(), reply_msg)
     }
 
-    pub fn continue_repay_loan(&mut self) -> StdResult<Response<Empty>> {
+    pub fn continue_repay_loan(&mut self) -> ContractResult<Response<Empty>> {
         let reply_msg = Reply {
             id: SubmsgIds::RepayLoan.id(),
             result: cosmwasm_std::ContractResult::Ok(SubMsgExecutionResponse {
@@ -637,39 +933,115 @@ impl Sdk {
         crate::contract::reply(self.deps.as_mut(), mock_env(), reply_msg)
     }
 
-    pub fn user_deposit(&mut self, address: &str, amount: Uint128) -> StdResult<Response<Empty>> {
-        let cw20_deposit_msg = Cw20ReceiveMsg {
-            sender: address.to_string(),
-            amount,
-            msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    pub fn user_deposit(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+    ) -> ContractResult<Response<Empty>> {
+        self.user_deposit_guarded(address, amount, None, None)
+    }
+
+    pub fn user_deposit_guarded(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+        min_nasset_out: Option<Uint256>,
+        deadline: Option<u64>,
+    ) -> ContractResult<Response<Empty>> {
+        let deposit_msg = Cw20HookMsg::Deposit {
+            min_nasset_out,
+            deadline,
+            recipient: None,
+        };
+        self.send_cw20_hook(BASSET_TOKEN_ADDR, address, amount, deposit_msg)
+    }
+
+    pub fn user_deposit_to(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+        recipient: &str,
+    ) -> ContractResult<Response<Empty>> {
+        let deposit_msg = Cw20HookMsg::Deposit {
+            min_nasset_out: None,
+            deadline: None,
+            recipient: Some(recipient.to_string()),
         };
+        self.send_cw20_hook(BASSET_TOKEN_ADDR, address, amount, deposit_msg)
+    }
 
-        let info = mock_info(BASSET_TOKEN_ADDR, &vec![]);
-        crate::contract::execute(
-            self.deps.as_mut(),
-            mock_env(),
-            info,
-            ExecuteMsg::Receive(cw20_deposit_msg),
-        )
+    pub fn user_withdraw(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+    ) -> ContractResult<Response<Empty>> {
+        self.user_withdraw_guarded(address, amount, None, None)
     }
 
-    pub fn user_withdraw(&mut self, address: &str, amount: Uint128) -> StdResult<Response<Empty>> {
-        let cw20_deposit_msg = Cw20ReceiveMsg {
+    pub fn user_withdraw_guarded(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+        min_basset_out: Option<Uint256>,
+        deadline: Option<u64>,
+    ) -> ContractResult<Response<Empty>> {
+        let withdraw_msg = Cw20HookMsg::Withdraw {
+            min_basset_out,
+            deadline,
+            recipient: None,
+            msg: None,
+        };
+        self.send_cw20_hook(NASSET_TOKEN_ADDR, address, amount, withdraw_msg)
+    }
+
+    pub fn user_withdraw_to(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+        recipient: &str,
+        msg: Option<Binary>,
+    ) -> ContractResult<Response<Empty>> {
+        let withdraw_msg = Cw20HookMsg::Withdraw {
+            min_basset_out: None,
+            deadline: None,
+            recipient: Some(recipient.to_string()),
+            msg,
+        };
+        self.send_cw20_hook(NASSET_TOKEN_ADDR, address, amount, withdraw_msg)
+    }
+
+    pub fn user_request_withdraw(
+        &mut self,
+        address: &str,
+        amount: Uint128,
+    ) -> ContractResult<Response<Empty>> {
+        let request_msg = Cw20HookMsg::RequestWithdraw { recipient: None };
+        self.send_cw20_hook(NASSET_TOKEN_ADDR, address, amount, request_msg)
+    }
+
+    fn send_cw20_hook(
+        &mut self,
+        token: &str,
+        address: &str,
+        amount: Uint128,
+        hook_msg: Cw20HookMsg,
+    ) -> ContractResult<Response<Empty>> {
+        let cw20_msg = Cw20ReceiveMsg {
             sender: address.to_string(),
             amount,
-            msg: to_binary(&Cw20HookMsg::Withdraw {}).unwrap(),
+            msg: to_binary(&hook_msg).unwrap(),
         };
 
-        let info = mock_info(NASSET_TOKEN_ADDR, &vec![]);
+        let info = mock_info(token, &[]);
         crate::contract::execute(
             self.deps.as_mut(),
             mock_env(),
             info,
-            ExecuteMsg::Receive(cw20_deposit_msg),
+            ExecuteMsg::Receive(cw20_msg),
         )
     }
 
-    pub fn user_send_honest_work(&mut self) -> StdResult<Response<Empty>> {
+    pub fn user_send_honest_work(&mut self) -> ContractResult<Response<Empty>> {
         let honest_work_msg = basset_vault::basset_vault::AnyoneMsg::HonestWork {};
         let env = mock_env();
         let info = mock_info(&"addr9999".to_string(), &vec![]);
@@ -683,7 +1055,7 @@ impl Sdk {
         )
     }
 
-    pub fn send_swap_anc(&mut self) -> StdResult<Response<Empty>> {
+    pub fn send_swap_anc(&mut self) -> ContractResult<Response<Empty>> {
         let info = mock_info(MOCK_CONTRACT_ADDR, &vec![]);
         crate::contract::execute(
             self.deps.as_mut(),
@@ -695,7 +1067,7 @@ impl Sdk {
         )
     }
 
-    pub fn send_distribute_rewards(&mut self) -> StdResult<Response<Empty>> {
+    pub fn send_distribute_rewards(&mut self) -> ContractResult<Response<Empty>> {
         let info = mock_info(MOCK_CONTRACT_ADDR, &vec![]);
         crate::contract::execute(
             self.deps.as_mut(),
@@ -706,4 +1078,40 @@ impl Sdk {
             },
         )
     }
+
+    pub fn send_process_withdrawal(&mut self) -> ContractResult<Response<Empty>> {
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        crate::contract::execute(
+            self.deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Yourself {
+                yourself_msg: YourselfMsg::ProcessWithdrawal {},
+            },
+        )
+    }
+
+    pub fn emergency_deleverage(&mut self) -> ContractResult<Response<Empty>> {
+        let info = mock_info("addr9999", &[]);
+        crate::contract::execute(
+            self.deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Anyone {
+                anyone_msg: basset_vault::basset_vault::AnyoneMsg::EmergencyDeleverage {},
+            },
+        )
+    }
+
+    pub fn send_repay_after_deleverage(&mut self) -> ContractResult<Response<Empty>> {
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        crate::contract::execute(
+            self.deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Yourself {
+                yourself_msg: YourselfMsg::RepayAfterDeleverage {},
+            },
+        )
+    }
 }
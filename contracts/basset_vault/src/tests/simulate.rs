@@ -0,0 +1,148 @@
+use super::sdk::Sdk;
+use crate::tests::sdk::{ANCHOR_MARKET_CONTRACT, OVER_LOAN_BALANCE_VALUE, STABLE_DENOM};
+use crate::MIN_ANC_REWARDS_TO_CLAIM;
+use basset_vault::astroport_pair::SimulationResponse;
+use basset_vault::basset_vault::{
+    QueryMsg, RebalanceResponse, SimulateHonestWorkResponse, SimulateRebalanceResponse,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use basset_vault::querier::AnchorMarketMsg;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, to_binary, Coin, CosmosMsg, Response, Uint128, WasmMsg};
+use std::str::FromStr;
+
+fn messages(response: Response) -> Vec<CosmosMsg> {
+    response.messages.into_iter().map(|m| m.msg).collect()
+}
+
+#[test]
+fn simulate_rebalance_nothing() {
+    let mut sdk = Sdk::init();
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    let simulation: SimulateRebalanceResponse = sdk.query(QueryMsg::SimulateRebalance {}).unwrap();
+    assert_eq!(
+        SimulateRebalanceResponse {
+            rebalance: RebalanceResponse::Nothing {},
+            unwinding: false,
+            skipped: None,
+            keeper_reward: Uint256::zero(),
+            messages: vec![],
+            attributes: vec![attr("action", "rebalance_not_needed")],
+        },
+        simulation
+    );
+}
+
+#[test]
+fn simulate_rebalance_repay_equals_execution() {
+    let mut sdk = Sdk::init();
+    sdk.set_stable_balance(Uint128::new(200));
+    sdk.set_aterra_balance(Uint256::from(200u64));
+    sdk.set_aterra_exchange_rate(Decimal256::from_str("1.2").unwrap());
+    sdk.set_tax(Decimal256::from_str("0.2").unwrap().into(), 10u128);
+    sdk.set_borrower_action(BorrowerActionResponse::Repay {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(50u64),
+        price_is_stale: false,
+    });
+
+    let simulation: SimulateRebalanceResponse = sdk.query(QueryMsg::SimulateRebalance {}).unwrap();
+    let response = sdk.rebalance().unwrap();
+
+    assert_eq!(
+        RebalanceResponse::Repay {
+            amount: Uint256::from(10_000u64),
+            advised_buffer_size: Uint256::from(50u64),
+        },
+        simulation.rebalance
+    );
+    assert_eq!(messages(response.clone()), simulation.messages);
+    assert_eq!(response.attributes, simulation.attributes);
+}
+
+#[test]
+fn simulate_rebalance_borrow_with_deposit_after_borrow() {
+    let mut sdk = Sdk::init();
+    sdk.set_stable_balance(Uint128::new(500));
+    sdk.set_tax(Decimal256::zero().into(), 0);
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(10_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+
+    let simulation: SimulateRebalanceResponse = sdk.query(QueryMsg::SimulateRebalance {}).unwrap();
+    let response = sdk.rebalance().unwrap();
+
+    //borrow message is the same, then deposit all above buffer to Anchor
+    let mut expected_messages = messages(response);
+    expected_messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+        msg: to_binary(&AnchorMarketMsg::DepositStable {}).unwrap(),
+        funds: vec![Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: Uint128::new(9_500),
+        }],
+    }));
+    assert_eq!(expected_messages, simulation.messages);
+}
+
+#[test]
+fn simulate_honest_work_not_claimable() {
+    let mut sdk = Sdk::init();
+    sdk.set_anc_pending_rewards(Decimal256::zero());
+
+    let simulation: SimulateHonestWorkResponse =
+        sdk.query(QueryMsg::SimulateHonestWork {}).unwrap();
+    assert!(!simulation.claimable);
+    assert!(simulation.messages.is_empty());
+}
+
+#[test]
+fn simulate_honest_work_equals_execution() {
+    let mut sdk = Sdk::init();
+
+    let stable_coin_balance = Uint128::new(5_000_000);
+    let stables_from_selling_anc = Uint128::new(1_000_000);
+    let over_loan_balance_value = Decimal256::from_str(OVER_LOAN_BALANCE_VALUE).unwrap();
+    sdk.set_anc_pending_rewards(Decimal256::from_uint256(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.set_stable_balance(stable_coin_balance);
+    sdk.set_loan(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_balance(Uint256::from(stable_coin_balance));
+    sdk.set_aterra_exchange_rate(over_loan_balance_value);
+    sdk.set_tax(Decimal256::zero().into(), 0);
+    sdk.set_anc_swap_simulation(SimulationResponse {
+        return_amount: stables_from_selling_anc,
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+    sdk.set_psi_swap_simulation(SimulationResponse {
+        return_amount: Uint128::new(2_000_000),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+    });
+
+    let simulation: SimulateHonestWorkResponse =
+        sdk.query(QueryMsg::SimulateHonestWork {}).unwrap();
+    assert!(simulation.claimable);
+    assert_eq!(
+        Uint256::from(MIN_ANC_REWARDS_TO_CLAIM),
+        simulation.anc_amount
+    );
+    assert_eq!(
+        Uint256::from(stables_from_selling_anc),
+        simulation.expected_stables
+    );
+
+    //claimed ANC is swapped and profit is distributed
+    sdk.set_anc_balance(Uint256::from(MIN_ANC_REWARDS_TO_CLAIM));
+    sdk.send_swap_anc().unwrap();
+    sdk.set_stable_balance(stable_coin_balance + stables_from_selling_anc);
+    let response = sdk.send_distribute_rewards().unwrap();
+
+    assert_eq!(messages(response.clone()), simulation.messages);
+    assert_eq!(response.attributes, simulation.attributes);
+}
@@ -0,0 +1,43 @@
+use super::sdk::Sdk;
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, Uint128};
+
+#[test]
+fn rebalance_marks_decision_made_on_stale_price() {
+    let mut sdk = Sdk::init();
+
+    sdk.set_borrower_action(BorrowerActionResponse::nothing().with_stale_price());
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        vec![
+            attr("action", "rebalance_not_needed"),
+            attr("price_is_stale", "true")
+        ],
+        response.attributes
+    );
+
+    sdk.set_stable_balance(Uint128::new(200));
+    sdk.set_aterra_balance(Uint256::from(200u64));
+    sdk.set_aterra_exchange_rate(Decimal256::one());
+    sdk.set_borrower_action(
+        BorrowerActionResponse::repay(Uint256::from(10_000u64), Uint256::from(50u64))
+            .with_stale_price(),
+    );
+    let response = sdk.rebalance().unwrap();
+    assert!(response
+        .attributes
+        .contains(&attr("price_is_stale", "true")));
+}
+
+#[test]
+fn rebalance_on_fresh_price_is_not_marked() {
+    let mut sdk = Sdk::init();
+
+    sdk.set_borrower_action(BorrowerActionResponse::nothing());
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        vec![attr("action", "rebalance_not_needed")],
+        response.attributes
+    );
+}
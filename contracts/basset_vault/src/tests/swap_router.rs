@@ -0,0 +1,219 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::{
+    ANCHOR_TOKEN, GOVERNANCE_CONTRACT, PSI_TOKEN, ROUTER_CONTRACT, STABLE_DENOM,
+};
+use basset_vault::astroport_router::{
+    Cw20HookMsg as AstroportRouterCw20HookMsg, SimulateSwapOperationsResponse, SwapOperation,
+};
+use basset_vault::basset_vault::{ExecuteMsg, GovernanceMsg, QueryMsg, SwapRouterConfigResponse};
+use basset_vault::terraswap::AssetInfo;
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Response, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+fn update_swap_router_config(
+    sdk: &mut Sdk,
+    sender: &str,
+    anc_route: Option<Vec<SwapOperation>>,
+    psi_route: Option<Vec<SwapOperation>>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateSwapRouterConfig {
+            router_contract_addr: Some(ROUTER_CONTRACT.to_string()),
+            anc_route,
+            psi_route,
+        },
+    };
+    let info = mock_info(sender, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+fn anc_asset() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: Addr::unchecked(ANCHOR_TOKEN),
+    }
+}
+
+fn psi_asset() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: Addr::unchecked(PSI_TOKEN),
+    }
+}
+
+fn stable_asset() -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: STABLE_DENOM.to_string(),
+    }
+}
+
+//ANC -> Psi -> UST
+fn anc_route() -> Vec<SwapOperation> {
+    vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: anc_asset(),
+            ask_asset_info: psi_asset(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: psi_asset(),
+            ask_asset_info: stable_asset(),
+        },
+    ]
+}
+
+#[test]
+fn fail_to_update_swap_router_config_if_sender_is_not_governance() {
+    let mut sdk = Sdk::init();
+
+    let res = update_swap_router_config(&mut sdk, "addr0001", Some(anc_route()), None);
+    assert!(res.is_err());
+}
+
+#[test]
+fn fail_to_set_route_with_wrong_ends() {
+    let mut sdk = Sdk::init();
+
+    //ANC route should end with stables
+    let res = update_swap_router_config(
+        &mut sdk,
+        GOVERNANCE_CONTRACT,
+        Some(vec![SwapOperation::AstroSwap {
+            offer_asset_info: anc_asset(),
+            ask_asset_info: psi_asset(),
+        }]),
+        None,
+    );
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    //Psi route should start with stables
+    let res = update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, None, Some(anc_route()));
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
+
+#[test]
+fn fail_to_set_broken_route() {
+    let mut sdk = Sdk::init();
+
+    let res = update_swap_router_config(
+        &mut sdk,
+        GOVERNANCE_CONTRACT,
+        Some(vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: anc_asset(),
+                ask_asset_info: psi_asset(),
+            },
+            SwapOperation::NativeSwap {
+                offer_denom: "uluna".to_string(),
+                ask_denom: STABLE_DENOM.to_string(),
+            },
+        ]),
+        None,
+    );
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
+
+#[test]
+fn update_swap_router_config_and_query_it() {
+    let mut sdk = Sdk::init();
+
+    let router_config: SwapRouterConfigResponse = sdk.query(QueryMsg::SwapRouterConfig {}).unwrap();
+    assert_eq!(
+        SwapRouterConfigResponse {
+            router_contract_addr: None,
+            anc_route: vec![],
+            psi_route: vec![],
+        },
+        router_config
+    );
+
+    update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, Some(anc_route()), None).unwrap();
+    let router_config: SwapRouterConfigResponse = sdk.query(QueryMsg::SwapRouterConfig {}).unwrap();
+    assert_eq!(
+        SwapRouterConfigResponse {
+            router_contract_addr: Some(ROUTER_CONTRACT.to_string()),
+            anc_route: anc_route(),
+            psi_route: vec![],
+        },
+        router_config
+    );
+
+    //empty route switches ANC back to pair
+    update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, Some(vec![]), None).unwrap();
+    let router_config: SwapRouterConfigResponse = sdk.query(QueryMsg::SwapRouterConfig {}).unwrap();
+    assert!(router_config.anc_route.is_empty());
+}
+
+#[test]
+fn swap_anc_through_router() {
+    let mut sdk = Sdk::init();
+    update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, Some(anc_route()), None).unwrap();
+
+    let anc_balance = Uint256::from(1_000u64);
+    sdk.set_anc_balance(anc_balance);
+    sdk.set_stable_balance(Uint128::zero());
+    sdk.set_router_simulation(SimulateSwapOperationsResponse {
+        amount: Uint128::new(2_000),
+    });
+
+    let response = sdk.send_swap_anc().unwrap();
+    assert_eq!(2, response.messages.len());
+    assert_eq!(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANCHOR_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: ROUTER_CONTRACT.to_string(),
+                amount: anc_balance.into(),
+                msg: to_binary(&AstroportRouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: anc_route(),
+                    //1% max spread
+                    minimum_receive: Some(Uint128::new(1_980)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }),
+        response.messages[0].msg
+    );
+}
+
+#[test]
+fn skip_swap_anc_if_router_returns_nothing() {
+    let mut sdk = Sdk::init();
+    update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, Some(anc_route()), None).unwrap();
+
+    sdk.set_anc_balance(Uint256::from(1_000u64));
+    sdk.set_stable_balance(Uint128::zero());
+    sdk.set_router_simulation(SimulateSwapOperationsResponse {
+        amount: Uint128::zero(),
+    });
+
+    let response = sdk.send_swap_anc().unwrap();
+    assert!(response.messages.is_empty());
+}
+
+#[test]
+fn skip_swap_anc_if_router_price_is_far_from_twap() {
+    let mut sdk = Sdk::init();
+    sdk.set_twap_oracles();
+    update_swap_router_config(&mut sdk, GOVERNANCE_CONTRACT, Some(anc_route()), None).unwrap();
+
+    sdk.set_anc_balance(Uint256::from(1_000u64));
+    sdk.set_stable_balance(Uint128::zero());
+    sdk.set_router_simulation(SimulateSwapOperationsResponse {
+        amount: Uint128::new(1_940),
+    });
+    sdk.set_anc_twap_return_amount(Uint256::from(2_000u64));
+
+    let response = sdk.send_swap_anc().unwrap();
+    assert!(response.messages.is_empty());
+
+    //within 1% max spread from TWAP
+    sdk.set_router_simulation(SimulateSwapOperationsResponse {
+        amount: Uint128::new(1_990),
+    });
+    let response = sdk.send_swap_anc().unwrap();
+    assert_eq!(2, response.messages.len());
+}
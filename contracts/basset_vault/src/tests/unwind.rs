@@ -0,0 +1,198 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::state::{load_rebalance_reward_reserve, store_rebalance_reward_reserve};
+use crate::tests::sdk::{
+    ANCHOR_MARKET_CONTRACT, ATERRA_TOKEN, BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, STABLE_DENOM,
+};
+use crate::SubmsgIds;
+use basset_vault::basset_vault::{
+    AnyoneMsg, ExecuteMsg, GovernanceMsg, QueryMsg, UnwindStatusResponse,
+};
+use basset_vault::querier::{AnchorMarketCw20Msg, AnchorMarketMsg};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Response, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+fn start_unwind(sdk: &mut Sdk) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::Unwind {},
+    };
+    let info = mock_info(GOVERNANCE_CONTRACT, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+#[test]
+fn fail_to_unwind_if_sender_is_not_governance() {
+    let mut sdk = Sdk::init();
+
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::Unwind {},
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg);
+    assert!(res.is_err());
+
+    let unwind_status: UnwindStatusResponse = sdk.query(QueryMsg::UnwindStatus {}).unwrap();
+    assert!(!unwind_status.unwinding);
+}
+
+#[test]
+fn unwind_repays_whole_loan() {
+    let mut sdk = Sdk::init();
+    let loan = Uint256::from(1_000u64);
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_loan(loan);
+    sdk.set_stable_balance(Uint128::new(5_000));
+    sdk.set_aterra_balance(Uint256::from(10_000u64));
+    sdk.set_aterra_exchange_rate(Decimal256::one());
+
+    let response = start_unwind(&mut sdk).unwrap();
+    assert_eq!(
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+                msg: to_binary(&AnchorMarketMsg::RepayStable {}).unwrap(),
+                funds: vec![Coin {
+                    denom: STABLE_DENOM.to_string(),
+                    amount: loan.into(),
+                }],
+            },
+            SubmsgIds::RepayLoan.id(),
+        )],
+        response.messages
+    );
+    assert!(response.attributes.contains(&attr("unwind", "repay_loan")));
+
+    let unwind_status: UnwindStatusResponse = sdk.query(QueryMsg::UnwindStatus {}).unwrap();
+    assert_eq!(
+        UnwindStatusResponse {
+            unwinding: true,
+            completed: false,
+            loan_amount: loan,
+            aterra_balance: Uint256::from(10_000u64),
+        },
+        unwind_status
+    );
+
+    //deposits, withdrawals and claiming remainder are not available
+    sdk.set_basset_balance(Uint256::from(100u64));
+    let res = sdk.user_deposit("addr0001", Uint128::new(100));
+    assert_eq!(Err(ContractError::Unwinding), res);
+
+    sdk.set_nasset_supply(Uint256::from(100u64));
+    sdk.set_collateral_balance(Uint256::from(100u64));
+    let res = sdk.user_withdraw("addr0001", Uint128::new(100));
+    assert_eq!(Err(ContractError::UnwindNotCompleted), res);
+
+    let msg = ExecuteMsg::Anyone {
+        anyone_msg: AnyoneMsg::ClaimRemainder {},
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(Err(ContractError::Unwinding), res);
+}
+
+#[test]
+fn unwind_redeems_all_aterra_after_loan_repaid() {
+    let mut sdk = Sdk::init();
+    let aterra_balance = Uint256::from(10_000u64);
+    sdk.set_aterra_balance(aterra_balance);
+    start_unwind(&mut sdk).unwrap();
+
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: ATERRA_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: ANCHOR_MARKET_CONTRACT.to_string(),
+                amount: aterra_balance.into(),
+                msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })],
+        response.messages
+    );
+
+    sdk.set_aterra_balance(Uint256::zero());
+    let response = sdk.rebalance().unwrap();
+    assert!(response.messages.is_empty());
+    assert_eq!(
+        vec![attr("action", "unwind"), attr("status", "completed")],
+        response.attributes
+    );
+
+    let unwind_status: UnwindStatusResponse = sdk.query(QueryMsg::UnwindStatus {}).unwrap();
+    assert!(unwind_status.completed);
+}
+
+#[test]
+fn withdraw_stables_share_after_unwind() {
+    let mut sdk = Sdk::init();
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_aterra_balance(Uint256::zero());
+    start_unwind(&mut sdk).unwrap();
+
+    let user_address = "addr6666";
+    sdk.set_nasset_supply(Uint256::from(4_000u64));
+    sdk.set_collateral_balance(Uint256::from(8_000u64));
+    sdk.set_stable_balance(Uint128::new(1_000));
+
+    let response = sdk
+        .user_withdraw(user_address, Uint128::new(1_000))
+        .unwrap();
+    assert_eq!(5, response.messages.len());
+    assert_eq!(
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: user_address.to_string(),
+            amount: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: Uint128::new(250),
+            }],
+        })),
+        response.messages[0]
+    );
+    assert_eq!(
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: BASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: user_address.to_string(),
+                amount: Uint128::new(2_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        }),
+        response.messages[3]
+    );
+}
+
+#[test]
+fn rewards_stay_in_vault_while_unwinding() {
+    let mut sdk = Sdk::init();
+    start_unwind(&mut sdk).unwrap();
+
+    let response = sdk.send_distribute_rewards().unwrap();
+    assert!(response.messages.is_empty());
+    assert_eq!(
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("skipped", "unwinding")
+        ],
+        response.attributes
+    );
+}
+
+#[test]
+fn rebalance_reward_reserve_goes_to_nasset_holders_on_unwind() {
+    let mut sdk = Sdk::init();
+    store_rebalance_reward_reserve(sdk.deps.as_mut().storage, &Uint256::from(500u64)).unwrap();
+
+    start_unwind(&mut sdk).unwrap();
+    assert_eq!(
+        Uint256::zero(),
+        load_rebalance_reward_reserve(sdk.deps.as_ref().storage).unwrap()
+    );
+}
@@ -0,0 +1,182 @@
+use super::sdk::Sdk;
+use crate::tests::sdk::NASSET_TOKEN_ADDR;
+use basset_vault::basset_vault::{
+    ConvertToAssetsResponse, ConvertToSharesResponse, MaxDepositResponse, MaxWithdrawResponse,
+    PreviewDepositResponse, PreviewWithdrawResponse, QueryMsg, TotalAssetsResponse,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{from_binary, CosmosMsg, Response, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+fn minted_nasset(response: &Response) -> Uint256 {
+    response
+        .messages
+        .iter()
+        .find_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) if contract_addr == NASSET_TOKEN_ADDR => match from_binary(msg).unwrap() {
+                Cw20ExecuteMsg::Mint { amount, .. } => Some(amount.into()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn withdrawn_basset(response: &Response) -> Uint256 {
+    response
+        .messages
+        .iter()
+        .find_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(msg) {
+                Ok(Cw20ExecuteMsg::Transfer { amount, .. }) => Some(amount.into()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn preview_deposit_equal_to_minted_nasset() {
+    let mut sdk = Sdk::init();
+
+    //first farmer come
+    let user_1_address = "addr9999".to_string();
+    let deposit_1_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    let preview: PreviewDepositResponse = sdk
+        .query(QueryMsg::PreviewDeposit {
+            basset_amount: deposit_1_amount,
+        })
+        .unwrap();
+    sdk.set_basset_balance(deposit_1_amount);
+    let response = sdk
+        .user_deposit(&user_1_address, deposit_1_amount.into())
+        .unwrap();
+    //minimum liquidity is locked on first deposit
+    assert_eq!(Uint256::from(1_999_999_000u128), preview.nasset_amount);
+    assert_eq!(minted_nasset(&response), preview.nasset_amount);
+
+    //bAsset in custody grows, and someone transfer bAsset directly to contract
+    let user_2_address = "addr6666".to_string();
+    let deposit_2_amount: Uint256 = 3_000_000_000u128.into();
+    let basset_directly_transfered: Uint256 = 500_000_000u128.into();
+    sdk.set_nasset_supply(deposit_1_amount);
+    sdk.set_collateral_balance(Uint256::from(3_000_000_000u128));
+    sdk.set_basset_balance(basset_directly_transfered);
+    let preview: PreviewDepositResponse = sdk
+        .query(QueryMsg::PreviewDeposit {
+            basset_amount: deposit_2_amount,
+        })
+        .unwrap();
+    let converted: ConvertToSharesResponse = sdk
+        .query(QueryMsg::ConvertToShares {
+            basset_amount: deposit_2_amount,
+        })
+        .unwrap();
+
+    sdk.set_basset_balance(deposit_2_amount + basset_directly_transfered);
+    let response = sdk
+        .user_deposit(&user_2_address, deposit_2_amount.into())
+        .unwrap();
+    //2_000 * 3_000 / 3_000, direct transfers are not counted
+    assert_eq!(Uint256::from(2_000_000_000u128), preview.nasset_amount);
+    assert_eq!(minted_nasset(&response), preview.nasset_amount);
+    assert_eq!(converted.nasset_amount, preview.nasset_amount);
+}
+
+#[test]
+fn preview_withdraw_equal_to_withdrawn_basset() {
+    let mut sdk = Sdk::init();
+
+    let user_address = "addr9999".to_string();
+    let nasset_supply: Uint256 = 2_000_000_000u128.into();
+    let user_nasset_balance: Uint256 = 500_000_000u128.into();
+    sdk.set_nasset_supply(nasset_supply);
+    sdk.set_nasset_balance(&user_address, user_nasset_balance);
+    sdk.set_collateral_balance(Uint256::from(3_000_000_000u128));
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    let preview: PreviewWithdrawResponse = sdk
+        .query(QueryMsg::PreviewWithdraw {
+            nasset_amount: user_nasset_balance,
+        })
+        .unwrap();
+    let converted: ConvertToAssetsResponse = sdk
+        .query(QueryMsg::ConvertToAssets {
+            nasset_amount: user_nasset_balance,
+        })
+        .unwrap();
+    let max_withdraw: MaxWithdrawResponse = sdk
+        .query(QueryMsg::MaxWithdraw {
+            address: user_address.clone(),
+        })
+        .unwrap();
+    let total_assets: TotalAssetsResponse = sdk.query(QueryMsg::TotalAssets {}).unwrap();
+
+    let response = sdk
+        .user_withdraw(&user_address, user_nasset_balance.into())
+        .unwrap();
+
+    assert_eq!(Uint256::from(750_000_000u128), preview.basset_amount);
+    assert_eq!(withdrawn_basset(&response), preview.basset_amount);
+    assert_eq!(preview.basset_amount, converted.basset_amount);
+    assert_eq!(
+        MaxWithdrawResponse {
+            nasset_amount: user_nasset_balance,
+            basset_amount: preview.basset_amount,
+        },
+        max_withdraw
+    );
+    assert_eq!(
+        TotalAssetsResponse {
+            basset_in_custody: Uint256::from(3_000_000_000u128),
+            nasset_supply,
+            unsolicited_basset: Uint256::zero(),
+        },
+        total_assets
+    );
+}
+
+#[test]
+fn frozen_vault_queries() {
+    let mut sdk = Sdk::init();
+
+    let user_address = "addr9999".to_string();
+    sdk.set_nasset_supply(Uint256::one());
+    sdk.set_nasset_balance(&user_address, Uint256::one());
+    sdk.set_collateral_balance(Uint256::zero());
+
+    let preview_deposit: Result<PreviewDepositResponse, _> = sdk.query(QueryMsg::PreviewDeposit {
+        basset_amount: Uint256::one(),
+    });
+    assert!(preview_deposit.is_err());
+    let preview_withdraw: Result<PreviewWithdrawResponse, _> =
+        sdk.query(QueryMsg::PreviewWithdraw {
+            nasset_amount: Uint256::one(),
+        });
+    assert!(preview_withdraw.is_err());
+
+    let converted: ConvertToSharesResponse = sdk
+        .query(QueryMsg::ConvertToShares {
+            basset_amount: Uint256::one(),
+        })
+        .unwrap();
+    assert_eq!(Uint256::zero(), converted.nasset_amount);
+
+    let max_deposit: MaxDepositResponse = sdk.query(QueryMsg::MaxDeposit {}).unwrap();
+    assert_eq!(Uint256::zero(), max_deposit.basset_amount);
+
+    let max_withdraw: MaxWithdrawResponse = sdk
+        .query(QueryMsg::MaxWithdraw {
+            address: user_address,
+        })
+        .unwrap();
+    assert_eq!(Uint256::zero(), max_withdraw.nasset_amount);
+    assert_eq!(Uint256::zero(), max_withdraw.basset_amount);
+}
@@ -1,11 +1,12 @@
 use super::sdk::Sdk;
+use crate::error::ContractError;
 use crate::tests::sdk::{
     ANCHOR_CUSTODY_BASSET_CONTRACT, ANCHOR_OVERSEER_CONTRACT, BASSET_TOKEN_ADDR, NASSET_TOKEN_ADDR,
 };
 use basset_vault::querier::AnchorCustodyMsg;
 use basset_vault::{basset_vault_strategy::BorrowerActionResponse, querier::AnchorOverseerMsg};
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{to_binary, WasmMsg};
+use cosmwasm_std::{testing::mock_env, to_binary, WasmMsg};
 use cosmwasm_std::{CosmosMsg, SubMsg};
 use cw20::Cw20ExecuteMsg;
 use std::str::FromStr;
@@ -34,7 +35,9 @@ fn withdraw_good_case() {
     //set basset locked in custody & borrwer action
     sdk.set_collateral_balance(deposit_1_amount + deposit_2_amount);
     sdk.set_nasset_supply(deposit_1_amount + deposit_2_amount);
-    sdk.set_borrower_action(BorrowerActionResponse::Nothing {});
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
 
     //first user withdraw
     let user_1_withdraw_response = sdk
@@ -156,7 +159,9 @@ fn withdraw_bad_case() {
     //but locked basset amount is half!
     sdk.set_collateral_balance((deposit_1_amount + deposit_2_amount) / decimal_two);
     sdk.set_nasset_supply(deposit_1_amount + deposit_2_amount);
-    sdk.set_borrower_action(BorrowerActionResponse::Nothing {});
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
 
     //first user withdraw
     let user_1_withdraw_response = sdk
@@ -272,10 +277,142 @@ fn withdraw_nasset_but_basset_balance_is_zero() {
     //set basset locked in custody & borrwer action
     sdk.set_collateral_balance(Uint256::zero());
     sdk.set_nasset_supply(deposit_amount);
-    sdk.set_borrower_action(BorrowerActionResponse::Nothing {});
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
 
     //user withdraw
     let user_withdraw_response = sdk.user_withdraw(&user_address, deposit_amount.into());
 
     assert!(user_withdraw_response.is_err());
 }
+
+#[test]
+fn withdraw_fails_if_basset_to_withdraw_less_than_min_basset_out() {
+    let mut sdk = Sdk::init();
+
+    //farmer come
+    let user_address = "addr9999".to_string();
+    let deposit_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    sdk.set_basset_balance(deposit_amount);
+    sdk.user_deposit(&user_address, deposit_amount.into())
+        .unwrap();
+
+    //we were liquidated and lost half of bAsset
+    let basset_in_custody = deposit_amount / Decimal256::from_uint256(Uint256::from(2u64));
+    sdk.set_collateral_balance(basset_in_custody);
+    sdk.set_nasset_supply(deposit_amount);
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    let response = sdk.user_withdraw_guarded(
+        &user_address,
+        deposit_amount.into(),
+        Some(deposit_amount),
+        None,
+    );
+    assert_eq!(
+        response.err().unwrap(),
+        ContractError::TooLittleBAssetOut {
+            min_basset_out: deposit_amount,
+            basset_to_withdraw: basset_in_custody,
+        }
+    );
+
+    sdk.user_withdraw_guarded(
+        &user_address,
+        deposit_amount.into(),
+        Some(basset_in_custody),
+        None,
+    )
+    .unwrap();
+}
+
+#[test]
+fn withdraw_fails_after_deadline() {
+    let mut sdk = Sdk::init();
+
+    //farmer come
+    let user_address = "addr9999".to_string();
+    let deposit_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_nasset_supply(Uint256::zero());
+    sdk.set_basset_balance(deposit_amount);
+    sdk.user_deposit(&user_address, deposit_amount.into())
+        .unwrap();
+
+    sdk.set_collateral_balance(deposit_amount);
+    sdk.set_nasset_supply(deposit_amount);
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    let current_time = mock_env().block.time.seconds();
+    let response = sdk.user_withdraw_guarded(
+        &user_address,
+        deposit_amount.into(),
+        None,
+        Some(current_time - 1),
+    );
+    assert_eq!(
+        response.err().unwrap(),
+        ContractError::DeadlineExceeded {
+            deadline: current_time - 1,
+            current_time,
+        }
+    );
+}
+
+#[test]
+fn withdraw_to_recipient_with_hook_msg() {
+    let mut sdk = Sdk::init();
+
+    let user_address = "addr9999".to_string();
+    let router_address = "addr7777".to_string();
+    let nasset_amount: Uint256 = 2_000_000_000u128.into();
+    sdk.set_collateral_balance(nasset_amount);
+    sdk.set_nasset_supply(nasset_amount);
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    //without hook msg bAsset is transferred to recipient
+    let response = sdk
+        .user_withdraw_to(&user_address, nasset_amount.into(), &router_address, None)
+        .unwrap();
+    assert!(response
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: router_address.clone(),
+                amount: nasset_amount.into(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+
+    //with hook msg bAsset is sent to recipient contract
+    let hook_msg = to_binary(&"swap").unwrap();
+    let response = sdk
+        .user_withdraw_to(
+            &user_address,
+            nasset_amount.into(),
+            &router_address,
+            Some(hook_msg.clone()),
+        )
+        .unwrap();
+    assert!(response
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: router_address,
+                amount: nasset_amount.into(),
+                msg: hook_msg,
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+}
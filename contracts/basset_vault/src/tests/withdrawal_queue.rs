@@ -0,0 +1,277 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::{BASSET_TOKEN_ADDR, GOVERNANCE_CONTRACT, NASSET_TOKEN_ADDR};
+use crate::SubmsgIds;
+use basset_vault::basset_vault::{
+    Cw20HookMsg, ExecuteMsg, GovernanceMsg, GuardianMsg, PendingWithdrawalResponse,
+    PendingWithdrawalsResponse, QueryMsg, YourselfMsg,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{attr, to_binary, CosmosMsg, StdError, SubMsg, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+fn process_withdrawal_submsg() -> SubMsg {
+    SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Yourself {
+                yourself_msg: YourselfMsg::ProcessWithdrawal {},
+            })
+            .unwrap(),
+            funds: vec![],
+        },
+        SubmsgIds::ProcessWithdrawal.id(),
+    )
+}
+
+fn pending_withdrawals(sdk: &Sdk) -> PendingWithdrawalsResponse {
+    sdk.query(QueryMsg::PendingWithdrawals {
+        start_after: None,
+        limit: None,
+    })
+    .unwrap()
+}
+
+#[test]
+fn request_withdraw_locks_nasset_in_queue() {
+    let mut sdk = Sdk::init();
+    sdk.set_nasset_supply(Uint256::from(4_000u64));
+    sdk.set_collateral_balance(Uint256::from(8_000u64));
+
+    sdk.user_request_withdraw("addr9999", 1_000u128.into())
+        .unwrap();
+    sdk.user_request_withdraw("addr6666", 3_000u128.into())
+        .unwrap();
+
+    assert_eq!(
+        PendingWithdrawalsResponse {
+            total_nasset_amount: Uint256::from(4_000u64),
+            pending_count: 2,
+            requests: vec![
+                PendingWithdrawalResponse {
+                    id: 0,
+                    recipient: "addr9999".to_string(),
+                    nasset_amount: Uint256::from(1_000u64),
+                },
+                PendingWithdrawalResponse {
+                    id: 1,
+                    recipient: "addr6666".to_string(),
+                    nasset_amount: Uint256::from(3_000u64),
+                },
+            ],
+        },
+        pending_withdrawals(&sdk)
+    );
+
+    let response: PendingWithdrawalsResponse = sdk
+        .query(QueryMsg::PendingWithdrawals {
+            start_after: Some(0),
+            limit: Some(1),
+        })
+        .unwrap();
+    assert_eq!(1, response.requests.len());
+    assert_eq!(1, response.requests[0].id);
+}
+
+#[test]
+fn request_withdraw_fails_if_sender_is_not_nasset_token() {
+    let mut sdk = Sdk::init();
+
+    let cw20_msg = Cw20ReceiveMsg {
+        sender: "addr9999".to_string(),
+        amount: 1_000u128.into(),
+        msg: to_binary(&Cw20HookMsg::RequestWithdraw { recipient: None }).unwrap(),
+    };
+    let info = mock_info(BASSET_TOKEN_ADDR, &[]);
+    let response = crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Receive(cw20_msg),
+    );
+    assert_eq!(
+        response.err().unwrap(),
+        ContractError::Std(StdError::generic_err("unauthorized"))
+    );
+    assert_eq!(0, pending_withdrawals(&sdk).pending_count);
+}
+
+#[test]
+fn rebalance_starts_queue_processing() {
+    let mut sdk = Sdk::init();
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    //empty queue, nothing to process
+    let response = sdk.rebalance().unwrap();
+    assert!(!response.messages.contains(&process_withdrawal_submsg()));
+
+    sdk.set_nasset_supply(Uint256::from(1_000u64));
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+    sdk.user_request_withdraw("addr9999", 1_000u128.into())
+        .unwrap();
+    let response = sdk.rebalance().unwrap();
+    assert_eq!(Some(&process_withdrawal_submsg()), response.messages.last());
+}
+
+#[test]
+fn process_withdrawals_fifo() {
+    let mut sdk = Sdk::init();
+
+    let nasset_supply: Uint256 = 4_000u64.into();
+    sdk.set_nasset_supply(nasset_supply);
+    sdk.set_collateral_balance(Uint256::from(8_000u64));
+    sdk.set_borrower_action(BorrowerActionResponse::Nothing {
+        price_is_stale: false,
+    });
+
+    sdk.user_request_withdraw("addr9999", 1_000u128.into())
+        .unwrap();
+    sdk.user_request_withdraw("addr6666", 3_000u128.into())
+        .unwrap();
+
+    //first request processed, next one scheduled
+    let response = sdk.send_process_withdrawal().unwrap();
+    assert!(response
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr9999".to_string(),
+                amount: 2_000u128.into(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+    assert!(response
+        .messages
+        .contains(&SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: NASSET_TOKEN_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                amount: 1_000u128.into(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))));
+    assert_eq!(Some(&process_withdrawal_submsg()), response.messages.last());
+    assert!(response.attributes.contains(&attr("withdrawal_id", "0")));
+
+    let queue = pending_withdrawals(&sdk);
+    assert_eq!(1, queue.pending_count);
+    assert_eq!(Uint256::from(3_000u64), queue.total_nasset_amount);
+
+    //last request processed, nothing scheduled
+    sdk.set_nasset_supply(Uint256::from(3_000u64));
+    sdk.set_collateral_balance(Uint256::from(6_000u64));
+    let response = sdk.send_process_withdrawal().unwrap();
+    assert!(response.attributes.contains(&attr("withdrawal_id", "1")));
+    assert!(!response.messages.contains(&process_withdrawal_submsg()));
+
+    let queue = pending_withdrawals(&sdk);
+    assert_eq!(0, queue.pending_count);
+    assert_eq!(Uint256::zero(), queue.total_nasset_amount);
+
+    let response = sdk.send_process_withdrawal().unwrap();
+    assert!(response
+        .attributes
+        .contains(&attr("skipped", "queue_empty")));
+}
+
+#[test]
+fn request_worth_zero_basset_is_rejected() {
+    let mut sdk = Sdk::init();
+    sdk.set_nasset_supply(Uint256::from(4_000u64));
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+
+    //1_000 * 3 / 4_000 = 0.75 => 0
+    let response = sdk.user_request_withdraw("addr9999", 3u128.into());
+    assert_eq!(Err(ContractError::ZeroBAssetToWithdraw), response);
+    assert_eq!(0, pending_withdrawals(&sdk).pending_count);
+}
+
+#[test]
+fn request_worth_zero_basset_is_returned_on_processing() {
+    let mut sdk = Sdk::init();
+    sdk.set_nasset_supply(Uint256::from(4_000u64));
+    sdk.set_collateral_balance(Uint256::from(4_000u64));
+
+    sdk.user_request_withdraw("addr9999", 3u128.into()).unwrap();
+    sdk.user_request_withdraw("addr6666", 1_000u128.into())
+        .unwrap();
+
+    //bAsset share of the first request dropped to zero, it would never succeed
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+    let response = sdk.send_process_withdrawal().unwrap();
+    assert_eq!(
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: NASSET_TOKEN_ADDR.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr9999".to_string(),
+                    amount: 3u128.into(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            process_withdrawal_submsg(),
+        ],
+        response.messages
+    );
+    assert!(response
+        .attributes
+        .contains(&attr("skipped", "zero_basset_to_withdraw")));
+
+    let queue = pending_withdrawals(&sdk);
+    assert_eq!(1, queue.pending_count);
+    assert_eq!(1, queue.requests[0].id);
+}
+
+#[test]
+fn queue_is_not_processed_while_withdraw_paused() {
+    let mut sdk = Sdk::init();
+    sdk.set_nasset_supply(Uint256::from(1_000u64));
+    sdk.set_collateral_balance(Uint256::from(1_000u64));
+    sdk.user_request_withdraw("addr9999", 1_000u128.into())
+        .unwrap();
+
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateGuardian {
+            guardian_addr: Some("addr7777".to_string()),
+        },
+    };
+    crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        msg,
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Guardian {
+        guardian_msg: GuardianMsg::SetPause {
+            deposit: None,
+            withdraw: Some(true),
+            borrow: None,
+            honest_work: None,
+            claim_remainder: None,
+        },
+    };
+    crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        mock_info("addr7777", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let response = sdk.send_process_withdrawal();
+    assert_eq!(
+        Err(ContractError::Paused {
+            operation: "withdraw".to_string()
+        }),
+        response
+    );
+    assert_eq!(1, pending_withdrawals(&sdk).pending_count);
+}
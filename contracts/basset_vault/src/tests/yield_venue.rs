@@ -0,0 +1,97 @@
+use super::sdk::Sdk;
+use crate::error::ContractError;
+use crate::tests::sdk::{ANCHOR_MARKET_CONTRACT, GOVERNANCE_CONTRACT, STABLE_DENOM};
+use basset_vault::basset_vault::{
+    ConfigResponse, ExecuteMsg, GovernanceMsg, QueryMsg, YourselfMsg,
+};
+use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+use basset_vault::querier::AnchorMarketMsg;
+use basset_vault::yield_venue::YieldVenueKind;
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{to_binary, Coin, CosmosMsg, Decimal, Response, Uint128, WasmMsg};
+
+fn update_yield_venue(
+    sdk: &mut Sdk,
+    sender: &str,
+    yield_venue: YieldVenueKind,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateYieldVenue { yield_venue },
+    };
+    let info = mock_info(sender, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+#[test]
+fn fail_to_update_yield_venue_if_sender_is_not_governance() {
+    let mut sdk = Sdk::init();
+
+    let res = update_yield_venue(&mut sdk, "addr0001", YieldVenueKind::AnchorEarn {});
+    assert!(res.is_err());
+}
+
+#[test]
+fn fail_to_update_yield_venue_to_the_same_one() {
+    let mut sdk = Sdk::init();
+
+    let res = update_yield_venue(&mut sdk, GOVERNANCE_CONTRACT, YieldVenueKind::AnchorEarn {});
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let config: ConfigResponse = sdk.query(QueryMsg::Config {}).unwrap();
+    assert_eq!(YieldVenueKind::AnchorEarn {}, config.yield_venue);
+}
+
+#[test]
+fn deposit_to_yield_venue_keeps_aim_buffer() {
+    let mut sdk = Sdk::init();
+    sdk.set_tax(Decimal::zero(), 0);
+    sdk.set_borrower_action(BorrowerActionResponse::Borrow {
+        amount: Uint256::from(5_000u64),
+        advised_buffer_size: Uint256::from(1_000u64),
+        price_is_stale: false,
+    });
+    sdk.rebalance().unwrap();
+
+    sdk.set_stable_balance(Uint128::new(5_000));
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let response = crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Yourself {
+            yourself_msg: YourselfMsg::DepositToYieldVenue {},
+        },
+    )
+    .unwrap();
+
+    assert_eq!(1, response.messages.len());
+    assert_eq!(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANCHOR_MARKET_CONTRACT.to_string(),
+            msg: to_binary(&AnchorMarketMsg::DepositStable {}).unwrap(),
+            funds: vec![Coin {
+                denom: STABLE_DENOM.to_string(),
+                amount: Uint128::new(4_000),
+            }],
+        }),
+        response.messages[0].msg
+    );
+}
+
+#[test]
+fn fail_to_deposit_to_yield_venue_if_sender_is_not_vault() {
+    let mut sdk = Sdk::init();
+
+    let info = mock_info("addr0001", &[]);
+    let res = crate::contract::execute(
+        sdk.deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Yourself {
+            yourself_msg: YourselfMsg::DepositToYieldVenue {},
+        },
+    );
+    assert!(res.is_err());
+}
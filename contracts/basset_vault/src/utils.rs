@@ -1,23 +1,34 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    to_binary, Coin, CosmosMsg, Deps, Env, Response, StdResult, SubMsg, Uint128, WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, Env, Response, StdError, StdResult, SubMsg,
+    Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
 
 use crate::tax_querier::TaxInfo;
 use crate::SubmsgIds;
 use crate::{
-    state::{load_last_anc_claim_seconds, Config},
-    MAX_SECS_DELAY_BETWEEN_ANC_CLAIM, MIN_ANC_REWARDS_TO_CLAIM,
+    state::{load_last_anc_claim_seconds, Config, RouterRoute},
+    MAX_SECS_DELAY_BETWEEN_ANC_CLAIM, MINIMUM_NASSET_LIQUIDITY, MIN_ANC_REWARDS_TO_CLAIM,
 };
 use basset_vault::{
-    astroport_pair::ExecuteMsg as AstroportExecuteMsg,
+    anchor::market::{calc_anchor_aprs, AnchorAprs},
+    astroport_oracle::query_consult,
+    astroport_pair::{
+        query_simulation, Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg,
+        SimulationResponse,
+    },
+    astroport_router::{
+        query_simulate_swap_operations, Cw20HookMsg as AstroportRouterCw20HookMsg,
+        ExecuteMsg as AstroportRouterExecuteMsg, SwapOperation,
+    },
+    basset_vault::{ExecuteMsg, YourselfMsg},
     psi_distributor::{
         AnyoneMsg as PsiDistributorAnyoneMsg, ExecuteMsg as PsiDistributorExecuteMsg,
     },
-    querier::{AnchorMarketCw20Msg, AnchorMarketMsg},
+    querier::query_supply,
     terraswap::{Asset, AssetInfo},
 };
-use cw20::Cw20ExecuteMsg;
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum RepayLoanAction {
@@ -46,30 +57,33 @@ impl RepayLoanAction {
         }
     }
 
+    pub fn aterra_to_sell(&self) -> Uint256 {
+        match *self {
+            RepayLoanAction::SellAterra { amount } => amount,
+            RepayLoanAction::RepayLoanAndSellAterra {
+                aterra_amount_to_sell,
+                ..
+            } => aterra_amount_to_sell,
+
+            _ => Uint256::zero(),
+        }
+    }
+
     pub fn to_response(&self, config: &Config) -> StdResult<Response> {
         match self {
             RepayLoanAction::Nothing => Ok(Response::default()),
 
-            &RepayLoanAction::RepayLoan { amount } => {
-                let repay_stable_coin = Coin {
-                    denom: config.stable_denom.clone(),
-                    amount: amount.into(),
-                };
-
-                Ok(Response::new()
-                    .add_submessage(SubMsg::reply_on_success(
-                        CosmosMsg::Wasm(WasmMsg::Execute {
-                            contract_addr: config.anchor_market_contract.to_string(),
-                            msg: to_binary(&AnchorMarketMsg::RepayStable {})?,
-                            funds: vec![repay_stable_coin],
-                        }),
-                        SubmsgIds::RepayLoan.id(),
-                    ))
-                    .add_attributes(vec![
-                        ("action", "repay_loan"),
-                        ("amount", &amount.to_string()),
-                    ]))
-            }
+            &RepayLoanAction::RepayLoan { amount } => Ok(Response::new()
+                .add_submessage(SubMsg::reply_on_success(
+                    config
+                        .market_adapter()
+                        .repay(&config.stable_denom, amount)?,
+                    SubmsgIds::RepayLoan.id(),
+                ))
+                .add_attributes(vec![
+                    ("action", "repay_loan"),
+                    ("amount", &amount.to_string()),
+                ])),
 
             &RepayLoanAction::SellAterra { amount } => {
                 Ok(Response::new()
@@ -77,15 +91,7 @@ impl RepayLoanAction {
                         //Always because Anchor can block withdrawing
                         //if there are too many borrowers
                         SubMsg::reply_always(
-                            CosmosMsg::Wasm(WasmMsg::Execute {
-                                contract_addr: config.aterra_token.to_string(),
-                                msg: to_binary(&Cw20ExecuteMsg::Send {
-                                    contract: config.anchor_market_contract.to_string(),
-                                    amount: amount.into(),
-                                    msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {})?,
-                                })?,
-                                funds: vec![],
-                            }),
+                            config.venue_adapter().redeem(amount)?,
                             SubmsgIds::RedeemStableOnRepayLoan.id(),
                         ),
                     )
@@ -99,32 +105,17 @@ impl RepayLoanAction {
                 repay_loan_amount,
                 aterra_amount_to_sell,
             } => {
-                let repay_stable_coin = Coin {
-                    denom: config.stable_denom.to_string(),
-                    amount: repay_loan_amount.into(),
-                };
-
                 Ok(Response::new()
                     .add_submessages(vec![
                         //first message is to repay loan
                         SubMsg::reply_on_success(
-                            CosmosMsg::Wasm(WasmMsg::Execute {
-                                contract_addr: config.anchor_market_contract.to_string(),
-                                msg: to_binary(&AnchorMarketMsg::RepayStable {})?,
-                                funds: vec![repay_stable_coin],
-                            }),
+                            config
+                                .market_adapter()
+                                .repay(&config.stable_denom, repay_loan_amount)?,
                             SubmsgIds::RepayLoan.id(),
                         ),
                         SubMsg::reply_on_success(
-                            CosmosMsg::Wasm(WasmMsg::Execute {
-                                contract_addr: config.aterra_token.to_string(),
-                                msg: to_binary(&Cw20ExecuteMsg::Send {
-                                    contract: config.anchor_market_contract.to_string(),
-                                    amount: aterra_amount_to_sell.into(),
-                                    msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {})?,
-                                })?,
-                                funds: vec![],
-                            }),
+                            config.venue_adapter().redeem(aterra_amount_to_sell)?,
                             SubmsgIds::RedeemStableOnRepayLoan.id(),
                         ),
                     ])
@@ -139,6 +130,98 @@ impl RepayLoanAction {
     }
 }
 
+/// Loan repayment computed in one pass: redeem aterra (if needed) and repay loan.
+/// `is_complete` is false when Anchor liquidity is not enough to redeem wanted aterra,
+/// in that case repayment falls back to reply-driven iterations
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct RepayPlan {
+    pub aterra_to_redeem: Uint256,
+    pub expected_stables: Uint256,
+    pub repay_amount: Uint256,
+    pub is_complete: bool,
+}
+
+impl RepayPlan {
+    pub fn to_response(&self, config: &Config) -> StdResult<Response> {
+        let mut response = Response::new();
+
+        if !self.aterra_to_redeem.is_zero() {
+            //liquidity is checked on planning, so redeeming should not fail
+            response = response
+                .add_message(config.venue_adapter().redeem(self.aterra_to_redeem)?)
+                .add_attributes(vec![
+                    ("action_1", "sell_aterra"),
+                    ("aterra_amount", &self.aterra_to_redeem.to_string()),
+                ]);
+        }
+
+        if !self.repay_amount.is_zero() {
+            response = response
+                .add_submessage(SubMsg::reply_on_success(
+                    config
+                        .market_adapter()
+                        .repay(&config.stable_denom, self.repay_amount)?,
+                    SubmsgIds::RepayLoan.id(),
+                ))
+                .add_attributes(vec![
+                    ("action_2", "repay_loan"),
+                    ("loan_amount", &self.repay_amount.to_string()),
+                ]);
+        }
+
+        Ok(response)
+    }
+}
+
+pub fn plan_loan_repayment(
+    stable_coin_balance: Uint256,
+    aterra_balance: Uint256,
+    aterra_exchange_rate: Decimal256,
+    redeemable_stables: Uint256,
+    total_repay_amount: Uint256,
+    aim_buffer_size: Uint256,
+    tax_info: &TaxInfo,
+) -> RepayPlan {
+    let wanted_stables = calc_wanted_stablecoins(
+        stable_coin_balance,
+        tax_info.append_tax(total_repay_amount),
+        aim_buffer_size,
+    );
+    if wanted_stables.is_zero() || aterra_balance.is_zero() || aterra_exchange_rate.is_zero() {
+        return RepayPlan {
+            repay_amount: total_repay_amount.min(tax_info.subtract_tax(stable_coin_balance)),
+            is_complete: true,
+            ..RepayPlan::default()
+        };
+    }
+
+    let redeemable_aterra = aterra_balance.min(redeemable_stables / aterra_exchange_rate);
+    //adding tax that anchor contract will pay to send stable coins to us
+    let wanted_aterra = tax_info.append_tax(wanted_stables) / aterra_exchange_rate;
+    let aterra_to_redeem = wanted_aterra.min(redeemable_aterra);
+    let expected_stables = tax_info.subtract_tax(aterra_to_redeem * aterra_exchange_rate);
+    //do not redeem aterra if we will receive nothing
+    let (aterra_to_redeem, expected_stables) = if expected_stables.is_zero() {
+        (Uint256::zero(), Uint256::zero())
+    } else {
+        (aterra_to_redeem, expected_stables)
+    };
+
+    let repay_amount =
+        total_repay_amount.min(tax_info.subtract_tax(stable_coin_balance + expected_stables));
+
+    //we have aterra to redeem more, but Anchor do not have enough stables
+    let is_liquidity_constrained =
+        wanted_aterra > redeemable_aterra && aterra_balance > redeemable_aterra;
+
+    RepayPlan {
+        aterra_to_redeem,
+        expected_stables,
+        repay_amount,
+        is_complete: !is_liquidity_constrained,
+    }
+}
+
 /// Returns `RepayLoanAction::Nothing` if all the listed values are zero
 macro_rules! return_nothing_if_zero {
     ($first:expr $(, $others:expr)*) => {
@@ -152,11 +235,19 @@ pub fn get_repay_loan_action(
     stable_coin_balance: Uint256,
     aterra_balance: Uint256,
     aterra_exchange_rate: Decimal256,
+    redeemable_stables: Uint256,
     total_repay_amount: Uint256,
     aim_buffer_size: Uint256,
     tax_info: &TaxInfo,
     is_first_try: bool,
 ) -> RepayLoanAction {
+    //Anchor fails to redeem aterra if there are not enough stables on market balance,
+    //so use only part of aterra that can be redeemed right now
+    let aterra_balance = if aterra_exchange_rate.is_zero() {
+        aterra_balance
+    } else {
+        aterra_balance.min(redeemable_stables / aterra_exchange_rate)
+    };
     return_nothing_if_zero!(aterra_balance, stable_coin_balance);
 
     let max_amount_to_send = tax_info.subtract_tax(stable_coin_balance);
@@ -242,6 +333,110 @@ pub fn get_repay_loan_action(
     }
 }
 
+/// bAsset amount to sell to get LTV (loan / collateral value) back to `trigger_ltv`.
+/// Zero if LTV is not above `trigger_ltv` or loan can be repaid from `available_stables`.
+/// Anchor unlocks collateral only while loan stays under borrow limit, so amount
+/// is capped by what can be unlocked with current loan
+pub fn calc_basset_to_sell_on_emergency(
+    loan_amount: Uint256,
+    basset_in_custody: Uint256,
+    basset_price: Decimal256,
+    available_stables: Uint256,
+    trigger_ltv: Decimal256,
+    basset_max_ltv: Decimal256,
+    max_basset_amount: Uint256,
+) -> Uint256 {
+    if basset_price.is_zero() || basset_max_ltv.is_zero() || trigger_ltv >= Decimal256::one() {
+        return Uint256::zero();
+    }
+
+    let safe_loan_amount = basset_in_custody * basset_price * trigger_ltv;
+    if loan_amount <= safe_loan_amount + available_stables {
+        return Uint256::zero();
+    }
+
+    // (loan - available - sell * price) = trigger_ltv * (basset_in_custody - sell) * price
+    let basset_to_sell = Uint256::one()
+        * (Decimal256::from_uint256(loan_amount - safe_loan_amount - available_stables)
+            / (basset_price * (Decimal256::one() - trigger_ltv)));
+
+    // loan = (basset_in_custody - unlockable) * price * basset_max_ltv
+    let borrow_limit = Decimal256::from_uint256(basset_in_custody) * basset_price * basset_max_ltv;
+    let loan = Decimal256::from_uint256(loan_amount);
+    let unlockable_basset = if borrow_limit > loan {
+        floor_to_uint((borrow_limit - loan) / (basset_price * basset_max_ltv))
+    } else {
+        Uint256::zero()
+    };
+
+    basset_to_sell
+        .min(max_basset_amount)
+        .min(basset_in_custody)
+        .min(unlockable_basset)
+}
+
+/// Projected yearly rates for nAsset holders.
+/// `*_apr` are Anchor rates per borrowed UST, `*_yield` and `borrow_cost` are
+/// per collateral value. Whole loan is assumed to be deposited to Anchor
+#[derive(PartialEq, Debug, Default)]
+pub struct EstimatedApr {
+    pub deposit_apr: Decimal256,
+    pub borrow_apr: Decimal256,
+    pub distribution_apr: Decimal256,
+    pub deposit_yield: Decimal256,
+    pub distribution_yield: Decimal256,
+    pub borrow_cost: Decimal256,
+    pub nasset_holder_share: Decimal256,
+    pub apr: Decimal256,
+}
+
+/// `deposit_rate`, `borrow_rate` and `anc_emission_rate` are per block, as Anchor returns them
+pub fn calc_estimated_apr(
+    deposit_rate: Decimal256,
+    borrow_rate: Decimal256,
+    anc_emission_rate: Decimal256,
+    total_liabilities: Decimal256,
+    anc_price: Decimal256,
+    ltv: Decimal256,
+    protocol_fee: Decimal256,
+) -> EstimatedApr {
+    let AnchorAprs {
+        deposit_apr,
+        borrow_apr,
+        distribution_apr,
+    } = calc_anchor_aprs(
+        deposit_rate,
+        borrow_rate,
+        anc_emission_rate,
+        total_liabilities,
+        anc_price,
+    );
+
+    let deposit_yield = deposit_apr * ltv;
+    let distribution_yield = distribution_apr * ltv;
+    let borrow_cost = borrow_apr * ltv;
+    let nasset_holder_share = Decimal256::one() - protocol_fee.min(Decimal256::one());
+
+    //interest is covered from ANC selling profit first, rest buys Psi
+    let income = deposit_yield + distribution_yield;
+    let apr = if income > borrow_cost {
+        (income - borrow_cost) * nasset_holder_share
+    } else {
+        Decimal256::zero()
+    };
+
+    EstimatedApr {
+        deposit_apr,
+        borrow_apr,
+        distribution_apr,
+        deposit_yield,
+        distribution_yield,
+        borrow_cost,
+        nasset_holder_share,
+        apr,
+    }
+}
+
 fn calc_wanted_stablecoins(
     stable_coin_balance: Uint256,
     repay_amount: Uint256,
@@ -279,14 +474,11 @@ impl AfterBorrowAction {
             AfterBorrowAction::Nothing => Ok(Response::default()),
 
             &AfterBorrowAction::Deposit { amount } => Ok(Response::new()
-                .add_message(WasmMsg::Execute {
-                    contract_addr: config.anchor_market_contract.to_string(),
-                    msg: to_binary(&AnchorMarketMsg::DepositStable {})?,
-                    funds: vec![Coin {
-                        denom: config.stable_denom.clone(),
-                        amount: amount.into(),
-                    }],
-                })
+                .add_message(
+                    config
+                        .venue_adapter()
+                        .deposit(&config.stable_denom, amount)?,
+                )
                 .add_attributes(vec![("action", "deposit"), ("amount", &amount.to_string())])),
         }
     }
@@ -323,7 +515,81 @@ pub enum ActionWithProfit {
 }
 
 impl ActionWithProfit {
-    pub fn to_response(&self, config: &Config, tax_info: &TaxInfo) -> StdResult<Response> {
+    /// stable coins (tax excluded) to be swapped to Psi
+    pub fn psi_offer_amount(&self, tax_info: &TaxInfo) -> Option<Uint128> {
+        match self {
+            &ActionWithProfit::BuyPsi { amount } => Some(tax_info.subtract_tax(amount).into()),
+            &ActionWithProfit::Split { buy_psi, .. } => Some(tax_info.subtract_tax(buy_psi).into()),
+            ActionWithProfit::DepositToAnc { .. } | ActionWithProfit::Nothing => None,
+        }
+    }
+
+    /// used when Psi price impact is too high,
+    /// deposited stables will be counted as profit on next rewards distribution
+    pub fn deposit_instead_of_buying_psi(&self) -> Self {
+        match self {
+            &ActionWithProfit::BuyPsi { amount } => ActionWithProfit::DepositToAnc { amount },
+            &ActionWithProfit::Split {
+                buy_psi,
+                deposit_to_anc,
+            } => ActionWithProfit::DepositToAnc {
+                amount: buy_psi + deposit_to_anc,
+            },
+            &ActionWithProfit::DepositToAnc { amount } => ActionWithProfit::DepositToAnc { amount },
+            ActionWithProfit::Nothing => ActionWithProfit::Nothing,
+        }
+    }
+
+    /// Moves `compound_share` of stables for buying Psi to compounding.
+    /// Returns action for the rest and stables (tax included) to buy bAsset on
+    pub fn split_compounding(&self, compound_share: Decimal256) -> (Self, Uint256) {
+        match self {
+            &ActionWithProfit::BuyPsi { amount } => {
+                let to_compound = amount * compound_share;
+                let buy_psi = amount - to_compound;
+                if buy_psi.is_zero() {
+                    (ActionWithProfit::Nothing, to_compound)
+                } else {
+                    (ActionWithProfit::BuyPsi { amount: buy_psi }, to_compound)
+                }
+            }
+            &ActionWithProfit::Split {
+                buy_psi,
+                deposit_to_anc,
+            } => {
+                let to_compound = buy_psi * compound_share;
+                let buy_psi = buy_psi - to_compound;
+                if buy_psi.is_zero() {
+                    (
+                        ActionWithProfit::DepositToAnc {
+                            amount: deposit_to_anc,
+                        },
+                        to_compound,
+                    )
+                } else {
+                    (
+                        ActionWithProfit::Split {
+                            buy_psi,
+                            deposit_to_anc,
+                        },
+                        to_compound,
+                    )
+                }
+            }
+            &ActionWithProfit::DepositToAnc { amount } => {
+                (ActionWithProfit::DepositToAnc { amount }, Uint256::zero())
+            }
+            ActionWithProfit::Nothing => (ActionWithProfit::Nothing, Uint256::zero()),
+        }
+    }
+
+    /// `psi_swap` should be set if action buys Psi
+    pub fn to_response(
+        &self,
+        config: &Config,
+        tax_info: &TaxInfo,
+        psi_swap: Option<&RewardSwap>,
+    ) -> StdResult<Response> {
         match self {
             ActionWithProfit::Nothing => Ok(Response::new().add_attributes(vec![
                 ("action", "distribute_rewards"),
@@ -334,14 +600,11 @@ impl ActionWithProfit {
                 let stable_coin_to_lending: Uint128 = tax_info.subtract_tax(amount).into();
 
                 Ok(Response::new()
-                    .add_message(WasmMsg::Execute {
-                        contract_addr: config.anchor_market_contract.to_string(),
-                        msg: to_binary(&AnchorMarketMsg::DepositStable {})?,
-                        funds: vec![Coin {
-                            denom: config.stable_denom.clone(),
-                            amount: stable_coin_to_lending,
-                        }],
-                    })
+                    .add_message(
+                        config
+                            .venue_adapter()
+                            .deposit(&config.stable_denom, stable_coin_to_lending.into())?,
+                    )
                     .add_attributes(vec![
                         ("action", "distribute_rewards"),
                         ("deposit_to_anc", &stable_coin_to_lending.to_string()),
@@ -349,36 +612,25 @@ impl ActionWithProfit {
             }
 
             &ActionWithProfit::BuyPsi { amount } => {
+                let psi_swap = psi_swap.ok_or_else(swap_limits_not_set_err)?;
                 let stable_coin_to_buy_psi: Uint128 = tax_info.subtract_tax(amount).into();
-                let swap_asset = Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: config.stable_denom.clone(),
-                    },
-                    amount: stable_coin_to_buy_psi,
-                };
 
                 Ok(Response::new()
                     .add_messages(vec![
-                        WasmMsg::Execute {
-                            contract_addr: config.psi_stable_swap_contract.to_string(),
-                            msg: to_binary(&AstroportExecuteMsg::Swap {
-                                offer_asset: swap_asset,
-                                max_spread: None,
-                                belief_price: None,
-                                to: Some(config.psi_distributor.to_string()),
-                            })?,
-                            funds: vec![Coin {
+                        psi_swap.native_swap_msg(
+                            Coin {
                                 denom: config.stable_denom.clone(),
                                 amount: stable_coin_to_buy_psi,
-                            }],
-                        },
-                        WasmMsg::Execute {
+                            },
+                            Some(config.psi_distributor.to_string()),
+                        )?,
+                        CosmosMsg::Wasm(WasmMsg::Execute {
                             contract_addr: config.psi_distributor.to_string(),
                             msg: to_binary(&PsiDistributorExecuteMsg::Anyone {
                                 anyone_msg: PsiDistributorAnyoneMsg::DistributeRewards {},
                             })?,
                             funds: vec![],
-                        },
+                        }),
                     ])
                     .add_attributes(vec![
                         ("action", "distribute_rewards"),
@@ -390,45 +642,31 @@ impl ActionWithProfit {
                 buy_psi,
                 deposit_to_anc,
             } => {
+                let psi_swap = psi_swap.ok_or_else(swap_limits_not_set_err)?;
                 let stable_coin_to_lending: Uint128 = tax_info.subtract_tax(deposit_to_anc).into();
                 let stable_coin_to_buy_psi: Uint128 = tax_info.subtract_tax(buy_psi).into();
-                let swap_asset = Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: config.stable_denom.clone(),
-                    },
-                    amount: stable_coin_to_buy_psi,
-                };
 
                 Ok(Response::new()
+                    .add_message(
+                        config
+                            .venue_adapter()
+                            .deposit(&config.stable_denom, stable_coin_to_lending.into())?,
+                    )
                     .add_messages(vec![
-                        WasmMsg::Execute {
-                            contract_addr: config.anchor_market_contract.to_string(),
-                            msg: to_binary(&AnchorMarketMsg::DepositStable {})?,
-                            funds: vec![Coin {
-                                denom: config.stable_denom.clone(),
-                                amount: stable_coin_to_lending,
-                            }],
-                        },
-                        WasmMsg::Execute {
-                            contract_addr: config.psi_stable_swap_contract.to_string(),
-                            msg: to_binary(&AstroportExecuteMsg::Swap {
-                                offer_asset: swap_asset,
-                                max_spread: None,
-                                belief_price: None,
-                                to: Some(config.psi_distributor.to_string()),
-                            })?,
-                            funds: vec![Coin {
+                        psi_swap.native_swap_msg(
+                            Coin {
                                 denom: config.stable_denom.clone(),
                                 amount: stable_coin_to_buy_psi,
-                            }],
-                        },
-                        WasmMsg::Execute {
+                            },
+                            Some(config.psi_distributor.to_string()),
+                        )?,
+                        CosmosMsg::Wasm(WasmMsg::Execute {
                             contract_addr: config.psi_distributor.to_string(),
                             msg: to_binary(&PsiDistributorExecuteMsg::Anyone {
                                 anyone_msg: PsiDistributorAnyoneMsg::DistributeRewards {},
                             })?,
                             funds: vec![],
-                        },
+                        }),
                     ])
                     .add_attributes(vec![
                         ("action", "distribute_rewards"),
@@ -438,8 +676,357 @@ impl ActionWithProfit {
             }
         }
     }
+
+    /// Same amounts as sent in `to_response`, for the vault ledger
+    pub fn distributed_rewards(
+        &self,
+        tax_info: &TaxInfo,
+        psi_swap: Option<&RewardSwap>,
+    ) -> DistributedRewards {
+        let deposited_to_anc = match self {
+            &ActionWithProfit::DepositToAnc { amount } => tax_info.subtract_tax(amount),
+            &ActionWithProfit::Split { deposit_to_anc, .. } => {
+                tax_info.subtract_tax(deposit_to_anc)
+            }
+            ActionWithProfit::BuyPsi { .. } | ActionWithProfit::Nothing => Uint256::zero(),
+        };
+
+        DistributedRewards {
+            deposited_to_anc,
+            stables_to_psi: self
+                .psi_offer_amount(tax_info)
+                .map(Uint256::from)
+                .unwrap_or_default(),
+            psi_bought: psi_swap
+                .map(|swap| Uint256::from(swap.return_amount()))
+                .unwrap_or_default(),
+            stables_to_compound: Uint256::zero(),
+        }
+    }
 }
 
+/// How ANC selling profit was split on rewards distribution
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct DistributedRewards {
+    pub deposited_to_anc: Uint256,
+    pub stables_to_psi: Uint256,
+    //expected by swap simulation, real amount goes directly to psi distributor
+    pub psi_bought: Uint256,
+    pub stables_to_compound: Uint256,
+}
+
+/// Part of profit swapped to bAsset, which is locked as collateral right after
+#[derive(Debug, PartialEq)]
+pub struct Compounding {
+    //stables (tax excluded) offered for bAsset
+    pub offer_amount: Uint128,
+    pub basset_swap: RewardSwap,
+}
+
+impl Compounding {
+    pub fn to_messages(&self, config: &Config, env: &Env) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![
+            self.basset_swap.native_swap_msg(
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: self.offer_amount,
+                },
+                None,
+            )?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::Yourself {
+                    yourself_msg: YourselfMsg::LockCompoundedBasset {},
+                })?,
+                funds: vec![],
+            }),
+        ])
+    }
+}
+
+fn swap_limits_not_set_err() -> StdError {
+    StdError::generic_err("swap limits for buying Psi are not set")
+}
+
+/// `belief_price` and `max_spread` for Astroport swap
+#[derive(Debug, PartialEq)]
+pub struct SwapLimits {
+    pub belief_price: Decimal,
+    pub max_spread: Decimal,
+    //simulated return amount, used for accounting only
+    pub return_amount: Uint128,
+}
+
+impl SwapLimits {
+    /// Returns None if price impact of the swap is higher than `max_spread`,
+    /// or pool price is worse than `reference_amount` (return by TWAP) by more than `max_spread`
+    pub fn from_simulation(
+        offer_amount: Uint128,
+        simulation: &SimulationResponse,
+        max_spread: Decimal256,
+        reference_amount: Option<Uint256>,
+    ) -> Option<Self> {
+        //return amount without spread, i.e. by pool price
+        let spot_return_amount =
+            simulation.return_amount + simulation.commission_amount + simulation.spread_amount;
+        if offer_amount.is_zero() || spot_return_amount.is_zero() {
+            return None;
+        }
+
+        //simulation runs in the same block as swap, so pool price could be moved right before it
+        if is_below_reference(spot_return_amount.into(), reference_amount, max_spread) {
+            return None;
+        }
+
+        let spot_return_amount = Decimal256::from_uint256(Uint256::from(spot_return_amount));
+        let price_impact =
+            Decimal256::from_uint256(Uint256::from(simulation.spread_amount)) / spot_return_amount;
+        if price_impact > max_spread {
+            return None;
+        }
+
+        //Astroport expects price of ask asset in offer asset units
+        let belief_price =
+            Decimal256::from_uint256(Uint256::from(offer_amount)) / spot_return_amount;
+        Some(SwapLimits {
+            belief_price: belief_price.into(),
+            max_spread: max_spread.into(),
+            return_amount: simulation.return_amount,
+        })
+    }
+}
+
+/// True if `return_amount` is less than `reference_amount` by more than `max_spread`
+fn is_below_reference(
+    return_amount: Uint256,
+    reference_amount: Option<Uint256>,
+    max_spread: Decimal256,
+) -> bool {
+    match reference_amount {
+        Some(reference_amount) => {
+            return_amount < reference_amount * (Decimal256::one() - max_spread)
+        }
+        None => false,
+    }
+}
+
+/// Return amount for `offer_asset` by TWAP price, None if there is no `twap_oracle`
+fn query_reference_amount(
+    deps: Deps,
+    twap_oracle: Option<&Addr>,
+    offer_asset: &Asset,
+) -> StdResult<Option<Uint256>> {
+    twap_oracle
+        .map(|twap_oracle| {
+            query_consult(
+                deps,
+                twap_oracle,
+                offer_asset.info.clone(),
+                offer_asset.amount,
+            )
+        })
+        .transpose()
+}
+
+pub fn query_swap_limits(
+    deps: Deps,
+    pair_contract: &Addr,
+    offer_asset: Asset,
+    max_spread: Decimal256,
+    twap_oracle: Option<&Addr>,
+) -> StdResult<Option<SwapLimits>> {
+    let offer_amount = offer_asset.amount;
+    let reference_amount = query_reference_amount(deps, twap_oracle, &offer_asset)?;
+    let simulation = query_simulation(deps, pair_contract, offer_asset)?;
+    Ok(SwapLimits::from_simulation(
+        offer_amount,
+        &simulation,
+        max_spread,
+        reference_amount,
+    ))
+}
+
+/// Swap through DEX router by stored route
+#[derive(Debug, PartialEq)]
+pub struct RouterSwap {
+    pub router_contract: Addr,
+    pub operations: Vec<SwapOperation>,
+    //router fails swap if last operation returns less
+    pub minimum_receive: Uint128,
+    //simulated return amount, used for accounting only
+    pub return_amount: Uint128,
+}
+
+impl RouterSwap {
+    /// `max_spread` is allowed slippage from simulated amount.
+    /// Returns None if route returns nothing, or returns less than `reference_amount`
+    /// (return by TWAP) by more than `max_spread`
+    pub fn from_simulation(
+        route: &RouterRoute,
+        simulated_amount: Uint128,
+        max_spread: Decimal256,
+        reference_amount: Option<Uint256>,
+    ) -> Option<Self> {
+        if simulated_amount.is_zero() {
+            return None;
+        }
+
+        //route pools could be moved right before swap, same as single pair.
+        //Simulated amount includes fees of every hop, so `max_spread` should cover them
+        if is_below_reference(simulated_amount.into(), reference_amount, max_spread) {
+            return None;
+        }
+
+        let minimum_receive = Uint256::from(simulated_amount) * (Decimal256::one() - max_spread);
+        Some(RouterSwap {
+            router_contract: route.router_contract.clone(),
+            operations: route.operations.to_vec(),
+            minimum_receive: minimum_receive.into(),
+            return_amount: simulated_amount,
+        })
+    }
+}
+
+/// How reward token is swapped: on pair from config or through router
+#[derive(Debug, PartialEq)]
+pub enum RewardSwap {
+    Pair {
+        pair_contract: Addr,
+        limits: SwapLimits,
+    },
+    Router(RouterSwap),
+}
+
+impl RewardSwap {
+    pub fn return_amount(&self) -> Uint128 {
+        match self {
+            RewardSwap::Pair { limits, .. } => limits.return_amount,
+            RewardSwap::Router(router_swap) => router_swap.return_amount,
+        }
+    }
+
+    /// Message to swap native `offer` coin, swapped tokens are sent to `to` (or to sender)
+    pub fn native_swap_msg(&self, offer: Coin, to: Option<String>) -> StdResult<CosmosMsg> {
+        let (contract_addr, msg) = match self {
+            RewardSwap::Pair {
+                pair_contract,
+                limits,
+            } => (
+                pair_contract.to_string(),
+                to_binary(&AstroportExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: offer.denom.clone(),
+                        },
+                        amount: offer.amount,
+                    },
+                    max_spread: Some(limits.max_spread),
+                    belief_price: Some(limits.belief_price),
+                    to,
+                })?,
+            ),
+            RewardSwap::Router(router_swap) => (
+                router_swap.router_contract.to_string(),
+                to_binary(&AstroportRouterExecuteMsg::ExecuteSwapOperations {
+                    operations: router_swap.operations.clone(),
+                    minimum_receive: Some(router_swap.minimum_receive),
+                    to,
+                })?,
+            ),
+        };
+
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds: vec![offer],
+        }))
+    }
+
+    /// Message to swap `amount` of cw20 `token`, swapped tokens are sent to `to` (or to sender)
+    pub fn token_swap_msg(
+        &self,
+        token: &Addr,
+        amount: Uint128,
+        to: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        let (contract, msg) = match self {
+            RewardSwap::Pair {
+                pair_contract,
+                limits,
+            } => (
+                pair_contract.to_string(),
+                to_binary(&AstroportCw20HookMsg::Swap {
+                    belief_price: Some(limits.belief_price),
+                    max_spread: Some(limits.max_spread),
+                    to,
+                })?,
+            ),
+            RewardSwap::Router(router_swap) => (
+                router_swap.router_contract.to_string(),
+                to_binary(&AstroportRouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: router_swap.operations.clone(),
+                    minimum_receive: Some(router_swap.minimum_receive),
+                    to,
+                })?,
+            ),
+        };
+
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract,
+                amount,
+                msg,
+            })?,
+            funds: vec![],
+        }))
+    }
+}
+
+/// Swap on `pair_contract`, or through router if `router_route` is set.
+/// Returns None if swap should be skipped (price impact too high, price is too far
+/// from `twap_oracle` one or nothing to receive)
+pub fn query_reward_swap(
+    deps: Deps,
+    pair_contract: &Addr,
+    router_route: Option<RouterRoute>,
+    offer_asset: Asset,
+    max_spread: Decimal256,
+    twap_oracle: Option<&Addr>,
+) -> StdResult<Option<RewardSwap>> {
+    match router_route {
+        Some(route) => {
+            let reference_amount = query_reference_amount(deps, twap_oracle, &offer_asset)?;
+            let simulation = query_simulate_swap_operations(
+                deps,
+                route.router_contract,
+                offer_asset.amount,
+                route.operations.to_vec(),
+            )?;
+            Ok(
+                RouterSwap::from_simulation(
+                    &route,
+                    simulation.amount,
+                    max_spread,
+                    reference_amount,
+                )
+                .map(RewardSwap::Router),
+            )
+        }
+
+        None => Ok(
+            query_swap_limits(deps, pair_contract, offer_asset, max_spread, twap_oracle)?.map(
+                |limits| RewardSwap::Pair {
+                    pair_contract: pair_contract.clone(),
+                    limits,
+                },
+            ),
+        ),
+    }
+}
+
+/// `aterra_amount` and `aterra_exchange_rate` are shares and exchange rate
+/// of current yield venue (aUST for Anchor Earn)
 pub fn split_profit_to_handle_interest(
     borrowed_amount: Uint256,
     aterra_amount: Uint256,
@@ -485,6 +1072,105 @@ pub fn split_profit_to_handle_interest(
     };
 }
 
+/// bAsset and nAsset amounts which define nAsset price.
+/// Used by both execute and query paths, so previews can't diverge from executions.
+pub struct VaultBalances {
+    pub nasset_supply: Uint256,
+    pub basset_in_custody: Uint256,
+}
+
+impl VaultBalances {
+    pub fn query(deps: Deps, env: &Env, config: &Config) -> StdResult<Self> {
+        let nasset_supply: Uint256 = query_supply(&deps.querier, &config.nasset_token)?.into();
+        let basset_in_custody = config
+            .market_adapter()
+            .collateral_amount(deps, &env.contract.address)?;
+
+        Ok(VaultBalances {
+            nasset_supply,
+            basset_in_custody,
+        })
+    }
+
+    /// bAsset balance is zero, but nAsset supply is not.
+    /// No deposits and withdrawals allowed in that case (read comments in 'withdraw_basset')
+    pub fn is_frozen(&self) -> bool {
+        self.basset_in_custody.is_zero() && !self.nasset_supply.is_zero()
+    }
+
+    pub fn assert_not_frozen(&self) -> StdResult<()> {
+        if self.is_frozen() {
+            return Err(StdError::generic_err(
+                "bAsset balance is zero, but nAsset supply is not! Freeze contract.",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_first_deposit(&self) -> bool {
+        self.basset_in_custody.is_zero()
+    }
+
+    // nAsset tokens to mint:
+    // user_share = (deposited_basset / total_basset)
+    // nAsset_to_mint = nAsset_supply * user_share / (1 - user_share)
+    // first depositor gets 'deposit_amount' without MINIMUM_NASSET_LIQUIDITY,
+    // which is minted to vault itself
+    pub fn nasset_to_mint(&self, deposit_amount: Uint256) -> Uint256 {
+        if self.is_first_deposit() {
+            let minimum_liquidity = Uint256::from(MINIMUM_NASSET_LIQUIDITY);
+            if deposit_amount <= minimum_liquidity {
+                Uint256::zero()
+            } else {
+                deposit_amount - minimum_liquidity
+            }
+        } else {
+            // 'nasset_supply' can't be zero here, cause we already mint some for first farmer
+            self.nasset_supply * deposit_amount / Decimal256::from_uint256(self.basset_in_custody)
+        }
+    }
+
+    pub fn basset_to_withdraw(&self, nasset_amount: Uint256) -> Uint256 {
+        if self.nasset_supply.is_zero() {
+            return Uint256::zero();
+        }
+
+        self.basset_in_custody * nasset_amount / Decimal256::from_uint256(self.nasset_supply)
+    }
+}
+
+/// Loan and aUST left to unwind
+pub struct UnwindProgress {
+    pub loan_amount: Uint256,
+    pub aterra_balance: Uint256,
+}
+
+impl UnwindProgress {
+    pub fn query(deps: Deps, env: &Env, config: &Config) -> StdResult<Self> {
+        let loan_info = config
+            .market_adapter()
+            .loan_info(deps, &env.contract.address)?;
+        let aterra_balance = config
+            .venue_adapter()
+            .share_balance(deps, &env.contract.address)?;
+
+        Ok(UnwindProgress {
+            loan_amount: loan_info.loan_amount,
+            aterra_balance,
+        })
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.loan_amount.is_zero() && self.aterra_balance.is_zero()
+    }
+}
+
+/// Integer part of `value`, Anchor floors ANC rewards the same way on claim
+pub fn floor_to_uint(value: Decimal256) -> Uint256 {
+    Uint256::from(value.0 / Decimal256::DECIMAL_FRACTIONAL)
+}
+
 pub fn is_anc_rewards_claimable(
     deps: Deps,
     env: &Env,
@@ -507,18 +1193,28 @@ mod test {
     use crate::{state::store_last_anc_claim_seconds, tax_querier::TaxInfo};
 
     use super::{
-        calc_after_borrow_action, calc_wanted_stablecoins, get_repay_loan_action,
-        is_anc_rewards_claimable, split_profit_to_handle_interest, ActionWithProfit,
-        AfterBorrowAction, RepayLoanAction,
+        calc_after_borrow_action, calc_basset_to_sell_on_emergency, calc_estimated_apr,
+        calc_wanted_stablecoins, floor_to_uint, get_repay_loan_action, is_anc_rewards_claimable,
+        plan_loan_repayment, split_profit_to_handle_interest, ActionWithProfit, AfterBorrowAction,
+        RepayLoanAction, RepayPlan, SwapLimits,
+    };
+
+    use basset_vault::anchor::market::{
+        calc_redeemable_stables, StateResponse as AnchorMarketStateResponse,
     };
 
+    use basset_vault::astroport_pair::SimulationResponse;
     use cosmwasm_bignumber::{Decimal256, Uint256};
     use cosmwasm_std::{
         testing::{mock_dependencies, mock_env},
-        Timestamp,
+        Decimal, Timestamp, Uint128,
     };
     use std::str::FromStr;
 
+    fn enough_liquidity() -> Uint256 {
+        Uint256::from(u128::MAX)
+    }
+
     #[test]
     fn get_repay_loan_action_sell_all_1() {
         let aterra_balance = Uint256::from(500u64);
@@ -534,6 +1230,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -562,6 +1259,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -585,6 +1283,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -608,6 +1307,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -631,6 +1331,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -661,6 +1362,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -689,6 +1391,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -719,6 +1422,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -749,6 +1453,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -780,6 +1485,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -815,6 +1521,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -844,6 +1551,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -876,6 +1584,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -904,6 +1613,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -927,6 +1637,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -960,6 +1671,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -988,6 +1700,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1023,6 +1736,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1056,6 +1770,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1089,6 +1804,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1124,6 +1840,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1154,6 +1871,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1185,6 +1903,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1213,6 +1932,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1243,6 +1963,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1266,6 +1987,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1290,6 +2012,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1317,6 +2040,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1351,6 +2075,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1374,6 +2099,7 @@ mod test {
             stable_coin_balance,
             aterra_balance,
             aterra_exchange_rate,
+            enough_liquidity(),
             repay_amount,
             aim_buffer_size,
             &tax_info,
@@ -1388,6 +2114,86 @@ mod test {
         );
     }
 
+    #[test]
+    fn plan_loan_repayment_complete() {
+        let tax_info = TaxInfo {
+            rate: Decimal256::zero(),
+            cap: Uint256::zero(),
+        };
+        let repay_plan = plan_loan_repayment(
+            Uint256::from(100u64),
+            Uint256::from(500u64),
+            Decimal256::from_str("1.2").unwrap(),
+            enough_liquidity(),
+            Uint256::from(1_000u64),
+            Uint256::from(100u64),
+            &tax_info,
+        );
+        //want 1000 stables, but have only 500 aterra, redeem all of them
+        assert_eq!(
+            RepayPlan {
+                aterra_to_redeem: Uint256::from(500u64),
+                expected_stables: Uint256::from(600u64),
+                repay_amount: Uint256::from(700u64),
+                is_complete: true,
+            },
+            repay_plan
+        );
+    }
+
+    #[test]
+    fn plan_loan_repayment_liquidity_constrained() {
+        let tax_info = TaxInfo {
+            rate: Decimal256::zero(),
+            cap: Uint256::zero(),
+        };
+        let repay_plan = plan_loan_repayment(
+            Uint256::from(100u64),
+            Uint256::from(500u64),
+            Decimal256::from_str("1.2").unwrap(),
+            Uint256::from(120u64),
+            Uint256::from(1_000u64),
+            Uint256::from(100u64),
+            &tax_info,
+        );
+        //only 100 aterra can be redeemed for now
+        assert_eq!(
+            RepayPlan {
+                aterra_to_redeem: Uint256::from(100u64),
+                expected_stables: Uint256::from(120u64),
+                repay_amount: Uint256::from(220u64),
+                is_complete: false,
+            },
+            repay_plan
+        );
+    }
+
+    #[test]
+    fn plan_loan_repayment_nothing_to_redeem() {
+        let tax_info = TaxInfo {
+            rate: Decimal256::percent(1),
+            cap: Uint256::from(100u64),
+        };
+        let repay_plan = plan_loan_repayment(
+            Uint256::from(2_000u64),
+            Uint256::from(500u64),
+            Decimal256::from_str("1.2").unwrap(),
+            Uint256::zero(),
+            Uint256::from(1_000u64),
+            Uint256::from(100u64),
+            &tax_info,
+        );
+        assert_eq!(
+            RepayPlan {
+                aterra_to_redeem: Uint256::zero(),
+                expected_stables: Uint256::zero(),
+                repay_amount: Uint256::from(1_000u64),
+                is_complete: true,
+            },
+            repay_plan
+        );
+    }
+
     #[test]
     fn calc_wanted_stablecoins_1() {
         let stable_coin_balance = Uint256::zero();
@@ -1779,4 +2585,316 @@ mod test {
             assert!(!is_claimable.unwrap());
         }
     }
+
+    #[test]
+    fn swap_limits_from_simulation() {
+        let simulation = SimulationResponse {
+            return_amount: Uint128::new(1_940),
+            spread_amount: Uint128::new(20),
+            commission_amount: Uint128::new(40),
+        };
+        let swap_limits = SwapLimits::from_simulation(
+            Uint128::new(1_000),
+            &simulation,
+            Decimal256::percent(1),
+            None,
+        );
+        assert_eq!(
+            Some(SwapLimits {
+                belief_price: Decimal::from_str("0.5").unwrap(),
+                max_spread: Decimal::percent(1),
+                return_amount: Uint128::new(1_940),
+            }),
+            swap_limits
+        );
+    }
+
+    #[test]
+    fn swap_limits_from_simulation_price_impact_too_high() {
+        let simulation = SimulationResponse {
+            return_amount: Uint128::new(1_940),
+            spread_amount: Uint128::new(60),
+            commission_amount: Uint128::zero(),
+        };
+        let swap_limits = SwapLimits::from_simulation(
+            Uint128::new(1_000),
+            &simulation,
+            Decimal256::percent(1),
+            None,
+        );
+        assert_eq!(None, swap_limits);
+    }
+
+    #[test]
+    fn swap_limits_from_empty_simulation() {
+        let simulation = SimulationResponse {
+            return_amount: Uint128::zero(),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        };
+        let swap_limits = SwapLimits::from_simulation(
+            Uint128::new(1_000),
+            &simulation,
+            Decimal256::percent(1),
+            None,
+        );
+        assert_eq!(None, swap_limits);
+    }
+
+    #[test]
+    fn swap_limits_from_simulation_far_from_reference_price() {
+        //pool returns 2_000 by spot price, TWAP price gives 2_100
+        let simulation = SimulationResponse {
+            return_amount: Uint128::new(1_940),
+            spread_amount: Uint128::new(20),
+            commission_amount: Uint128::new(40),
+        };
+        let swap_limits = SwapLimits::from_simulation(
+            Uint128::new(1_000),
+            &simulation,
+            Decimal256::percent(1),
+            Some(Uint256::from(2_100u64)),
+        );
+        assert_eq!(None, swap_limits);
+
+        let swap_limits = SwapLimits::from_simulation(
+            Uint128::new(1_000),
+            &simulation,
+            Decimal256::percent(5),
+            Some(Uint256::from(2_100u64)),
+        );
+        assert_eq!(Uint128::new(1_940), swap_limits.unwrap().return_amount);
+    }
+
+    #[test]
+    fn get_repay_loan_action_repay_from_buffer_if_no_liquidity_to_redeem() {
+        let aterra_balance = Uint256::from(500u64);
+        let stable_coin_balance = Uint256::from(1_000u64);
+        let aterra_exchange_rate = Decimal256::one();
+        let repay_amount = Uint256::from(100u64);
+        let aim_buffer_size = Uint256::from(1_000u64);
+        let tax_info = TaxInfo {
+            rate: Decimal256::zero(),
+            cap: Uint256::zero(),
+        };
+        let repay_action = get_repay_loan_action(
+            stable_coin_balance,
+            aterra_balance,
+            aterra_exchange_rate,
+            Uint256::zero(),
+            repay_amount,
+            aim_buffer_size,
+            &tax_info,
+            true,
+        );
+        assert_eq!(
+            RepayLoanAction::RepayLoan {
+                amount: repay_amount
+            },
+            repay_action
+        );
+    }
+
+    #[test]
+    fn get_repay_loan_action_sell_only_redeemable_aterra() {
+        let aterra_balance = Uint256::from(500u64);
+        let stable_coin_balance = Uint256::zero();
+        let aterra_exchange_rate = Decimal256::from_str("2").unwrap();
+        let redeemable_stables = Uint256::from(300u64);
+        let repay_amount = Uint256::from(1_000u64);
+        let aim_buffer_size = Uint256::zero();
+        let tax_info = TaxInfo {
+            rate: Decimal256::zero(),
+            cap: Uint256::zero(),
+        };
+        let repay_action = get_repay_loan_action(
+            stable_coin_balance,
+            aterra_balance,
+            aterra_exchange_rate,
+            redeemable_stables,
+            repay_amount,
+            aim_buffer_size,
+            &tax_info,
+            true,
+        );
+        assert_eq!(
+            RepayLoanAction::SellAterra {
+                amount: Uint256::from(150u64)
+            },
+            repay_action
+        );
+    }
+
+    #[test]
+    fn redeemable_stables_exclude_market_reserves() {
+        let market_state = AnchorMarketStateResponse {
+            total_liabilities: Decimal256::from_str("5000").unwrap(),
+            total_reserves: Decimal256::from_str("100.5").unwrap(),
+            anc_emission_rate: Decimal256::zero(),
+        };
+        assert_eq!(
+            Uint256::from(899u64),
+            calc_redeemable_stables(&market_state, Uint256::from(1_000u64))
+        );
+        assert_eq!(
+            Uint256::zero(),
+            calc_redeemable_stables(&market_state, Uint256::from(50u64))
+        );
+    }
+
+    #[test]
+    fn estimated_apr_covers_interest_before_nasset_holders_share() {
+        let estimated_apr = calc_estimated_apr(
+            Decimal256::from_str("0.00000004").unwrap(),
+            Decimal256::from_str("0.00000005").unwrap(),
+            Decimal256::from_str("20").unwrap(),
+            Decimal256::from_str("1000000000").unwrap(),
+            Decimal256::from_str("2").unwrap(),
+            Decimal256::from_str("0.5").unwrap(),
+            Decimal256::from_str("0.1").unwrap(),
+        );
+        assert_eq!(
+            Decimal256::from_str("0.1862724").unwrap(),
+            estimated_apr.deposit_apr
+        );
+        assert_eq!(
+            Decimal256::from_str("0.2328405").unwrap(),
+            estimated_apr.borrow_apr
+        );
+        //20 * 4_656_810 * 2 / 1_000_000_000
+        assert_eq!(
+            Decimal256::from_str("0.1862724").unwrap(),
+            estimated_apr.distribution_apr
+        );
+        assert_eq!(
+            Decimal256::from_str("0.11642025").unwrap(),
+            estimated_apr.borrow_cost
+        );
+        assert_eq!(
+            Decimal256::from_str("0.9").unwrap(),
+            estimated_apr.nasset_holder_share
+        );
+        //(0.0931362 + 0.0931362 - 0.11642025) * 0.9
+        assert_eq!(
+            Decimal256::from_str("0.062866935").unwrap(),
+            estimated_apr.apr
+        );
+    }
+
+    #[test]
+    fn estimated_apr_is_zero_when_interest_exceeds_income() {
+        let estimated_apr = calc_estimated_apr(
+            Decimal256::from_str("0.00000001").unwrap(),
+            Decimal256::from_str("0.00000005").unwrap(),
+            Decimal256::zero(),
+            Decimal256::zero(),
+            Decimal256::from_str("2").unwrap(),
+            Decimal256::from_str("0.5").unwrap(),
+            Decimal256::zero(),
+        );
+        assert_eq!(Decimal256::zero(), estimated_apr.distribution_apr);
+        assert_eq!(Decimal256::zero(), estimated_apr.apr);
+    }
+
+    #[test]
+    fn basset_to_sell_on_emergency_zero_if_ltv_is_below_trigger() {
+        //collateral value is 1000, LTV is 0.5
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            Uint256::from(500u64),
+            Uint256::from(100u64),
+            Decimal256::from_str("10").unwrap(),
+            Uint256::zero(),
+            Decimal256::from_str("0.55").unwrap(),
+            Decimal256::from_str("0.7").unwrap(),
+            Uint256::from(50u64),
+        );
+        assert_eq!(Uint256::zero(), basset_to_sell);
+    }
+
+    #[test]
+    fn basset_to_sell_on_emergency_zero_if_stables_are_enough() {
+        //LTV is 0.58, but 30 UST is enough to get it back to 0.55
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            Uint256::from(580u64),
+            Uint256::from(100u64),
+            Decimal256::from_str("10").unwrap(),
+            Uint256::from(30u64),
+            Decimal256::from_str("0.55").unwrap(),
+            Decimal256::from_str("0.7").unwrap(),
+            Uint256::from(50u64),
+        );
+        assert_eq!(Uint256::zero(), basset_to_sell);
+    }
+
+    #[test]
+    fn basset_to_sell_on_emergency() {
+        //(580 - 550 - 10) / (10 * (1 - 0.55)) = 4.44
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            Uint256::from(580u64),
+            Uint256::from(100u64),
+            Decimal256::from_str("10").unwrap(),
+            Uint256::from(10u64),
+            Decimal256::from_str("0.55").unwrap(),
+            Decimal256::from_str("0.7").unwrap(),
+            Uint256::from(50u64),
+        );
+        assert_eq!(Uint256::from(4u64), basset_to_sell);
+
+        //limited by governance
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            Uint256::from(580u64),
+            Uint256::from(100u64),
+            Decimal256::from_str("10").unwrap(),
+            Uint256::from(10u64),
+            Decimal256::from_str("0.55").unwrap(),
+            Decimal256::from_str("0.7").unwrap(),
+            Uint256::from(3u64),
+        );
+        assert_eq!(Uint256::from(3u64), basset_to_sell);
+    }
+
+    #[test]
+    fn basset_to_sell_on_emergency_is_limited_by_unlockable_amount() {
+        //LTV 0.58 is between trigger 0.55 and max 0.6
+        let loan_amount = Uint256::from(580u64);
+        let basset_in_custody = Uint256::from(100u64);
+        let basset_price = Decimal256::from_str("10").unwrap();
+        let basset_max_ltv = Decimal256::from_str("0.6").unwrap();
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            loan_amount,
+            basset_in_custody,
+            basset_price,
+            Uint256::zero(),
+            Decimal256::from_str("0.55").unwrap(),
+            basset_max_ltv,
+            Uint256::from(50u64),
+        );
+        //(1_000 * 0.6 - 580) / (10 * 0.6) = 3.33
+        assert_eq!(Uint256::from(3u64), basset_to_sell);
+
+        //Anchor overseer accepts unlock: loan is still under borrow limit
+        let borrow_limit = (basset_in_custody - basset_to_sell) * basset_price * basset_max_ltv;
+        assert!(loan_amount <= borrow_limit);
+
+        //nothing can be unlocked above max LTV
+        let basset_to_sell = calc_basset_to_sell_on_emergency(
+            Uint256::from(600u64),
+            basset_in_custody,
+            basset_price,
+            Uint256::zero(),
+            Decimal256::from_str("0.55").unwrap(),
+            basset_max_ltv,
+            Uint256::from(50u64),
+        );
+        assert_eq!(Uint256::zero(), basset_to_sell);
+    }
+
+    #[test]
+    fn floor_decimal_to_uint() {
+        assert_eq!(
+            Uint256::from(1_234u64),
+            floor_to_uint(Decimal256::from_str("1234.999999").unwrap())
+        );
+        assert_eq!(Uint256::zero(), floor_to_uint(Decimal256::percent(99)));
+    }
 }
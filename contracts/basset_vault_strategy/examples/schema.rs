@@ -2,8 +2,9 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use basset_vault::basset_vault_strategy::{
-    BorrowerActionResponse, ConfigResponse, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg,
+    BorrowerActionResponse, CarryConfigResponse, CarryResponse, ConfigResponse,
+    EffectiveBandsResponse, ExecuteMsg, FreshPriceResponse, GovernanceMsg, InstantiateMsg,
+    LtvRampResponse, MigrateMsg, PriceSourcesResponse, QueryMsg, VolatilityConfigResponse,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -19,5 +20,12 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(BorrowerActionResponse), &out_dir);
+    export_schema(&schema_for!(PriceSourcesResponse), &out_dir);
+    export_schema(&schema_for!(CarryConfigResponse), &out_dir);
+    export_schema(&schema_for!(FreshPriceResponse), &out_dir);
+    export_schema(&schema_for!(CarryResponse), &out_dir);
+    export_schema(&schema_for!(VolatilityConfigResponse), &out_dir);
+    export_schema(&schema_for!(EffectiveBandsResponse), &out_dir);
+    export_schema(&schema_for!(LtvRampResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
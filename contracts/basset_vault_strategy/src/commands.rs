@@ -2,17 +2,23 @@ use cosmwasm_std::{BlockInfo, DepsMut, Env, MessageInfo, Response, StdError};
 
 use crate::{
     error::ContractError,
+    queries::query_fresh_price,
     state::{
-        load_config, load_gov_update, remove_gov_update, save_config, save_gov_update, Config,
-        GovernanceUpdateState,
+        load_borrow_ltvs, load_carry_config, load_config, load_gov_update, load_ltv_ramp,
+        load_price_observations, load_price_sources, load_volatility_config,
+        push_price_observation, remove_gov_update, remove_ltv_ramp, save_carry_config, save_config,
+        save_gov_update, save_ltv_ramp, save_price_sources, save_volatility_config, Config,
+        GovernanceUpdateState, LtvRamp, PriceObservation,
     },
-    ContractResult,
+    ContractResult, MAX_PRICE_OBSERVATIONS,
 };
+use basset_vault::basset_vault_strategy::{PriceSource, StalePricePolicy};
 use cosmwasm_bignumber::Decimal256;
 
 /// Executor: governance
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     mut current_config: Config,
     oracle_addr: Option<String>,
     basset_token_addr: Option<String>,
@@ -23,6 +29,7 @@ pub fn update_config(
     basset_max_ltv: Option<Decimal256>,
     buffer_part: Option<Decimal256>,
     price_timeframe: Option<u64>,
+    stale_price_policy: Option<StalePricePolicy>,
 ) -> ContractResult<Response> {
     if let Some(ref oracle_addr) = oracle_addr {
         current_config.oracle_contract = deps.api.addr_validate(oracle_addr)?;
@@ -36,11 +43,16 @@ pub fn update_config(
         current_config.stable_denom = stable_denom;
     }
 
-    current_config.validate_and_set_borrow_ltvs(
-        borrow_ltv_max.unwrap_or(current_config.get_borrow_ltv_max()),
-        borrow_ltv_min.unwrap_or(current_config.get_borrow_ltv_min()),
-        borrow_ltv_aim.unwrap_or(current_config.get_borrow_ltv_aim()),
-    )?;
+    if borrow_ltv_max.is_some() || borrow_ltv_min.is_some() || borrow_ltv_aim.is_some() {
+        //instant change stops the ramp at its current point
+        let current_ltvs = load_borrow_ltvs(deps.storage, &current_config, get_time(&env.block))?;
+        current_config.validate_and_set_borrow_ltvs(
+            borrow_ltv_max.unwrap_or(current_ltvs.max),
+            borrow_ltv_min.unwrap_or(current_ltvs.min),
+            borrow_ltv_aim.unwrap_or(current_ltvs.aim),
+        )?;
+        remove_ltv_ramp(deps.storage);
+    }
 
     if let Some(basset_max_ltv) = basset_max_ltv {
         current_config.set_basset_max_ltv(basset_max_ltv)?;
@@ -48,16 +60,254 @@ pub fn update_config(
 
     if let Some(buffer_part) = buffer_part {
         current_config.set_buffer_part(buffer_part)?;
+        //bands move linearly, so buffer enough for both ends of ramp is enough in between
+        if let Some(ltv_ramp) = load_ltv_ramp(deps.storage)? {
+            if ltv_ramp.is_active(get_time(&env.block)) {
+                Config::validate_buffer_part(buffer_part, &ltv_ramp.start)?;
+            }
+        }
     }
 
     if let Some(price_timeframe) = price_timeframe {
         current_config.price_timeframe = price_timeframe;
     }
 
+    if let Some(stale_price_policy) = stale_price_policy {
+        if let StalePricePolicy::Fallback { ref source } = stale_price_policy {
+            validate_price_source(&deps, source)?;
+        }
+        current_config.set_stale_price_policy(stale_price_policy)?;
+    }
+
     save_config(deps.storage, &current_config)?;
     Ok(Response::default())
 }
 
+/// Executor: governance
+pub fn ramp_ltv(
+    deps: DepsMut,
+    env: Env,
+    mut config: Config,
+    target_aim: Decimal256,
+    target_min: Decimal256,
+    target_max: Decimal256,
+    end_time: u64,
+) -> ContractResult<Response> {
+    let current_time = get_time(&env.block);
+    if end_time <= current_time {
+        return Err(ContractError::InappropriateValue);
+    }
+
+    let start = load_borrow_ltvs(deps.storage, &config, current_time)?;
+    //start bands are valid, so every point between them and valid targets is valid too
+    config.validate_and_set_borrow_ltvs(target_max, target_min, target_aim)?;
+    //buffer have to be enough for target bands as well
+    config.set_buffer_part(config.get_buffer_part())?;
+
+    save_config(deps.storage, &config)?;
+    save_ltv_ramp(
+        deps.storage,
+        &LtvRamp {
+            start_time: current_time,
+            end_time,
+            start,
+        },
+    )?;
+    Ok(Response::default())
+}
+
+/// Executor: governance
+pub fn stop_ramp(deps: DepsMut, env: Env, mut config: Config) -> ContractResult<Response> {
+    let current_time = get_time(&env.block);
+    let ltv_ramp = match load_ltv_ramp(deps.storage)? {
+        Some(ltv_ramp) if ltv_ramp.is_active(current_time) => ltv_ramp,
+        _ => return Err(StdError::generic_err("no active ramp").into()),
+    };
+
+    let current_ltvs = ltv_ramp.borrow_ltvs_at(&config.get_borrow_ltvs(), current_time);
+    config.validate_and_set_borrow_ltvs(current_ltvs.max, current_ltvs.min, current_ltvs.aim)?;
+
+    save_config(deps.storage, &config)?;
+    remove_ltv_ramp(deps.storage);
+    Ok(Response::default())
+}
+
+/// Executor: governance
+pub fn update_price_sources(
+    deps: DepsMut,
+    price_sources: Option<Vec<PriceSource>>,
+    max_deviation: Option<Decimal256>,
+) -> ContractResult<Response> {
+    let mut sources_config = load_price_sources(deps.storage)?;
+
+    if let Some(price_sources) = price_sources {
+        for source in price_sources.iter() {
+            validate_price_source(&deps, source)?;
+        }
+        sources_config.price_sources = price_sources;
+    }
+
+    if let Some(max_deviation) = max_deviation {
+        if max_deviation >= Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        sources_config.max_deviation = max_deviation;
+    }
+
+    //sources would never agree
+    if !sources_config.price_sources.is_empty() && sources_config.max_deviation.is_zero() {
+        return Err(ContractError::InappropriateValue);
+    }
+
+    save_price_sources(deps.storage, &sources_config)?;
+    Ok(Response::default())
+}
+
+/// Executor: governance
+pub fn update_carry_config(
+    deps: DepsMut,
+    anchor_market_addr: Option<String>,
+    anchor_overseer_addr: Option<String>,
+    anc_stable_swap_addr: Option<String>,
+    anchor_token_addr: Option<String>,
+    min_spread: Option<Decimal256>,
+    negative_carry_ltv_factor: Option<Decimal256>,
+) -> ContractResult<Response> {
+    let mut carry_config = load_carry_config(deps.storage)?;
+
+    if let Some(ref anchor_market_addr) = anchor_market_addr {
+        carry_config.anchor_market_contract = Some(deps.api.addr_validate(anchor_market_addr)?);
+    }
+
+    if let Some(ref anchor_overseer_addr) = anchor_overseer_addr {
+        carry_config.anchor_overseer_contract = Some(deps.api.addr_validate(anchor_overseer_addr)?);
+    }
+
+    if let Some(ref anc_stable_swap_addr) = anc_stable_swap_addr {
+        carry_config.anc_stable_swap_contract = Some(deps.api.addr_validate(anc_stable_swap_addr)?);
+    }
+
+    if let Some(ref anchor_token_addr) = anchor_token_addr {
+        carry_config.anchor_token = Some(deps.api.addr_validate(anchor_token_addr)?);
+    }
+
+    if let Some(min_spread) = min_spread {
+        carry_config.min_spread = min_spread;
+    }
+
+    if let Some(negative_carry_ltv_factor) = negative_carry_ltv_factor {
+        if negative_carry_ltv_factor > Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        carry_config.negative_carry_ltv_factor = negative_carry_ltv_factor;
+    }
+
+    save_carry_config(deps.storage, &carry_config)?;
+    Ok(Response::default())
+}
+
+/// Executor: governance
+pub fn update_volatility_config(
+    deps: DepsMut,
+    max_observations: Option<u32>,
+    min_interval: Option<u64>,
+    calm_volatility: Option<Decimal256>,
+    stress_volatility: Option<Decimal256>,
+    max_ltv_reduction: Option<Decimal256>,
+    max_buffer_part_increase: Option<Decimal256>,
+) -> ContractResult<Response> {
+    let mut volatility_config = load_volatility_config(deps.storage)?;
+
+    if let Some(max_observations) = max_observations {
+        if max_observations > MAX_PRICE_OBSERVATIONS {
+            return Err(ContractError::InappropriateValue);
+        }
+        volatility_config.max_observations = max_observations;
+    }
+
+    if let Some(min_interval) = min_interval {
+        volatility_config.min_interval = min_interval;
+    }
+
+    if let Some(calm_volatility) = calm_volatility {
+        volatility_config.calm_volatility = calm_volatility;
+    }
+
+    if let Some(stress_volatility) = stress_volatility {
+        volatility_config.stress_volatility = stress_volatility;
+    }
+
+    if let Some(max_ltv_reduction) = max_ltv_reduction {
+        if max_ltv_reduction > Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        volatility_config.max_ltv_reduction = max_ltv_reduction;
+    }
+
+    if let Some(max_buffer_part_increase) = max_buffer_part_increase {
+        if max_buffer_part_increase > Decimal256::one() {
+            return Err(ContractError::InappropriateValue);
+        }
+        volatility_config.max_buffer_part_increase = max_buffer_part_increase;
+    }
+
+    if !volatility_config.stress_volatility.is_zero()
+        && volatility_config.stress_volatility <= volatility_config.calm_volatility
+    {
+        return Err(ContractError::InappropriateValue);
+    }
+
+    save_volatility_config(deps.storage, &volatility_config)?;
+    Ok(Response::default())
+}
+
+/// Executor: anyone
+pub fn record_price(deps: DepsMut, env: Env) -> ContractResult<Response> {
+    let volatility_config = load_volatility_config(deps.storage)?;
+    if !volatility_config.is_enabled() {
+        return Err(StdError::generic_err("volatility tracking is disabled").into());
+    }
+
+    let current_time = get_time(&env.block);
+    let observations = load_price_observations(deps.storage)?;
+    if let Some(last_observation) = observations.last() {
+        if current_time < last_observation.time + volatility_config.min_interval {
+            return Err(StdError::generic_err("too early to record price").into());
+        }
+    }
+
+    let config = load_config(deps.storage)?;
+    let price = query_fresh_price(deps.as_ref(), &env, &config)?;
+    push_price_observation(
+        deps.storage,
+        PriceObservation {
+            time: current_time,
+            price,
+        },
+        volatility_config.max_observations,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "record_price"),
+        ("price", &price.to_string()),
+    ]))
+}
+
+fn validate_price_source(deps: &DepsMut, source: &PriceSource) -> ContractResult<()> {
+    match source {
+        PriceSource::Oracle { contract_addr } => {
+            deps.api.addr_validate(contract_addr)?;
+        }
+        PriceSource::AstroportTwap {
+            oracle_contract_addr,
+        } => {
+            deps.api.addr_validate(oracle_contract_addr)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn update_governance_addr(
     deps: DepsMut,
     env: Env,
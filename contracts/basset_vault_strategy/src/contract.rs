@@ -48,6 +48,7 @@ pub fn execute(
     match msg {
         ExecuteMsg::Anyone { anyone_msg } => match anyone_msg {
             AnyoneMsg::AcceptGovernance {} => commands::accept_governance(deps, env, info),
+            AnyoneMsg::RecordPrice {} => commands::record_price(deps, env),
         },
 
         ExecuteMsg::Governance { governance_msg } => {
@@ -67,8 +68,10 @@ pub fn execute(
                     basset_max_ltv,
                     buffer_part,
                     price_timeframe,
+                    stale_price_policy,
                 } => commands::update_config(
                     deps,
+                    env,
                     config,
                     oracle_addr,
                     basset_token_addr,
@@ -79,6 +82,7 @@ pub fn execute(
                     basset_max_ltv,
                     buffer_part,
                     price_timeframe,
+                    stale_price_policy,
                 ),
 
                 GovernanceMsg::UpdateGovernanceContract {
@@ -90,6 +94,56 @@ pub fn execute(
                     gov_addr,
                     seconds_to_wait_for_accept_gov_tx,
                 ),
+
+                GovernanceMsg::UpdatePriceSources {
+                    price_sources,
+                    max_deviation,
+                } => commands::update_price_sources(deps, price_sources, max_deviation),
+
+                GovernanceMsg::UpdateCarryConfig {
+                    anchor_market_addr,
+                    anchor_overseer_addr,
+                    anc_stable_swap_addr,
+                    anchor_token_addr,
+                    min_spread,
+                    negative_carry_ltv_factor,
+                } => commands::update_carry_config(
+                    deps,
+                    anchor_market_addr,
+                    anchor_overseer_addr,
+                    anc_stable_swap_addr,
+                    anchor_token_addr,
+                    min_spread,
+                    negative_carry_ltv_factor,
+                ),
+
+                GovernanceMsg::UpdateVolatilityConfig {
+                    max_observations,
+                    min_interval,
+                    calm_volatility,
+                    stress_volatility,
+                    max_ltv_reduction,
+                    max_buffer_part_increase,
+                } => commands::update_volatility_config(
+                    deps,
+                    max_observations,
+                    min_interval,
+                    calm_volatility,
+                    stress_volatility,
+                    max_ltv_reduction,
+                    max_buffer_part_increase,
+                ),
+
+                GovernanceMsg::RampLtv {
+                    target_aim,
+                    target_min,
+                    target_max,
+                    end_time,
+                } => commands::ramp_ltv(
+                    deps, env, config, target_aim, target_min, target_max, end_time,
+                ),
+
+                GovernanceMsg::StopRamp {} => commands::stop_ramp(deps, env, config),
             }
         }
     }
@@ -108,6 +162,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             borrowed_amount,
             locked_basset_amount,
         )?),
+        QueryMsg::PriceSources {} => to_binary(&queries::query_price_sources(deps, env)?),
+        QueryMsg::FreshPrice {} => to_binary(&queries::query_fresh_price_response(deps, env)?),
+        QueryMsg::CarryConfig {} => to_binary(&queries::query_carry_config(deps)?),
+        QueryMsg::Carry {} => to_binary(&queries::query_carry(deps)?),
+        QueryMsg::VolatilityConfig {} => to_binary(&queries::query_volatility_config(deps)?),
+        QueryMsg::EffectiveBands {} => to_binary(&queries::query_effective_bands(deps, env)?),
+        QueryMsg::LtvRamp {} => to_binary(&queries::query_ltv_ramp(deps, env)?),
     }
 }
 
@@ -3,7 +3,6 @@ use error::ContractError;
 mod commands;
 pub mod contract;
 pub mod error;
-mod price;
 mod queries;
 pub mod state;
 
@@ -12,3 +11,9 @@ pub mod state;
 mod tests;
 
 type ContractResult<T> = Result<T, ContractError>;
+
+//to keep 'RecordPrice' and 'BorrowerAction' gas bounded
+pub const MAX_PRICE_OBSERVATIONS: u32 = 100;
+
+//realised volatility is price change per hour
+pub const VOLATILITY_PERIOD: u64 = 3600;
@@ -1,9 +1,29 @@
-use crate::price::{query_price, PriceResponse};
-use basset_vault::basset_vault_strategy::{BorrowerActionResponse, ConfigResponse};
+use basset_vault::anchor::market::{
+    calc_anchor_aprs, query_borrow_rate, query_market_config, query_market_state, AnchorAprs,
+};
+use basset_vault::anchor::oracle::{query_price, PriceResponse};
+use basset_vault::anchor::overseer::query_deposit_rate;
+use basset_vault::astroport_oracle::query_consult;
+use basset_vault::astroport_pair::query_spot_price;
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, CarryConfigResponse, CarryResponse, ConfigResponse,
+    EffectiveBandsResponse, FreshPriceResponse, LtvRampResponse, PriceSource, PriceSourcesResponse,
+    SourcePriceResponse, StalePricePolicy, VolatilityConfigResponse,
+};
+use basset_vault::querier::query_balance;
+use basset_vault::terraswap::AssetInfo;
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Deps, Env, StdResult, Timestamp};
+use cosmwasm_std::{Addr, Deps, Env, StdError, StdResult, Timestamp, Uint128};
 
-use crate::state::{load_config, Config};
+use crate::state::{
+    load_borrow_ltvs, load_carry_config, load_config, load_ltv_ramp, load_price_observations,
+    load_price_sources, load_volatility_config, CarryConfig, Config, PriceObservation,
+    PriceSourcesConfig, VolatilityConfig,
+};
+use crate::VOLATILITY_PERIOD;
+
+//bAsset amount to ask TWAP for, 1 bAsset with 6 decimals
+const TWAP_CONSULT_AMOUNT: u128 = 1_000_000;
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = load_config(deps.storage)?;
@@ -18,6 +38,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         basset_max_ltv: config.get_basset_max_ltv(),
         buffer_part: config.get_buffer_part(),
         price_timeframe: config.price_timeframe,
+        stale_price_policy: config.get_stale_price_policy().clone(),
     })
 }
 
@@ -36,29 +57,46 @@ impl LTVInfo {
         price_timeframe: u64,
         price: &PriceResponse,
         block_time: Timestamp,
+        stale_price_policy: &StalePricePolicy,
     ) -> Self {
-        let valid_update_time = block_time.seconds() - price_timeframe;
-        if price.last_updated_base < valid_update_time
-            || price.last_updated_quote < valid_update_time
-        {
-            //if price is too old we divide our LTV by half to avoid sharp liquidation
-            return Self {
+        match stale_price_policy {
+            //if price is too old we scale our LTV down to avoid sharp liquidation
+            StalePricePolicy::ScaleLtv { factor }
+                if is_price_stale(price, price_timeframe, block_time) =>
+            {
+                Self {
+                    basset_price: price.rate,
+                    borrow_ltv_max,
+                    borrow_ltv_min,
+                    borrow_ltv_aim,
+                }
+                .scaled(*factor)
+            }
+
+            _ => Self {
                 basset_price: price.rate,
-                borrow_ltv_max: borrow_ltv_max * Decimal256::percent(50),
-                borrow_ltv_min: borrow_ltv_min * Decimal256::percent(50),
-                borrow_ltv_aim: borrow_ltv_aim * Decimal256::percent(50),
-            };
+                borrow_ltv_max,
+                borrow_ltv_min,
+                borrow_ltv_aim,
+            },
         }
+    }
 
-        return Self {
-            basset_price: price.rate,
-            borrow_ltv_max,
-            borrow_ltv_min,
-            borrow_ltv_aim,
-        };
+    fn scaled(self, factor: Decimal256) -> Self {
+        Self {
+            basset_price: self.basset_price,
+            borrow_ltv_max: self.borrow_ltv_max * factor,
+            borrow_ltv_min: self.borrow_ltv_min * factor,
+            borrow_ltv_aim: self.borrow_ltv_aim * factor,
+        }
     }
 }
 
+fn is_price_stale(price: &PriceResponse, price_timeframe: u64, block_time: Timestamp) -> bool {
+    let valid_update_time = block_time.seconds() - price_timeframe;
+    price.last_updated_base < valid_update_time || price.last_updated_quote < valid_update_time
+}
+
 pub fn borrower_action(
     deps: Deps,
     env: Env,
@@ -66,32 +104,562 @@ pub fn borrower_action(
     locked_basset_amount: Uint256,
 ) -> StdResult<BorrowerActionResponse> {
     let config: Config = load_config(deps.storage)?;
+    let sources_config = load_price_sources(deps.storage)?;
 
-    let oracle_price: PriceResponse = query_price(
-        deps,
-        &config.oracle_contract,
-        config.basset_token.to_string(),
-        config.stable_denom.to_string(),
-    )?;
+    let aggregated_price = aggregate_prices(deps, &env, &config, &sources_config);
+    //do not lever up on price we can't trust, but repaying is always fine
+    let mut borrow_allowed = is_borrow_allowed(&aggregated_price, &sources_config);
+    let mut oracle_price = aggregated_price
+        .price
+        .ok_or_else(|| StdError::generic_err("no price source is available"))?;
+
+    let price_is_stale = is_price_stale(&oracle_price, config.price_timeframe, env.block.time);
+    if price_is_stale {
+        match config.get_stale_price_policy() {
+            StalePricePolicy::ScaleLtv { .. } => {}
+
+            StalePricePolicy::Freeze {} => {
+                return Ok(BorrowerActionResponse::nothing().with_stale_price());
+            }
+
+            StalePricePolicy::RepayOnly {} => borrow_allowed = false,
+
+            StalePricePolicy::Fallback { source } => {
+                match query_source_price(deps, &env, &config, source) {
+                    Ok(fallback_price)
+                        if !is_price_stale(
+                            &fallback_price,
+                            config.price_timeframe,
+                            env.block.time,
+                        ) =>
+                    {
+                        oracle_price = fallback_price
+                    }
+                    _ => borrow_allowed = false,
+                }
+            }
+        }
+    }
 
-    let ltv_info = LTVInfo::new(
-        config.get_borrow_ltv_max(),
-        config.get_borrow_ltv_min(),
-        config.get_borrow_ltv_aim(),
+    //do not lever up into negative carry, and deleverage while it lasts
+    let carry_config = load_carry_config(deps.storage)?;
+    let mut negative_carry = false;
+    if carry_config.is_enabled() {
+        match load_carry(deps, &config, &carry_config) {
+            Ok(carry) => {
+                negative_carry = carry.is_negative();
+                borrow_allowed &= carry.allows_borrowing(carry_config.min_spread);
+            }
+            //can't tell if borrowing is profitable
+            Err(_) => borrow_allowed = false,
+        }
+    }
+
+    let bands = load_effective_bands(deps, &env, &config)?.bands;
+    let mut ltv_info = LTVInfo::new(
+        bands.borrow_ltv_max,
+        bands.borrow_ltv_min,
+        bands.borrow_ltv_aim,
         config.price_timeframe,
         &oracle_price,
         env.block.time,
+        config.get_stale_price_policy(),
     );
+    if negative_carry {
+        ltv_info = ltv_info.scaled(carry_config.negative_carry_ltv_factor);
+    }
 
     let response = calc_borrower_action(
         ltv_info,
         borrowed_amount,
         locked_basset_amount,
         config.get_basset_max_ltv(),
-        config.get_buffer_part(),
+        bands.buffer_part,
     );
 
-    Ok(response)
+    let response = match response {
+        BorrowerActionResponse::Borrow { .. } if !borrow_allowed => {
+            BorrowerActionResponse::nothing()
+        }
+        _ => response,
+    };
+
+    if price_is_stale {
+        Ok(response.with_stale_price())
+    } else {
+        Ok(response)
+    }
+}
+
+pub fn query_price_sources(deps: Deps, env: Env) -> StdResult<PriceSourcesResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let sources_config = load_price_sources(deps.storage)?;
+
+    let aggregated_price = aggregate_prices(deps, &env, &config, &sources_config);
+    let borrow_allowed =
+        aggregated_price.price.is_some() && is_borrow_allowed(&aggregated_price, &sources_config);
+
+    Ok(PriceSourcesResponse {
+        sources: aggregated_price.sources,
+        median_price: aggregated_price.price.map(|price| price.rate),
+        deviation: aggregated_price.deviation,
+        max_deviation: sources_config.max_deviation,
+        borrow_allowed,
+    })
+}
+
+pub fn query_carry_config(deps: Deps) -> StdResult<CarryConfigResponse> {
+    let carry_config = load_carry_config(deps.storage)?;
+    Ok(CarryConfigResponse {
+        anchor_market_contract: carry_config
+            .anchor_market_contract
+            .as_ref()
+            .map(|addr| addr.to_string()),
+        anchor_overseer_contract: carry_config
+            .anchor_overseer_contract
+            .as_ref()
+            .map(|addr| addr.to_string()),
+        anc_stable_swap_contract: carry_config
+            .anc_stable_swap_contract
+            .as_ref()
+            .map(|addr| addr.to_string()),
+        anchor_token: carry_config
+            .anchor_token
+            .as_ref()
+            .map(|addr| addr.to_string()),
+        min_spread: carry_config.min_spread,
+        negative_carry_ltv_factor: carry_config.negative_carry_ltv_factor,
+        enabled: carry_config.is_enabled(),
+    })
+}
+
+pub fn query_carry(deps: Deps) -> StdResult<CarryResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let carry_config = load_carry_config(deps.storage)?;
+
+    let carry = load_carry(deps, &config, &carry_config)?;
+    Ok(CarryResponse {
+        deposit_apr: carry.deposit_apr,
+        distribution_apr: carry.distribution_apr,
+        borrow_apr: carry.borrow_apr,
+        spread: carry.spread(),
+        is_negative: carry.is_negative(),
+        borrow_allowed: carry.allows_borrowing(carry_config.min_spread),
+    })
+}
+
+pub fn query_volatility_config(deps: Deps) -> StdResult<VolatilityConfigResponse> {
+    let volatility_config = load_volatility_config(deps.storage)?;
+    Ok(VolatilityConfigResponse {
+        max_observations: volatility_config.max_observations,
+        min_interval: volatility_config.min_interval,
+        calm_volatility: volatility_config.calm_volatility,
+        stress_volatility: volatility_config.stress_volatility,
+        max_ltv_reduction: volatility_config.max_ltv_reduction,
+        max_buffer_part_increase: volatility_config.max_buffer_part_increase,
+        enabled: volatility_config.is_enabled(),
+    })
+}
+
+pub fn query_effective_bands(deps: Deps, env: Env) -> StdResult<EffectiveBandsResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let effective_bands = load_effective_bands(deps, &env, &config)?;
+    Ok(EffectiveBandsResponse {
+        borrow_ltv_max: effective_bands.bands.borrow_ltv_max,
+        borrow_ltv_min: effective_bands.bands.borrow_ltv_min,
+        borrow_ltv_aim: effective_bands.bands.borrow_ltv_aim,
+        buffer_part: effective_bands.bands.buffer_part,
+        volatility: effective_bands.volatility,
+        observations: effective_bands.observations,
+    })
+}
+
+pub fn query_ltv_ramp(deps: Deps, env: Env) -> StdResult<LtvRampResponse> {
+    let config: Config = load_config(deps.storage)?;
+    let ltv_ramp = load_ltv_ramp(deps.storage)?;
+    let current_time = env.block.time.seconds();
+
+    let target = config.get_borrow_ltvs();
+    let current = load_borrow_ltvs(deps.storage, &config, current_time)?;
+    Ok(LtvRampResponse {
+        borrow_ltv_max: current.max,
+        borrow_ltv_min: current.min,
+        borrow_ltv_aim: current.aim,
+        target_borrow_ltv_max: target.max,
+        target_borrow_ltv_min: target.min,
+        target_borrow_ltv_aim: target.aim,
+        start_time: ltv_ramp.as_ref().map(|ramp| ramp.start_time),
+        end_time: ltv_ramp.as_ref().map(|ramp| ramp.end_time),
+        is_active: ltv_ramp.is_some_and(|ramp| ramp.is_active(current_time)),
+    })
+}
+
+/// Median price of all sources, fails if it is stale
+pub fn query_fresh_price_response(deps: Deps, env: Env) -> StdResult<FreshPriceResponse> {
+    let config: Config = load_config(deps.storage)?;
+    Ok(FreshPriceResponse {
+        rate: query_fresh_price(deps, &env, &config)?,
+    })
+}
+
+pub fn query_fresh_price(deps: Deps, env: &Env, config: &Config) -> StdResult<Decimal256> {
+    let sources_config = load_price_sources(deps.storage)?;
+    let price = aggregate_prices(deps, env, config, &sources_config)
+        .price
+        .ok_or_else(|| StdError::generic_err("no price source is available"))?;
+
+    if is_price_stale(&price, config.price_timeframe, env.block.time) {
+        return Err(StdError::generic_err("price is stale"));
+    }
+
+    Ok(price.rate)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Bands {
+    borrow_ltv_max: Decimal256,
+    borrow_ltv_min: Decimal256,
+    borrow_ltv_aim: Decimal256,
+    buffer_part: Decimal256,
+}
+
+struct EffectiveBands {
+    bands: Bands,
+    volatility: Decimal256,
+    observations: u32,
+}
+
+fn load_effective_bands(deps: Deps, env: &Env, config: &Config) -> StdResult<EffectiveBands> {
+    let borrow_ltvs = load_borrow_ltvs(deps.storage, config, env.block.time.seconds())?;
+    let base_bands = Bands {
+        borrow_ltv_max: borrow_ltvs.max,
+        borrow_ltv_min: borrow_ltvs.min,
+        borrow_ltv_aim: borrow_ltvs.aim,
+        buffer_part: config.get_buffer_part(),
+    };
+
+    let volatility_config = load_volatility_config(deps.storage)?;
+    if !volatility_config.is_enabled() {
+        return Ok(EffectiveBands {
+            bands: base_bands,
+            volatility: Decimal256::zero(),
+            observations: 0,
+        });
+    }
+
+    let observations = load_price_observations(deps.storage)?;
+    let volatility = realised_volatility(&observations);
+    Ok(EffectiveBands {
+        bands: adjust_bands_to_volatility(base_bands, volatility, &volatility_config),
+        volatility,
+        observations: observations.len() as u32,
+    })
+}
+
+/// Absolute relative price changes between consecutive observations, summed up
+/// and divided by time they took, per `VOLATILITY_PERIOD`
+fn realised_volatility(observations: &[PriceObservation]) -> Decimal256 {
+    let (changes_sum, elapsed) = observations
+        .windows(2)
+        .filter(|pair| !pair[0].price.is_zero() && pair[1].time > pair[0].time)
+        .fold(
+            (Decimal256::zero(), 0u64),
+            |(changes_sum, elapsed), pair| {
+                let (previous, current) = (pair[0].price, pair[1].price);
+                let change = if current > previous {
+                    (current - previous) / previous
+                } else {
+                    (previous - current) / previous
+                };
+                (
+                    changes_sum + change,
+                    elapsed + (pair[1].time - pair[0].time),
+                )
+            },
+        );
+
+    if elapsed == 0 {
+        return Decimal256::zero();
+    }
+
+    changes_sum * Decimal256::from_uint256(Uint256::from(VOLATILITY_PERIOD))
+        / Decimal256::from_uint256(Uint256::from(elapsed))
+}
+
+/// 0 when market is calm, 1 when it is stressed, linear in between
+fn stress_level(volatility: Decimal256, volatility_config: &VolatilityConfig) -> Decimal256 {
+    if volatility <= volatility_config.calm_volatility {
+        Decimal256::zero()
+    } else if volatility >= volatility_config.stress_volatility {
+        Decimal256::one()
+    } else {
+        (volatility - volatility_config.calm_volatility)
+            / (volatility_config.stress_volatility - volatility_config.calm_volatility)
+    }
+}
+
+fn adjust_bands_to_volatility(
+    bands: Bands,
+    volatility: Decimal256,
+    volatility_config: &VolatilityConfig,
+) -> Bands {
+    let stress_level = stress_level(volatility, volatility_config);
+
+    //aim has to stay above min, otherwise we would borrow and repay in a loop,
+    //so it goes down at most halfway to min
+    let ltv_reduction = std::cmp::min(
+        volatility_config.max_ltv_reduction * stress_level,
+        (bands.borrow_ltv_aim - bands.borrow_ltv_min) * Decimal256::percent(50),
+    );
+    let buffer_part = std::cmp::min(
+        bands.buffer_part + volatility_config.max_buffer_part_increase * stress_level,
+        Decimal256::one(),
+    );
+
+    Bands {
+        borrow_ltv_max: bands.borrow_ltv_max - ltv_reduction,
+        borrow_ltv_min: bands.borrow_ltv_min,
+        borrow_ltv_aim: bands.borrow_ltv_aim - ltv_reduction,
+        buffer_part,
+    }
+}
+
+/// APRs per borrowed stable
+struct Carry {
+    deposit_apr: Decimal256,
+    distribution_apr: Decimal256,
+    borrow_apr: Decimal256,
+}
+
+impl Carry {
+    /// `deposit_rate`, `borrow_rate` and `anc_emission_rate` are per block, as Anchor returns them
+    fn new(
+        deposit_rate: Decimal256,
+        borrow_rate: Decimal256,
+        anc_emission_rate: Decimal256,
+        total_liabilities: Decimal256,
+        anc_price: Decimal256,
+    ) -> Self {
+        let AnchorAprs {
+            deposit_apr,
+            borrow_apr,
+            distribution_apr,
+        } = calc_anchor_aprs(
+            deposit_rate,
+            borrow_rate,
+            anc_emission_rate,
+            total_liabilities,
+            anc_price,
+        );
+
+        Self {
+            deposit_apr,
+            distribution_apr,
+            borrow_apr,
+        }
+    }
+
+    fn income(&self) -> Decimal256 {
+        self.deposit_apr + self.distribution_apr
+    }
+
+    fn is_negative(&self) -> bool {
+        self.income() < self.borrow_apr
+    }
+
+    fn spread(&self) -> Decimal256 {
+        if self.is_negative() {
+            self.borrow_apr - self.income()
+        } else {
+            self.income() - self.borrow_apr
+        }
+    }
+
+    fn allows_borrowing(&self, min_spread: Decimal256) -> bool {
+        !self.is_negative() && self.spread() >= min_spread
+    }
+}
+
+fn load_carry(deps: Deps, config: &Config, carry_config: &CarryConfig) -> StdResult<Carry> {
+    let (anchor_market, anchor_overseer, anc_stable_swap, anchor_token) = match (
+        &carry_config.anchor_market_contract,
+        &carry_config.anchor_overseer_contract,
+        &carry_config.anc_stable_swap_contract,
+        &carry_config.anchor_token,
+    ) {
+        (Some(market), Some(overseer), Some(swap), Some(token)) => (market, overseer, swap, token),
+        _ => return Err(StdError::generic_err("carry config is not set")),
+    };
+
+    let market_state = query_market_state(deps, anchor_market)?;
+    let market_config = query_market_config(deps, anchor_market)?;
+    let market_balance = query_balance(
+        &deps.querier,
+        anchor_market,
+        config.stable_denom.to_string(),
+    )?;
+    let borrow_rate = query_borrow_rate(
+        deps,
+        &deps.api.addr_humanize(&market_config.interest_model)?,
+        market_balance.into(),
+        &market_state,
+    )?;
+    let deposit_rate = query_deposit_rate(deps, anchor_overseer)?;
+    let anc_price = query_spot_price(deps, anc_stable_swap, anchor_token)?;
+
+    Ok(Carry::new(
+        deposit_rate,
+        borrow_rate,
+        market_state.anc_emission_rate,
+        market_state.total_liabilities,
+        anc_price,
+    ))
+}
+
+struct AggregatedPrice {
+    sources: Vec<SourcePriceResponse>,
+    //median rate with oldest update time, None if no source is available
+    price: Option<PriceResponse>,
+    deviation: Decimal256,
+}
+
+fn aggregate_prices(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    sources_config: &PriceSourcesConfig,
+) -> AggregatedPrice {
+    let main_source = PriceSource::Oracle {
+        contract_addr: config.oracle_contract.to_string(),
+    };
+
+    let sources: Vec<SourcePriceResponse> = std::iter::once(main_source)
+        .chain(sources_config.price_sources.iter().cloned())
+        .map(|source| SourcePriceResponse {
+            price: query_source_price(deps, env, config, &source).ok(),
+            source,
+        })
+        .collect();
+
+    let prices: Vec<PriceResponse> = sources
+        .iter()
+        .filter_map(|source| source.price.clone())
+        .collect();
+    let price = median_price(&prices);
+    let deviation = price
+        .as_ref()
+        .map(|price| price_deviation(&prices, price.rate))
+        .unwrap_or_default();
+
+    AggregatedPrice {
+        sources,
+        price,
+        deviation,
+    }
+}
+
+fn query_source_price(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    source: &PriceSource,
+) -> StdResult<PriceResponse> {
+    match source {
+        PriceSource::Oracle { contract_addr } => query_price(
+            deps,
+            &Addr::unchecked(contract_addr),
+            config.basset_token.to_string(),
+            config.stable_denom.to_string(),
+        ),
+
+        PriceSource::AstroportTwap {
+            oracle_contract_addr,
+        } => {
+            let stables_amount = query_consult(
+                deps,
+                &Addr::unchecked(oracle_contract_addr),
+                AssetInfo::Token {
+                    contract_addr: config.basset_token.clone(),
+                },
+                Uint128::new(TWAP_CONSULT_AMOUNT),
+            )?;
+
+            //TWAP oracle refuses to answer on outdated data
+            let current_time = env.block.time.seconds();
+            Ok(PriceResponse {
+                rate: Decimal256::from_uint256(stables_amount)
+                    / Decimal256::from_uint256(TWAP_CONSULT_AMOUNT),
+                last_updated_base: current_time,
+                last_updated_quote: current_time,
+            })
+        }
+    }
+}
+
+fn median_price(prices: &[PriceResponse]) -> Option<PriceResponse> {
+    if prices.is_empty() {
+        return None;
+    }
+
+    let mut rates: Vec<Decimal256> = prices.iter().map(|price| price.rate).collect();
+    rates.sort();
+    let middle = rates.len() / 2;
+    let rate = if rates.len() % 2 == 1 {
+        rates[middle]
+    } else {
+        (rates[middle - 1] + rates[middle]) * Decimal256::percent(50)
+    };
+
+    //median is as old as the oldest price it is based on
+    Some(PriceResponse {
+        rate,
+        last_updated_base: prices.iter().map(|p| p.last_updated_base).min()?,
+        last_updated_quote: prices.iter().map(|p| p.last_updated_quote).min()?,
+    })
+}
+
+/// (highest - lowest) / median
+fn price_deviation(prices: &[PriceResponse], median: Decimal256) -> Decimal256 {
+    let highest = prices
+        .iter()
+        .map(|price| price.rate)
+        .max()
+        .unwrap_or_default();
+    let lowest = prices
+        .iter()
+        .map(|price| price.rate)
+        .min()
+        .unwrap_or_default();
+
+    if median.is_zero() {
+        //nothing to compare with, sources either agree on zero or can't be trusted at all
+        return if highest.is_zero() {
+            Decimal256::zero()
+        } else {
+            Decimal256::one()
+        };
+    }
+
+    (highest - lowest) / median
+}
+
+/// With extra sources configured, borrowing needs most of them to respond and agree:
+/// deviation of one responding source is always zero, so it can't be cross-checked
+fn is_borrow_allowed(
+    aggregated_price: &AggregatedPrice,
+    sources_config: &PriceSourcesConfig,
+) -> bool {
+    if sources_config.price_sources.is_empty() {
+        return true;
+    }
+
+    let responding_sources = aggregated_price
+        .sources
+        .iter()
+        .filter(|source| source.price.is_some())
+        .count();
+    responding_sources * 2 > aggregated_price.sources.len()
+        && aggregated_price.deviation <= sources_config.max_deviation
 }
 
 fn calc_borrower_action(
@@ -140,14 +708,19 @@ fn calc_borrower_action(
 
 #[cfg(test)]
 mod test {
-    use basset_vault::basset_vault_strategy::BorrowerActionResponse;
+    use basset_vault::basset_vault_strategy::{BorrowerActionResponse, StalePricePolicy};
     use cosmwasm_bignumber::{Decimal256, Uint256};
     use cosmwasm_std::Timestamp;
     use std::str::FromStr;
 
-    use crate::{price::PriceResponse, queries::LTVInfo};
+    use crate::queries::LTVInfo;
+    use basset_vault::anchor::oracle::PriceResponse;
 
-    use super::calc_borrower_action;
+    use super::{
+        adjust_bands_to_volatility, calc_borrower_action, median_price, price_deviation,
+        realised_volatility, Bands, Carry,
+    };
+    use crate::state::{PriceObservation, VolatilityConfig};
 
     #[test]
     fn repay_loan() {
@@ -434,6 +1007,7 @@ mod test {
                 50,
                 &price_response,
                 block_time,
+                &StalePricePolicy::default(),
             );
 
             assert_eq!(
@@ -465,6 +1039,7 @@ mod test {
                 50,
                 &price_response,
                 block_time,
+                &StalePricePolicy::default(),
             );
 
             assert_eq!(
@@ -482,6 +1057,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn price_is_too_old_but_policy_does_not_scale_ltv() {
+        let borrow_ltv_max = Decimal256::from_str("0.85").unwrap();
+        let borrow_ltv_min = Decimal256::from_str("0.75").unwrap();
+        let borrow_ltv_aim = Decimal256::from_str("0.8").unwrap();
+
+        let block_time = Timestamp::from_seconds(100);
+        let price_response = PriceResponse {
+            rate: Decimal256::one(),
+            last_updated_base: 40,
+            last_updated_quote: 40,
+        };
+
+        let ltv_info = LTVInfo::new(
+            borrow_ltv_max,
+            borrow_ltv_min,
+            borrow_ltv_aim,
+            50,
+            &price_response,
+            block_time,
+            &StalePricePolicy::RepayOnly {},
+        );
+
+        assert_eq!(borrow_ltv_max, ltv_info.borrow_ltv_max);
+        assert_eq!(borrow_ltv_min, ltv_info.borrow_ltv_min);
+        assert_eq!(borrow_ltv_aim, ltv_info.borrow_ltv_aim);
+    }
+
     #[test]
     fn price_is_fresh() {
         let borrow_ltv_max = Decimal256::from_str("0.85").unwrap();
@@ -502,6 +1105,7 @@ mod test {
             50,
             &price_response,
             block_time,
+            &StalePricePolicy::default(),
         );
 
         assert_eq!(borrow_ltv_max, ltv_info.borrow_ltv_max);
@@ -534,4 +1138,171 @@ mod test {
         );
         assert_eq!(borrower_action, BorrowerActionResponse::nothing());
     }
+
+    fn price(rate: &str, last_updated: u64) -> PriceResponse {
+        PriceResponse {
+            rate: Decimal256::from_str(rate).unwrap(),
+            last_updated_base: last_updated,
+            last_updated_quote: last_updated,
+        }
+    }
+
+    #[test]
+    fn median_of_odd_number_of_prices() {
+        let prices = vec![price("1.2", 80), price("0.9", 90), price("1", 70)];
+
+        let median = median_price(&prices).unwrap();
+        assert_eq!(Decimal256::one(), median.rate);
+        //oldest update time
+        assert_eq!(70, median.last_updated_base);
+        assert_eq!(70, median.last_updated_quote);
+    }
+
+    #[test]
+    fn median_of_even_number_of_prices() {
+        let prices = vec![
+            price("1.2", 80),
+            price("0.9", 90),
+            price("1", 70),
+            price("5", 90),
+        ];
+
+        let median = median_price(&prices).unwrap();
+        assert_eq!(Decimal256::from_str("1.1").unwrap(), median.rate);
+    }
+
+    #[test]
+    fn no_median_without_prices() {
+        assert_eq!(None, median_price(&[]));
+    }
+
+    #[test]
+    fn deviation_from_median() {
+        let prices = vec![price("1.2", 80), price("0.9", 90), price("1", 70)];
+
+        //(1.2 - 0.9) / 1
+        assert_eq!(
+            Decimal256::from_str("0.3").unwrap(),
+            price_deviation(&prices, Decimal256::one())
+        );
+        assert_eq!(
+            Decimal256::zero(),
+            price_deviation(&[price("1", 80)], Decimal256::one())
+        );
+        assert_eq!(
+            Decimal256::one(),
+            price_deviation(&[price("0", 80), price("1", 80)], Decimal256::zero())
+        );
+    }
+
+    #[test]
+    fn carry_without_liabilities_has_no_distribution() {
+        let carry = Carry::new(
+            Decimal256::from_str("0.00000003").unwrap(),
+            Decimal256::from_str("0.00000002").unwrap(),
+            Decimal256::from_str("0.02").unwrap(),
+            Decimal256::zero(),
+            Decimal256::one(),
+        );
+
+        assert_eq!(Decimal256::zero(), carry.distribution_apr);
+        assert!(!carry.is_negative());
+        //(0.00000003 - 0.00000002) * 4_656_810
+        assert_eq!(Decimal256::from_str("0.0465681").unwrap(), carry.spread());
+        assert!(carry.allows_borrowing(Decimal256::percent(4)));
+        assert!(!carry.allows_borrowing(Decimal256::percent(5)));
+    }
+
+    fn observation(time: u64, price: &str) -> PriceObservation {
+        PriceObservation {
+            time,
+            price: Decimal256::from_str(price).unwrap(),
+        }
+    }
+
+    #[test]
+    fn volatility_is_price_change_per_period() {
+        let observations = vec![
+            observation(0, "1"),
+            observation(3600, "1.1"),
+            observation(7200, "0.99"),
+            observation(10800, "0.99"),
+        ];
+
+        //(0.1 + 0.1 + 0) / 3 hours
+        assert_eq!(
+            Decimal256::from_str("0.066666666666666666").unwrap(),
+            realised_volatility(&observations)
+        );
+        assert_eq!(
+            Decimal256::zero(),
+            realised_volatility(&[observation(10, "1")])
+        );
+    }
+
+    #[test]
+    fn volatility_accounts_for_time_between_observations() {
+        //same change over longer time is calmer
+        let observations = vec![
+            observation(0, "1"),
+            observation(3600, "1.1"),
+            observation(3 * 3600, "0.99"),
+        ];
+
+        //(0.1 + 0.1) / 3 hours
+        assert_eq!(
+            Decimal256::from_str("0.066666666666666666").unwrap(),
+            realised_volatility(&observations)
+        );
+        assert_eq!(
+            Decimal256::from_str("0.6").unwrap(),
+            realised_volatility(&[observation(0, "1"), observation(600, "1.1")])
+        );
+    }
+
+    #[test]
+    fn bands_tighten_with_volatility() {
+        let volatility_config = VolatilityConfig {
+            max_observations: 10,
+            min_interval: 0,
+            calm_volatility: Decimal256::percent(1),
+            stress_volatility: Decimal256::percent(5),
+            max_ltv_reduction: Decimal256::percent(4),
+            max_buffer_part_increase: Decimal256::percent(2),
+        };
+        let bands = Bands {
+            borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+            borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+            borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+            buffer_part: Decimal256::from_str("0.018").unwrap(),
+        };
+
+        //calm market
+        assert_eq!(
+            bands,
+            adjust_bands_to_volatility(bands.clone(), Decimal256::percent(1), &volatility_config)
+        );
+
+        //halfway to stress
+        assert_eq!(
+            Bands {
+                borrow_ltv_max: Decimal256::from_str("0.83").unwrap(),
+                borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+                borrow_ltv_aim: Decimal256::from_str("0.78").unwrap(),
+                buffer_part: Decimal256::from_str("0.028").unwrap(),
+            },
+            adjust_bands_to_volatility(bands.clone(), Decimal256::percent(3), &volatility_config)
+        );
+
+        //reduction is capped by half of distance between aim and min
+        assert_eq!(
+            Bands {
+                borrow_ltv_max: Decimal256::from_str("0.825").unwrap(),
+                borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+                borrow_ltv_aim: Decimal256::from_str("0.775").unwrap(),
+                buffer_part: Decimal256::from_str("0.038").unwrap(),
+            },
+            adjust_bands_to_volatility(bands, Decimal256::percent(50), &volatility_config)
+        );
+    }
 }
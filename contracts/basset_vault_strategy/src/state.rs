@@ -1,11 +1,12 @@
 use std::str::FromStr;
 
+use basset_vault::basset_vault_strategy::{PriceSource, StalePricePolicy};
 use basset_vault::BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP;
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_bignumber::Decimal256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{Addr, StdResult, Storage};
 
 use crate::{error::ContractError, ContractResult};
@@ -25,6 +26,9 @@ pub struct Config {
     //to be able to repay loan in 3 iterations (in case of aterra locked)
     buffer_part: Decimal256,
     pub price_timeframe: u64,
+    //missing in configs stored before it was introduced
+    #[serde(default)]
+    stale_price_policy: StalePricePolicy,
 }
 
 impl Config {
@@ -53,6 +57,7 @@ impl Config {
             basset_max_ltv,
             buffer_part,
             price_timeframe,
+            stale_price_policy: StalePricePolicy::default(),
         };
 
         config.set_basset_max_ltv(basset_max_ltv)?;
@@ -71,18 +76,36 @@ impl Config {
     }
 
     pub fn set_buffer_part(&mut self, value: Decimal256) -> ContractResult<()> {
-        if self.borrow_ltv_max <= self.borrow_ltv_aim {
+        Self::validate_buffer_part(value, &self.get_borrow_ltvs())?;
+        self.buffer_part = value;
+        Ok(())
+    }
+
+    /// Buffer has to be enough to repay loan from `ltvs.max` to `ltvs.aim`
+    /// in limited number of redeem iterations
+    pub fn validate_buffer_part(value: Decimal256, ltvs: &BorrowLtvs) -> ContractResult<()> {
+        if ltvs.max <= ltvs.aim {
             return Err(ContractError::InappropriateValue);
         }
 
-        let min_buffer_part = (self.borrow_ltv_max - self.borrow_ltv_aim)
+        let min_buffer_part = (ltvs.max - ltvs.aim)
             / Decimal256::from_str(&BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP.to_string())?;
 
         if value.is_zero() || value > Decimal256::one() || value < min_buffer_part {
             return Err(ContractError::InappropriateValue);
         }
 
-        self.buffer_part = value;
+        Ok(())
+    }
+
+    pub fn set_stale_price_policy(&mut self, value: StalePricePolicy) -> ContractResult<()> {
+        if let StalePricePolicy::ScaleLtv { factor } = value {
+            if factor.is_zero() || factor > Decimal256::one() {
+                return Err(ContractError::InappropriateValue);
+            }
+        }
+
+        self.stale_price_policy = value;
         Ok(())
     }
 
@@ -131,6 +154,14 @@ impl Config {
         self.borrow_ltv_aim
     }
 
+    pub fn get_borrow_ltvs(&self) -> BorrowLtvs {
+        BorrowLtvs {
+            max: self.borrow_ltv_max,
+            min: self.borrow_ltv_min,
+            aim: self.borrow_ltv_aim,
+        }
+    }
+
     pub fn get_basset_max_ltv(&self) -> Decimal256 {
         self.basset_max_ltv
     }
@@ -138,6 +169,115 @@ impl Config {
     pub fn get_buffer_part(&self) -> Decimal256 {
         self.buffer_part
     }
+
+    pub fn get_stale_price_policy(&self) -> &StalePricePolicy {
+        &self.stale_price_policy
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BorrowLtvs {
+    pub max: Decimal256,
+    pub min: Decimal256,
+    pub aim: Decimal256,
+}
+
+/// Linear move of LTV bands from `start` to ones stored in `Config`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LtvRamp {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub start: BorrowLtvs,
+}
+
+impl LtvRamp {
+    pub fn is_active(&self, time: u64) -> bool {
+        time < self.end_time
+    }
+
+    pub fn borrow_ltvs_at(&self, target: &BorrowLtvs, time: u64) -> BorrowLtvs {
+        if !self.is_active(time) {
+            return target.clone();
+        }
+
+        BorrowLtvs {
+            max: self.interpolate(self.start.max, target.max, time),
+            min: self.interpolate(self.start.min, target.min, time),
+            aim: self.interpolate(self.start.aim, target.aim, time),
+        }
+    }
+
+    fn interpolate(&self, start: Decimal256, target: Decimal256, time: u64) -> Decimal256 {
+        let elapsed = time.saturating_sub(self.start_time);
+        let progress = Decimal256::from_uint256(Uint256::from(elapsed))
+            / Decimal256::from_uint256(Uint256::from(self.end_time - self.start_time));
+
+        if target >= start {
+            start + (target - start) * progress
+        } else {
+            start - (start - target) * progress
+        }
+    }
+}
+
+/// Price feeds used together with `oracle_contract`.
+/// Default is no extra feeds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PriceSourcesConfig {
+    pub price_sources: Vec<PriceSource>,
+    //max (highest - lowest) / median price to allow borrowing
+    pub max_deviation: Decimal256,
+}
+
+/// Anchor contracts to check borrowing profitability.
+/// Default is disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CarryConfig {
+    pub anchor_market_contract: Option<Addr>,
+    pub anchor_overseer_contract: Option<Addr>,
+    pub anc_stable_swap_contract: Option<Addr>,
+    pub anchor_token: Option<Addr>,
+    //min carry APR to borrow more
+    pub min_spread: Decimal256,
+    //LTV bands are multiplied by it while carry is negative
+    pub negative_carry_ltv_factor: Decimal256,
+}
+
+impl CarryConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.anchor_market_contract.is_some()
+            && self.anchor_overseer_contract.is_some()
+            && self.anc_stable_swap_contract.is_some()
+            && self.anchor_token.is_some()
+            && !self.negative_carry_ltv_factor.is_zero()
+    }
+}
+
+/// Volatility based adjustment of LTV bands and buffer.
+/// Default is disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct VolatilityConfig {
+    pub max_observations: u32,
+    pub min_interval: u64,
+    pub calm_volatility: Decimal256,
+    pub stress_volatility: Decimal256,
+    pub max_ltv_reduction: Decimal256,
+    pub max_buffer_part_increase: Decimal256,
+}
+
+impl VolatilityConfig {
+    pub fn is_enabled(&self) -> bool {
+        //at least two prices to get one change
+        self.max_observations >= 2
+            && self.stress_volatility > self.calm_volatility
+            && (!self.max_ltv_reduction.is_zero() || !self.max_buffer_part_increase.is_zero())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PriceObservation {
+    pub time: u64,
+    pub price: Decimal256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -148,6 +288,12 @@ pub struct GovernanceUpdateState {
 
 static KEY_CONFIG: Item<Config> = Item::new("config");
 static KEY_GOVERNANCE_UPDATE: Item<GovernanceUpdateState> = Item::new("gov_update");
+static KEY_PRICE_SOURCES: Item<PriceSourcesConfig> = Item::new("price_sources");
+static KEY_CARRY_CONFIG: Item<CarryConfig> = Item::new("carry_config");
+static KEY_LTV_RAMP: Item<LtvRamp> = Item::new("ltv_ramp");
+static KEY_VOLATILITY_CONFIG: Item<VolatilityConfig> = Item::new("volatility_config");
+//oldest first
+static KEY_PRICE_OBSERVATIONS: Item<Vec<PriceObservation>> = Item::new("price_observations");
 
 pub fn load_config(storage: &dyn Storage) -> StdResult<Config> {
     KEY_CONFIG.load(storage)
@@ -157,6 +303,88 @@ pub fn save_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
     KEY_CONFIG.save(storage, config)
 }
 
+pub fn load_price_sources(storage: &dyn Storage) -> StdResult<PriceSourcesConfig> {
+    KEY_PRICE_SOURCES
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_price_sources(
+    storage: &mut dyn Storage,
+    price_sources: &PriceSourcesConfig,
+) -> StdResult<()> {
+    KEY_PRICE_SOURCES.save(storage, price_sources)
+}
+
+pub fn load_carry_config(storage: &dyn Storage) -> StdResult<CarryConfig> {
+    KEY_CARRY_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_carry_config(storage: &mut dyn Storage, carry_config: &CarryConfig) -> StdResult<()> {
+    KEY_CARRY_CONFIG.save(storage, carry_config)
+}
+
+pub fn load_ltv_ramp(storage: &dyn Storage) -> StdResult<Option<LtvRamp>> {
+    KEY_LTV_RAMP.may_load(storage)
+}
+
+pub fn save_ltv_ramp(storage: &mut dyn Storage, ltv_ramp: &LtvRamp) -> StdResult<()> {
+    KEY_LTV_RAMP.save(storage, ltv_ramp)
+}
+
+pub fn remove_ltv_ramp(storage: &mut dyn Storage) {
+    KEY_LTV_RAMP.remove(storage)
+}
+
+/// LTV bands at `time`, taking ramp into account
+pub fn load_borrow_ltvs(
+    storage: &dyn Storage,
+    config: &Config,
+    time: u64,
+) -> StdResult<BorrowLtvs> {
+    let target = config.get_borrow_ltvs();
+    Ok(match load_ltv_ramp(storage)? {
+        Some(ltv_ramp) => ltv_ramp.borrow_ltvs_at(&target, time),
+        None => target,
+    })
+}
+
+pub fn load_volatility_config(storage: &dyn Storage) -> StdResult<VolatilityConfig> {
+    KEY_VOLATILITY_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_volatility_config(
+    storage: &mut dyn Storage,
+    volatility_config: &VolatilityConfig,
+) -> StdResult<()> {
+    KEY_VOLATILITY_CONFIG.save(storage, volatility_config)
+}
+
+pub fn load_price_observations(storage: &dyn Storage) -> StdResult<Vec<PriceObservation>> {
+    KEY_PRICE_OBSERVATIONS
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+/// Adds observation and drops the oldest ones above `max_observations`
+pub fn push_price_observation(
+    storage: &mut dyn Storage,
+    observation: PriceObservation,
+    max_observations: u32,
+) -> StdResult<()> {
+    let mut observations = load_price_observations(storage)?;
+    observations.push(observation);
+    let max_observations = max_observations as usize;
+    if observations.len() > max_observations {
+        observations.drain(..observations.len() - max_observations);
+    }
+    KEY_PRICE_OBSERVATIONS.save(storage, &observations)
+}
+
 pub fn load_gov_update(storage: &dyn Storage) -> StdResult<GovernanceUpdateState> {
     KEY_GOVERNANCE_UPDATE.load(storage)
 }
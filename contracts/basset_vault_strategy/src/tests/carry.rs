@@ -0,0 +1,222 @@
+use super::price_feeds::{
+    borrower_action, init, set_oracle_price, Deps, GOVERNANCE_CONTRACT, ORACLE_CONTRACT,
+};
+use crate::error::ContractError;
+
+use basset_vault::anchor::market::{ConfigResponse as MarketConfig, StateResponse as MarketState};
+use basset_vault::anchor::overseer::EpochStateResponse;
+use basset_vault::astroport_pair::SimulationResponse;
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, CarryConfigResponse, CarryResponse, ExecuteMsg, GovernanceMsg, QueryMsg,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, to_binary, Api, Binary, Uint128};
+use std::str::FromStr;
+
+const ANCHOR_MARKET_CONTRACT: &str = "addr0005";
+const ANCHOR_OVERSEER_CONTRACT: &str = "addr0006";
+const ANC_STABLE_SWAP_CONTRACT: &str = "addr0007";
+const ANCHOR_TOKEN: &str = "addr0008";
+const ANCHOR_INTEREST_MODEL_CONTRACT: &str = "addr0009";
+
+fn update_carry_config(
+    deps: &mut Deps,
+    min_spread: Option<Decimal256>,
+    negative_carry_ltv_factor: Option<Decimal256>,
+) -> Result<(), ContractError> {
+    crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdateCarryConfig {
+                anchor_market_addr: Some(ANCHOR_MARKET_CONTRACT.to_string()),
+                anchor_overseer_addr: Some(ANCHOR_OVERSEER_CONTRACT.to_string()),
+                anc_stable_swap_addr: Some(ANC_STABLE_SWAP_CONTRACT.to_string()),
+                anchor_token_addr: Some(ANCHOR_TOKEN.to_string()),
+                min_spread,
+                negative_carry_ltv_factor,
+            },
+        },
+    )
+    .map(|_| ())
+}
+
+//Anchor use cosmwasm_storage::Singleton which add length prefix
+fn length_prefixed(key: &[u8]) -> Binary {
+    let mut prefixed = (key.len() as u16).to_be_bytes().to_vec();
+    prefixed.extend_from_slice(key);
+    Binary::from(prefixed)
+}
+
+/// Rates are per block, total liabilities are 1_000_000 and ANC price is 2
+fn set_anchor_rates(deps: &mut Deps, deposit_rate: &str, borrow_rate: &str, anc_emission: &str) {
+    let interest_model = deps
+        .api
+        .addr_canonicalize(ANCHOR_INTEREST_MODEL_CONTRACT)
+        .unwrap();
+    let querier = &mut deps.querier;
+    querier.raw_responses.insert(
+        (
+            ANCHOR_MARKET_CONTRACT.to_string(),
+            length_prefixed(b"config"),
+        ),
+        to_binary(&MarketConfig {
+            interest_model,
+            max_borrow_factor: Decimal256::one(),
+        })
+        .unwrap(),
+    );
+    querier.raw_responses.insert(
+        (
+            ANCHOR_MARKET_CONTRACT.to_string(),
+            length_prefixed(b"state"),
+        ),
+        to_binary(&MarketState {
+            total_liabilities: Decimal256::from_uint256(Uint256::from(1_000_000u64)),
+            total_reserves: Decimal256::zero(),
+            anc_emission_rate: Decimal256::from_str(anc_emission).unwrap(),
+        })
+        .unwrap(),
+    );
+    querier
+        .base
+        .update_balance(ANCHOR_MARKET_CONTRACT, coins(10_000_000, "uust"));
+    querier.responses.insert(
+        ANCHOR_INTEREST_MODEL_CONTRACT.to_string(),
+        to_binary(&basset_vault::anchor::market::BorrowRateResponse {
+            rate: Decimal256::from_str(borrow_rate).unwrap(),
+        })
+        .unwrap(),
+    );
+    querier.responses.insert(
+        ANCHOR_OVERSEER_CONTRACT.to_string(),
+        to_binary(&EpochStateResponse {
+            deposit_rate: Decimal256::from_str(deposit_rate).unwrap(),
+        })
+        .unwrap(),
+    );
+    querier.responses.insert(
+        ANC_STABLE_SWAP_CONTRACT.to_string(),
+        to_binary(&SimulationResponse {
+            return_amount: Uint128::new(2_000_000),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        })
+        .unwrap(),
+    );
+}
+
+//deposit APR = 0.00000003 * 4_656_810 = 0.1397
+//borrow APR = 0.00000005 * 4_656_810 = 0.2328
+//distribution APR = 0.02 * 4_656_810 * 2 / 1_000_000 = 0.1862
+fn set_positive_carry(deps: &mut Deps) {
+    set_anchor_rates(deps, "0.00000003", "0.00000005", "0.02");
+}
+
+//no ANC emissions, so borrow APR is higher than deposit APR
+fn set_negative_carry(deps: &mut Deps) {
+    set_anchor_rates(deps, "0.00000003", "0.00000005", "0");
+}
+
+fn carry(deps: &Deps) -> CarryResponse {
+    let response = crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::Carry {}).unwrap();
+    from_binary(&response).unwrap()
+}
+
+#[test]
+fn carry_config_is_disabled_by_default() {
+    let mut deps = init();
+
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::CarryConfig {}).unwrap();
+    let carry_config: CarryConfigResponse = from_binary(&response).unwrap();
+    assert!(!carry_config.enabled);
+
+    //factor is not set yet
+    update_carry_config(&mut deps, None, None).unwrap();
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::CarryConfig {}).unwrap();
+    let carry_config: CarryConfigResponse = from_binary(&response).unwrap();
+    assert!(!carry_config.enabled);
+    assert_eq!(
+        Some(ANCHOR_MARKET_CONTRACT.to_string()),
+        carry_config.anchor_market_contract
+    );
+
+    let res = update_carry_config(&mut deps, None, Some(Decimal256::from_str("1.1").unwrap()));
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
+
+#[test]
+fn positive_carry_allows_borrowing() {
+    let mut deps = init();
+    update_carry_config(&mut deps, None, Some(Decimal256::percent(50))).unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_positive_carry(&mut deps);
+
+    let carry = carry(&deps);
+    assert!(!carry.is_negative);
+    assert!(carry.borrow_allowed);
+    //0.1397 + 0.1862 - 0.2328
+    assert_eq!(Decimal256::from_str("0.0931362").unwrap(), carry.spread);
+
+    //max_borrow = 1_000 * 1 * 0.5 = 500
+    //aim = 500 * 0.8 = 400
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(400u64), Uint256::from(7u64)),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn carry_below_min_spread_blocks_borrowing() {
+    let mut deps = init();
+    update_carry_config(
+        &mut deps,
+        Some(Decimal256::percent(10)),
+        Some(Decimal256::percent(50)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_positive_carry(&mut deps);
+
+    assert!(!carry(&deps).borrow_allowed);
+    assert_eq!(BorrowerActionResponse::nothing(), borrower_action(&deps, 0));
+    //ltv = 450 / 500 = 0.9, repay to aim = 450 - 400
+    assert_eq!(
+        BorrowerActionResponse::repay(Uint256::from(50u64), Uint256::from(7u64)),
+        borrower_action(&deps, 450)
+    );
+}
+
+#[test]
+fn negative_carry_lowers_ltv() {
+    let mut deps = init();
+    update_carry_config(&mut deps, None, Some(Decimal256::percent(50))).unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_negative_carry(&mut deps);
+
+    let carry = carry(&deps);
+    assert!(carry.is_negative);
+    assert!(!carry.borrow_allowed);
+
+    assert_eq!(BorrowerActionResponse::nothing(), borrower_action(&deps, 0));
+    //bands are halved: max 0.425, aim 0.4
+    //ltv = 300 / 500 = 0.6, repay to aim = 300 - 200
+    //buffer_size = 500 * 0.425 * 0.018 = 3
+    assert_eq!(
+        BorrowerActionResponse::repay(Uint256::from(100u64), Uint256::from(3u64)),
+        borrower_action(&deps, 300)
+    );
+}
+
+#[test]
+fn unknown_carry_blocks_borrowing() {
+    let mut deps = init();
+    update_carry_config(&mut deps, None, Some(Decimal256::percent(50))).unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    assert_eq!(BorrowerActionResponse::nothing(), borrower_action(&deps, 0));
+}
@@ -1,7 +1,7 @@
 use crate::error::ContractError;
 use crate::state::load_config;
 
-use basset_vault::basset_vault_strategy::{ExecuteMsg, GovernanceMsg};
+use basset_vault::basset_vault_strategy::{ExecuteMsg, GovernanceMsg, StalePricePolicy};
 use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::testing::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info};
@@ -55,6 +55,7 @@ fn fail_to_change_config_if_sender_is_not_governance() {
             basset_max_ltv: new_basset_max_ltv,
             buffer_part: new_buffer_part,
             price_timeframe: new_price_timeframe,
+            stale_price_policy: None,
         },
     };
 
@@ -102,6 +103,7 @@ fn success_to_change_config_if_sender_governance() {
     let new_basset_max_ltv = Decimal256::from_str("0.7").unwrap();
     let new_buffer_part = Decimal256::from_str("0.99").unwrap();
     let new_price_timeframe = 100;
+    let new_stale_price_policy = StalePricePolicy::RepayOnly {};
 
     let change_config_msg = ExecuteMsg::Governance {
         governance_msg: GovernanceMsg::UpdateConfig {
@@ -114,6 +116,7 @@ fn success_to_change_config_if_sender_governance() {
             basset_max_ltv: Some(new_basset_max_ltv.clone()),
             buffer_part: Some(new_buffer_part.clone()),
             price_timeframe: Some(new_price_timeframe),
+            stale_price_policy: Some(new_stale_price_policy.clone()),
         },
     };
 
@@ -131,4 +134,5 @@ fn success_to_change_config_if_sender_governance() {
     assert_eq!(new_basset_max_ltv, config.get_basset_max_ltv());
     assert_eq!(new_buffer_part, config.get_buffer_part());
     assert_eq!(new_price_timeframe, config.price_timeframe);
+    assert_eq!(&new_stale_price_policy, config.get_stale_price_policy());
 }
@@ -0,0 +1,297 @@
+use super::price_feeds::{
+    borrower_action, init, set_oracle_price, Deps, GOVERNANCE_CONTRACT, ORACLE_CONTRACT,
+};
+use crate::error::ContractError;
+
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, ConfigResponse, EffectiveBandsResponse, ExecuteMsg, GovernanceMsg,
+    LtvRampResponse, QueryMsg,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Env, StdError, Timestamp};
+use std::str::FromStr;
+
+const RAMP_DURATION: u64 = 1_000;
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn now() -> u64 {
+    mock_env().block.time.seconds()
+}
+
+fn execute_governance(
+    deps: &mut Deps,
+    env: Env,
+    governance_msg: GovernanceMsg,
+) -> Result<(), ContractError> {
+    crate::contract::execute(
+        deps.as_mut(),
+        env,
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        ExecuteMsg::Governance { governance_msg },
+    )
+    .map(|_| ())
+}
+
+fn ramp_ltv(
+    deps: &mut Deps,
+    env: Env,
+    target_max: &str,
+    target_min: &str,
+    target_aim: &str,
+    end_time: u64,
+) -> Result<(), ContractError> {
+    execute_governance(
+        deps,
+        env,
+        GovernanceMsg::RampLtv {
+            target_aim: Decimal256::from_str(target_aim).unwrap(),
+            target_min: Decimal256::from_str(target_min).unwrap(),
+            target_max: Decimal256::from_str(target_max).unwrap(),
+            end_time,
+        },
+    )
+}
+
+fn query_ltv_ramp(deps: &Deps, env: Env) -> LtvRampResponse {
+    let response = crate::contract::query(deps.as_ref(), env, QueryMsg::LtvRamp {}).unwrap();
+    from_binary(&response).unwrap()
+}
+
+//ramp from 0.85/0.75/0.8 to 0.75/0.6/0.7, started half of duration ago
+fn init_with_ramp() -> Deps {
+    let mut deps = init();
+    ramp_ltv(
+        &mut deps,
+        env_at(now() - RAMP_DURATION / 2),
+        "0.75",
+        "0.6",
+        "0.7",
+        now() + RAMP_DURATION / 2,
+    )
+    .unwrap();
+    deps
+}
+
+#[test]
+fn bands_are_interpolated_during_ramp() {
+    let deps = init_with_ramp();
+
+    assert_eq!(
+        LtvRampResponse {
+            borrow_ltv_max: Decimal256::from_str("0.8").unwrap(),
+            borrow_ltv_min: Decimal256::from_str("0.675").unwrap(),
+            borrow_ltv_aim: Decimal256::from_str("0.75").unwrap(),
+            target_borrow_ltv_max: Decimal256::from_str("0.75").unwrap(),
+            target_borrow_ltv_min: Decimal256::from_str("0.6").unwrap(),
+            target_borrow_ltv_aim: Decimal256::from_str("0.7").unwrap(),
+            start_time: Some(now() - RAMP_DURATION / 2),
+            end_time: Some(now() + RAMP_DURATION / 2),
+            is_active: true,
+        },
+        query_ltv_ramp(&deps, mock_env())
+    );
+
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::EffectiveBands {}).unwrap();
+    let effective_bands: EffectiveBandsResponse = from_binary(&response).unwrap();
+    assert_eq!(
+        Decimal256::from_str("0.75").unwrap(),
+        effective_bands.borrow_ltv_aim
+    );
+
+    //ramp is over, targets are used
+    let ltv_ramp = query_ltv_ramp(&deps, env_at(now() + RAMP_DURATION));
+    assert!(!ltv_ramp.is_active);
+    assert_eq!(
+        Decimal256::from_str("0.7").unwrap(),
+        ltv_ramp.borrow_ltv_aim
+    );
+}
+
+#[test]
+fn borrower_action_follows_ramp() {
+    let mut deps = init_with_ramp();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    //max_borrow = 1_000 * 1 * 0.5 = 500
+    //aim = 500 * 0.75 = 375
+    //buffer_size = 500 * 0.8 * 0.018 = 7.2
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(375u64), Uint256::from(7u64)),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn new_ramp_starts_from_current_point() {
+    let mut deps = init_with_ramp();
+
+    ramp_ltv(
+        &mut deps,
+        mock_env(),
+        "0.85",
+        "0.75",
+        "0.8",
+        now() + RAMP_DURATION,
+    )
+    .unwrap();
+
+    let ltv_ramp = query_ltv_ramp(&deps, env_at(now() + RAMP_DURATION / 2));
+    assert_eq!(
+        Decimal256::from_str("0.825").unwrap(),
+        ltv_ramp.borrow_ltv_max
+    );
+    assert_eq!(
+        Decimal256::from_str("0.7125").unwrap(),
+        ltv_ramp.borrow_ltv_min
+    );
+    assert_eq!(
+        Decimal256::from_str("0.775").unwrap(),
+        ltv_ramp.borrow_ltv_aim
+    );
+}
+
+#[test]
+fn stop_ramp_keeps_current_point() {
+    let mut deps = init_with_ramp();
+
+    execute_governance(&mut deps, mock_env(), GovernanceMsg::StopRamp {}).unwrap();
+
+    let ltv_ramp = query_ltv_ramp(&deps, env_at(now() + RAMP_DURATION));
+    assert!(!ltv_ramp.is_active);
+    assert_eq!(None, ltv_ramp.end_time);
+    assert_eq!(
+        Decimal256::from_str("0.75").unwrap(),
+        ltv_ramp.borrow_ltv_aim
+    );
+
+    let response = crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&response).unwrap();
+    assert_eq!(Decimal256::from_str("0.8").unwrap(), config.borrow_ltv_max);
+    assert_eq!(
+        Decimal256::from_str("0.675").unwrap(),
+        config.borrow_ltv_min
+    );
+    assert_eq!(Decimal256::from_str("0.75").unwrap(), config.borrow_ltv_aim);
+
+    let res = execute_governance(&mut deps, mock_env(), GovernanceMsg::StopRamp {});
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err("no active ramp"))),
+        res
+    );
+}
+
+#[test]
+fn update_config_stops_ramp() {
+    let mut deps = init_with_ramp();
+
+    execute_governance(
+        &mut deps,
+        mock_env(),
+        GovernanceMsg::UpdateConfig {
+            oracle_addr: None,
+            basset_token_addr: None,
+            stable_denom: None,
+            borrow_ltv_max: None,
+            borrow_ltv_min: None,
+            borrow_ltv_aim: Some(Decimal256::from_str("0.7").unwrap()),
+            basset_max_ltv: None,
+            buffer_part: None,
+            price_timeframe: None,
+            stale_price_policy: None,
+        },
+    )
+    .unwrap();
+
+    //other bands stay at current point
+    let ltv_ramp = query_ltv_ramp(&deps, mock_env());
+    assert!(!ltv_ramp.is_active);
+    assert_eq!(
+        Decimal256::from_str("0.8").unwrap(),
+        ltv_ramp.borrow_ltv_max
+    );
+    assert_eq!(
+        Decimal256::from_str("0.675").unwrap(),
+        ltv_ramp.borrow_ltv_min
+    );
+    assert_eq!(
+        Decimal256::from_str("0.7").unwrap(),
+        ltv_ramp.borrow_ltv_aim
+    );
+}
+
+#[test]
+fn inappropriate_ramp_is_rejected() {
+    let mut deps = init();
+
+    //aim above max
+    let res = ramp_ltv(&mut deps, mock_env(), "0.75", "0.6", "0.8", now() + 1);
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    //ramp has to end in future
+    let res = ramp_ltv(&mut deps, mock_env(), "0.75", "0.6", "0.7", now());
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    //buffer_part 0.018 is less than (0.95 - 0.7) / 10
+    let res = ramp_ltv(&mut deps, mock_env(), "0.95", "0.6", "0.7", now() + 1);
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let res = crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::StopRamp {},
+        },
+    );
+    assert_eq!(Err(ContractError::Unauthorized {}), res.map(|_| ()));
+}
+
+#[test]
+fn buffer_part_has_to_fit_ramp_start() {
+    let mut deps = init();
+    //from 0.85/0.75/0.8 to 0.75/0.6/0.74
+    ramp_ltv(
+        &mut deps,
+        mock_env(),
+        "0.75",
+        "0.6",
+        "0.74",
+        now() + RAMP_DURATION,
+    )
+    .unwrap();
+
+    let update_buffer_part = |deps: &mut Deps, buffer_part: &str| {
+        execute_governance(
+            deps,
+            mock_env(),
+            GovernanceMsg::UpdateConfig {
+                oracle_addr: None,
+                basset_token_addr: None,
+                stable_denom: None,
+                borrow_ltv_max: None,
+                borrow_ltv_min: None,
+                borrow_ltv_aim: None,
+                basset_max_ltv: None,
+                buffer_part: Some(Decimal256::from_str(buffer_part).unwrap()),
+                price_timeframe: None,
+                stale_price_policy: None,
+            },
+        )
+    };
+
+    //enough for target bands, but less than (0.85 - 0.8) / 10
+    let res = update_buffer_part(&mut deps, "0.002");
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    update_buffer_part(&mut deps, "0.005").unwrap();
+    let response = crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&response).unwrap();
+    assert_eq!(Decimal256::from_str("0.005").unwrap(), config.buffer_part);
+}
@@ -1,3 +1,9 @@
+mod carry;
 mod change_config;
 mod change_governance_addr;
 mod instantiate;
+mod ltv_ramp;
+mod price_feeds;
+mod price_sources;
+mod stale_price_policy;
+mod volatility;
@@ -0,0 +1,116 @@
+use basset_vault::anchor::oracle::PriceResponse;
+use basset_vault::basset_vault_strategy::{BorrowerActionResponse, QueryMsg};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Binary, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub const GOVERNANCE_CONTRACT: &str = "addr0000";
+pub const ORACLE_CONTRACT: &str = "addr0001";
+pub const SECOND_ORACLE_CONTRACT: &str = "addr0003";
+pub const TWAP_ORACLE_CONTRACT: &str = "addr0004";
+
+/// Answers every smart query to contract with the same response
+pub struct PriceFeedsQuerier {
+    pub base: MockQuerier<Empty>,
+    pub responses: HashMap<String, Binary>,
+    //by contract and key
+    pub raw_responses: HashMap<(String, Binary), Binary>,
+}
+
+impl Querier for PriceFeedsQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
+                match self.responses.get(&contract_addr) {
+                    Some(response) => SystemResult::Ok(ContractResult::Ok(response.clone())),
+                    None => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    }),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                match self.raw_responses.get(&(contract_addr.clone(), key)) {
+                    Some(response) => SystemResult::Ok(ContractResult::Ok(response.clone())),
+                    None => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    }),
+                }
+            }
+            _ => self.base.handle_query(&request),
+        }
+    }
+}
+
+pub type Deps = OwnedDeps<MockStorage, MockApi, PriceFeedsQuerier>;
+
+pub fn init() -> Deps {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: PriceFeedsQuerier {
+            base: MockQuerier::new(&[]),
+            responses: HashMap::new(),
+            raw_responses: HashMap::new(),
+        },
+    };
+
+    let msg = basset_vault::basset_vault_strategy::InstantiateMsg {
+        governance_contract_addr: GOVERNANCE_CONTRACT.to_string(),
+        oracle_contract_addr: ORACLE_CONTRACT.to_string(),
+        basset_token_addr: "addr0002".to_string(),
+        stable_denom: "uust".to_string(),
+        borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+        borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+        borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+        basset_max_ltv: Decimal256::from_str("0.5").unwrap(),
+        buffer_part: Decimal256::from_str("0.018").unwrap(),
+        price_timeframe: 60,
+    };
+    crate::contract::instantiate(deps.as_mut(), mock_env(), mock_info("addr0010", &[]), msg)
+        .unwrap();
+
+    deps
+}
+
+pub fn set_oracle_price(deps: &mut Deps, oracle: &str, rate: &str) {
+    let time = mock_env().block.time.seconds();
+    set_oracle_price_updated_at(deps, oracle, rate, time);
+}
+
+pub fn set_oracle_price_updated_at(deps: &mut Deps, oracle: &str, rate: &str, last_updated: u64) {
+    deps.querier.responses.insert(
+        oracle.to_string(),
+        to_binary(&PriceResponse {
+            rate: Decimal256::from_str(rate).unwrap(),
+            last_updated_base: last_updated,
+            last_updated_quote: last_updated,
+        })
+        .unwrap(),
+    );
+}
+
+pub fn set_twap_return_amount(deps: &mut Deps, amount: u64) {
+    deps.querier.responses.insert(
+        TWAP_ORACLE_CONTRACT.to_string(),
+        to_binary(&Uint256::from(amount)).unwrap(),
+    );
+}
+
+pub fn borrower_action(deps: &Deps, borrowed_amount: u64) -> BorrowerActionResponse {
+    let response = crate::contract::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::BorrowerAction {
+            borrowed_amount: Uint256::from(borrowed_amount),
+            locked_basset_amount: Uint256::from(1_000u64),
+        },
+    )
+    .unwrap();
+    from_binary(&response).unwrap()
+}
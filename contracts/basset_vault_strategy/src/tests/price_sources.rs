@@ -0,0 +1,227 @@
+use super::price_feeds::{
+    borrower_action, init, set_oracle_price, set_oracle_price_updated_at, set_twap_return_amount,
+    Deps, GOVERNANCE_CONTRACT, ORACLE_CONTRACT, SECOND_ORACLE_CONTRACT, TWAP_ORACLE_CONTRACT,
+};
+use crate::error::ContractError;
+use crate::state::load_price_sources;
+
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, ExecuteMsg, FreshPriceResponse, GovernanceMsg, PriceSource,
+    PriceSourcesResponse, QueryMsg,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, StdError};
+use std::str::FromStr;
+
+pub fn update_price_sources(
+    deps: &mut Deps,
+    price_sources: Option<Vec<PriceSource>>,
+    max_deviation: Option<Decimal256>,
+) -> Result<(), ContractError> {
+    crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdatePriceSources {
+                price_sources,
+                max_deviation,
+            },
+        },
+    )
+    .map(|_| ())
+}
+
+pub fn extra_sources() -> Vec<PriceSource> {
+    vec![
+        PriceSource::Oracle {
+            contract_addr: SECOND_ORACLE_CONTRACT.to_string(),
+        },
+        PriceSource::AstroportTwap {
+            oracle_contract_addr: TWAP_ORACLE_CONTRACT.to_string(),
+        },
+    ]
+}
+
+fn price_sources(deps: &Deps) -> PriceSourcesResponse {
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::PriceSources {}).unwrap();
+    from_binary(&response).unwrap()
+}
+
+#[test]
+fn fail_to_update_price_sources_if_sender_is_not_governance() {
+    let mut deps = init();
+
+    let res = crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0010", &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdatePriceSources {
+                price_sources: Some(extra_sources()),
+                max_deviation: Some(Decimal256::percent(5)),
+            },
+        },
+    );
+    assert_eq!(Err(ContractError::Unauthorized), res.map(|_| ()));
+}
+
+#[test]
+fn fail_to_update_price_sources_with_wrong_deviation() {
+    let mut deps = init();
+
+    //sources without deviation would never allow borrowing
+    let res = update_price_sources(&mut deps, Some(extra_sources()), None);
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let res = update_price_sources(&mut deps, Some(extra_sources()), Some(Decimal256::one()));
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    let sources_config = load_price_sources(&deps.storage).unwrap();
+    assert_eq!(extra_sources(), sources_config.price_sources);
+    assert_eq!(Decimal256::percent(5), sources_config.max_deviation);
+
+    //empty list leaves only oracle_contract
+    update_price_sources(&mut deps, Some(vec![]), None).unwrap();
+    let sources_config = load_price_sources(&deps.storage).unwrap();
+    assert!(sources_config.price_sources.is_empty());
+}
+
+#[test]
+fn borrower_action_uses_median_price() {
+    let mut deps = init();
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.04");
+    set_twap_return_amount(&mut deps, 1_020_000);
+
+    let response = price_sources(&deps);
+    assert_eq!(3, response.sources.len());
+    assert_eq!(
+        PriceSource::Oracle {
+            contract_addr: ORACLE_CONTRACT.to_string()
+        },
+        response.sources[0].source
+    );
+    assert_eq!(
+        Some(Decimal256::from_str("1.02").unwrap()),
+        response.sources[2].price.as_ref().map(|price| price.rate)
+    );
+    assert_eq!(
+        Some(Decimal256::from_str("1.02").unwrap()),
+        response.median_price
+    );
+    assert!(response.borrow_allowed);
+
+    //max_borrow = 1_000 * 1.02 * 0.5 = 510
+    //aim = 510 * 0.8 = 408
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(408u64), Uint256::from(7u64)),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn borrow_is_refused_when_sources_disagree() {
+    let mut deps = init();
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.2");
+    set_twap_return_amount(&mut deps, 1_000_000);
+
+    let response = price_sources(&deps);
+    assert_eq!(Decimal256::percent(20), response.deviation);
+    assert!(!response.borrow_allowed);
+
+    assert_eq!(BorrowerActionResponse::nothing(), borrower_action(&deps, 0));
+
+    //max_borrow = 1_000 * 1 * 0.5 = 500
+    //ltv = 450 / 500 = 0.9, repay to aim = 450 - 400
+    assert_eq!(
+        BorrowerActionResponse::repay(Uint256::from(50u64), Uint256::from(7u64)),
+        borrower_action(&deps, 450)
+    );
+}
+
+#[test]
+fn unavailable_source_is_skipped() {
+    let mut deps = init();
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.02");
+
+    let response = price_sources(&deps);
+    assert_eq!(None, response.sources[2].price);
+    assert_eq!(
+        Some(Decimal256::from_str("1.01").unwrap()),
+        response.median_price
+    );
+    assert!(response.borrow_allowed);
+}
+
+#[test]
+fn borrow_is_refused_when_most_sources_are_unavailable() {
+    let mut deps = init();
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    let response = price_sources(&deps);
+    assert_eq!(Some(Decimal256::one()), response.median_price);
+    assert!(response.deviation.is_zero());
+    assert!(!response.borrow_allowed);
+
+    assert_eq!(BorrowerActionResponse::nothing(), borrower_action(&deps, 0));
+}
+
+#[test]
+fn fresh_price_is_median_of_sources() {
+    let mut deps = init();
+    update_price_sources(
+        &mut deps,
+        Some(extra_sources()),
+        Some(Decimal256::percent(5)),
+    )
+    .unwrap();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.04");
+    set_twap_return_amount(&mut deps, 1_020_000);
+
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::FreshPrice {}).unwrap();
+    let fresh_price: FreshPriceResponse = from_binary(&response).unwrap();
+    assert_eq!(Decimal256::from_str("1.02").unwrap(), fresh_price.rate);
+
+    //price_timeframe is 60 seconds
+    let time = mock_env().block.time.seconds();
+    set_oracle_price_updated_at(&mut deps, ORACLE_CONTRACT, "1", time - 100);
+    let res = crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::FreshPrice {});
+    assert_eq!(Err(StdError::generic_err("price is stale")), res);
+}
@@ -0,0 +1,173 @@
+use super::price_feeds::{
+    borrower_action, init, set_oracle_price, set_oracle_price_updated_at, Deps,
+    GOVERNANCE_CONTRACT, ORACLE_CONTRACT, SECOND_ORACLE_CONTRACT,
+};
+use crate::error::ContractError;
+
+use basset_vault::basset_vault_strategy::{
+    BorrowerActionResponse, ExecuteMsg, GovernanceMsg, PriceSource, StalePricePolicy,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use std::str::FromStr;
+
+fn update_stale_price_policy(
+    deps: &mut Deps,
+    stale_price_policy: StalePricePolicy,
+) -> Result<(), ContractError> {
+    crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdateConfig {
+                oracle_addr: None,
+                basset_token_addr: None,
+                stable_denom: None,
+                borrow_ltv_max: None,
+                borrow_ltv_min: None,
+                borrow_ltv_aim: None,
+                basset_max_ltv: None,
+                buffer_part: None,
+                price_timeframe: None,
+                stale_price_policy: Some(stale_price_policy),
+            },
+        },
+    )
+    .map(|_| ())
+}
+
+//price_timeframe is 60 seconds
+fn set_stale_oracle_price(deps: &mut Deps, oracle: &str, rate: &str) {
+    let last_updated = mock_env().block.time.seconds() - 100;
+    set_oracle_price_updated_at(deps, oracle, rate, last_updated);
+}
+
+#[test]
+fn fresh_price_is_not_marked_as_stale() {
+    let mut deps = init();
+    set_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    //max_borrow = 1_000 * 1 * 0.5 = 500
+    //aim = 500 * 0.8 = 400
+    let action = borrower_action(&deps, 0);
+    assert!(!action.price_is_stale());
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(400u64), Uint256::from(7u64)),
+        action
+    );
+}
+
+#[test]
+fn stale_price_scales_ltv() {
+    let mut deps = init();
+    set_stale_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    //default factor is 0.5: aim = 500 * 0.4 = 200
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(200u64), Uint256::from(3u64))
+            .with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+
+    update_stale_price_policy(
+        &mut deps,
+        StalePricePolicy::ScaleLtv {
+            factor: Decimal256::from_str("0.9").unwrap(),
+        },
+    )
+    .unwrap();
+
+    //aim = 500 * 0.72 = 360
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(360u64), Uint256::from(6u64))
+            .with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn stale_price_freezes_strategy() {
+    let mut deps = init();
+    update_stale_price_policy(&mut deps, StalePricePolicy::Freeze {}).unwrap();
+    set_stale_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    //ltv = 450 / 500 = 0.9, but nothing is done
+    assert_eq!(
+        BorrowerActionResponse::nothing().with_stale_price(),
+        borrower_action(&deps, 450)
+    );
+    assert_eq!(
+        BorrowerActionResponse::nothing().with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn stale_price_allows_only_repay() {
+    let mut deps = init();
+    update_stale_price_policy(&mut deps, StalePricePolicy::RepayOnly {}).unwrap();
+    set_stale_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+
+    assert_eq!(
+        BorrowerActionResponse::nothing().with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+    //ltv = 450 / 500 = 0.9, repay to aim = 450 - 400
+    assert_eq!(
+        BorrowerActionResponse::repay(Uint256::from(50u64), Uint256::from(7u64)).with_stale_price(),
+        borrower_action(&deps, 450)
+    );
+}
+
+#[test]
+fn stale_price_falls_back_to_secondary_source() {
+    let mut deps = init();
+    update_stale_price_policy(
+        &mut deps,
+        StalePricePolicy::Fallback {
+            source: PriceSource::Oracle {
+                contract_addr: SECOND_ORACLE_CONTRACT.to_string(),
+            },
+        },
+    )
+    .unwrap();
+    set_stale_oracle_price(&mut deps, ORACLE_CONTRACT, "1");
+    set_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.2");
+
+    //max_borrow = 1_000 * 1.2 * 0.5 = 600
+    //aim = 600 * 0.8 = 480
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(480u64), Uint256::from(9u64))
+            .with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+
+    //secondary source is stale too, so only repaying is allowed
+    set_stale_oracle_price(&mut deps, SECOND_ORACLE_CONTRACT, "1.2");
+    assert_eq!(
+        BorrowerActionResponse::nothing().with_stale_price(),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn fail_to_set_wrong_ltv_factor() {
+    let mut deps = init();
+
+    let res = update_stale_price_policy(
+        &mut deps,
+        StalePricePolicy::ScaleLtv {
+            factor: Decimal256::zero(),
+        },
+    );
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let res = update_stale_price_policy(
+        &mut deps,
+        StalePricePolicy::ScaleLtv {
+            factor: Decimal256::from_str("1.1").unwrap(),
+        },
+    );
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+}
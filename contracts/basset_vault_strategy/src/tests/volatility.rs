@@ -0,0 +1,211 @@
+use super::price_feeds::{
+    borrower_action, init, set_oracle_price_updated_at, Deps, GOVERNANCE_CONTRACT, ORACLE_CONTRACT,
+};
+use crate::error::ContractError;
+
+use basset_vault::basset_vault_strategy::{
+    AnyoneMsg, BorrowerActionResponse, EffectiveBandsResponse, ExecuteMsg, GovernanceMsg, QueryMsg,
+    VolatilityConfigResponse,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Env, StdError};
+use std::str::FromStr;
+
+//observations are one volatility period apart
+const MIN_INTERVAL: u64 = crate::VOLATILITY_PERIOD;
+
+fn update_volatility_config(
+    deps: &mut Deps,
+    max_observations: Option<u32>,
+    calm_volatility: &str,
+    stress_volatility: &str,
+) -> Result<(), ContractError> {
+    crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GOVERNANCE_CONTRACT, &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdateVolatilityConfig {
+                max_observations,
+                min_interval: Some(MIN_INTERVAL),
+                calm_volatility: Some(Decimal256::from_str(calm_volatility).unwrap()),
+                stress_volatility: Some(Decimal256::from_str(stress_volatility).unwrap()),
+                max_ltv_reduction: Some(Decimal256::percent(4)),
+                max_buffer_part_increase: Some(Decimal256::percent(2)),
+            },
+        },
+    )
+    .map(|_| ())
+}
+
+fn env_after(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn record_price_at(deps: &mut Deps, env: Env, rate: &str) -> Result<(), ContractError> {
+    set_oracle_price_updated_at(deps, ORACLE_CONTRACT, rate, env.block.time.seconds());
+    crate::contract::execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0005", &[]),
+        ExecuteMsg::Anyone {
+            anyone_msg: AnyoneMsg::RecordPrice {},
+        },
+    )
+    .map(|_| ())
+}
+
+fn query_effective_bands(deps: &Deps) -> EffectiveBandsResponse {
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::EffectiveBands {}).unwrap();
+    from_binary(&response).unwrap()
+}
+
+#[test]
+fn recording_is_disabled_by_default() {
+    let mut deps = init();
+
+    let response =
+        crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::VolatilityConfig {}).unwrap();
+    let volatility_config: VolatilityConfigResponse = from_binary(&response).unwrap();
+    assert!(!volatility_config.enabled);
+
+    let res = record_price_at(&mut deps, mock_env(), "1");
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err(
+            "volatility tracking is disabled"
+        ))),
+        res
+    );
+
+    //effective bands are config ones
+    assert_eq!(
+        EffectiveBandsResponse {
+            borrow_ltv_max: Decimal256::from_str("0.85").unwrap(),
+            borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+            borrow_ltv_aim: Decimal256::from_str("0.8").unwrap(),
+            buffer_part: Decimal256::from_str("0.018").unwrap(),
+            volatility: Decimal256::zero(),
+            observations: 0,
+        },
+        query_effective_bands(&deps)
+    );
+}
+
+#[test]
+fn volatile_price_tightens_bands() {
+    let mut deps = init();
+    update_volatility_config(&mut deps, Some(10), "0.01", "0.05").unwrap();
+
+    record_price_at(&mut deps, mock_env(), "1").unwrap();
+    let res = record_price_at(&mut deps, env_after(MIN_INTERVAL - 1), "1.03");
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err(
+            "too early to record price"
+        ))),
+        res
+    );
+    record_price_at(&mut deps, env_after(MIN_INTERVAL), "1.03").unwrap();
+
+    //volatility 0.03 is halfway to stress
+    assert_eq!(
+        EffectiveBandsResponse {
+            borrow_ltv_max: Decimal256::from_str("0.83").unwrap(),
+            borrow_ltv_min: Decimal256::from_str("0.75").unwrap(),
+            borrow_ltv_aim: Decimal256::from_str("0.78").unwrap(),
+            buffer_part: Decimal256::from_str("0.028").unwrap(),
+            volatility: Decimal256::from_str("0.03").unwrap(),
+            observations: 2,
+        },
+        query_effective_bands(&deps)
+    );
+
+    //max_borrow = 1_000 * 1.03 * 0.5 = 515
+    //aim = 515 * 0.78 = 401.7
+    //buffer_size = 515 * 0.83 * 0.028 = 11.9686
+    assert_eq!(
+        BorrowerActionResponse::borrow(Uint256::from(401u64), Uint256::from(11u64)),
+        borrower_action(&deps, 0)
+    );
+}
+
+#[test]
+fn oldest_observations_are_dropped() {
+    let mut deps = init();
+    update_volatility_config(&mut deps, Some(2), "0.01", "0.05").unwrap();
+
+    record_price_at(&mut deps, mock_env(), "1").unwrap();
+    record_price_at(&mut deps, env_after(MIN_INTERVAL), "1.05").unwrap();
+    assert_eq!(
+        Decimal256::from_str("0.05").unwrap(),
+        query_effective_bands(&deps).volatility
+    );
+
+    //market calmed down, spike is forgotten
+    record_price_at(&mut deps, env_after(2 * MIN_INTERVAL), "1.05").unwrap();
+    let effective_bands = query_effective_bands(&deps);
+    assert_eq!(2, effective_bands.observations);
+    assert_eq!(Decimal256::zero(), effective_bands.volatility);
+    assert_eq!(
+        Decimal256::from_str("0.8").unwrap(),
+        effective_bands.borrow_ltv_aim
+    );
+}
+
+#[test]
+fn stale_price_is_not_recorded() {
+    let mut deps = init();
+    update_volatility_config(&mut deps, Some(10), "0.01", "0.05").unwrap();
+
+    //price_timeframe is 60 seconds
+    let env = mock_env();
+    set_oracle_price_updated_at(
+        &mut deps,
+        ORACLE_CONTRACT,
+        "1",
+        env.block.time.seconds() - 100,
+    );
+    let res = crate::contract::execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0005", &[]),
+        ExecuteMsg::Anyone {
+            anyone_msg: AnyoneMsg::RecordPrice {},
+        },
+    );
+    assert_eq!(
+        Err(ContractError::Std(StdError::generic_err("price is stale"))),
+        res.map(|_| ())
+    );
+}
+
+#[test]
+fn inappropriate_volatility_config_is_rejected() {
+    let mut deps = init();
+
+    let res = update_volatility_config(&mut deps, Some(10), "0.05", "0.05");
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let res = update_volatility_config(&mut deps, Some(101), "0.01", "0.05");
+    assert_eq!(Err(ContractError::InappropriateValue), res);
+
+    let res = crate::contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &[]),
+        ExecuteMsg::Governance {
+            governance_msg: GovernanceMsg::UpdateVolatilityConfig {
+                max_observations: Some(10),
+                min_interval: None,
+                calm_volatility: None,
+                stress_volatility: None,
+                max_ltv_reduction: None,
+                max_buffer_part_increase: None,
+            },
+        },
+    );
+    assert_eq!(Err(ContractError::Unauthorized {}), res.map(|_| ()));
+}
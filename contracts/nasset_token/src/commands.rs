@@ -1,6 +1,12 @@
-use cosmwasm_std::{to_binary, Binary, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg};
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg,
+};
 
-use crate::{querier::query_rewards_contract, ContractResult};
+use crate::{
+    error::ContractError,
+    querier::{query_pause_state, query_rewards_contract},
+    ContractResult,
+};
 use basset_vault::nasset_token_rewards::{
     ExecuteMsg as NAssetRewardsExecuteMsg, TokenMsg as NassetRewardsTokenMsg,
 };
@@ -20,6 +26,7 @@ pub fn transfer(
     recipient: String,
     amount: Uint128,
 ) -> ContractResult<Response> {
+    assert_transfers_not_paused(deps.as_ref())?;
     let sender = info.sender.to_string();
     let rewards_contract = query_rewards_contract(deps.as_ref())?;
 
@@ -109,6 +116,7 @@ pub fn send(
     amount: Uint128,
     msg: Binary,
 ) -> ContractResult<Response> {
+    assert_transfers_not_paused(deps.as_ref())?;
     let sender = info.sender.to_string();
     let rewards_contract = query_rewards_contract(deps.as_ref())?;
 
@@ -149,6 +157,7 @@ pub fn transfer_from(
     recipient: String,
     amount: Uint128,
 ) -> ContractResult<Response> {
+    assert_transfers_not_paused(deps.as_ref())?;
     let rewards_contract = query_rewards_contract(deps.as_ref())?;
 
     let res: Response =
@@ -214,6 +223,7 @@ pub fn send_from(
     amount: Uint128,
     msg: Binary,
 ) -> ContractResult<Response> {
+    assert_transfers_not_paused(deps.as_ref())?;
     let rewards_contract = query_rewards_contract(deps.as_ref())?;
 
     let res: Response = cw20_send_from(
@@ -252,3 +262,11 @@ pub fn send_from(
         .add_submessages(res.messages)
         .add_attributes(res.attributes))
 }
+
+fn assert_transfers_not_paused(deps: Deps) -> ContractResult<()> {
+    if query_pause_state(deps)?.transfers {
+        return Err(ContractError::TransfersPaused);
+    }
+
+    Ok(())
+}
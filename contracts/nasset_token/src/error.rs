@@ -10,6 +10,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Transfers are paused")]
+    TransfersPaused,
+
     #[error("Cannot set to own account")]
     CannotSetOwnAccount,
 
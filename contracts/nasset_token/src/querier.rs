@@ -1,7 +1,8 @@
 use cosmwasm_std::{Addr, Binary, Deps, QueryRequest, StdResult, WasmQuery};
 
 use crate::state::load_config_holder_contract;
-use basset_vault::nasset_token_config_holder::Config;
+use basset_vault::nasset_token_config_holder::{Config, PauseState};
+use cosmwasm_std::from_slice;
 
 pub fn query_rewards_contract(deps: Deps) -> StdResult<Addr> {
     let config_holder_contract = load_config_holder_contract(deps.storage)?;
@@ -13,3 +14,16 @@ pub fn query_rewards_contract(deps: Deps) -> StdResult<Addr> {
 
     Ok(config.nasset_token_rewards_contract)
 }
+
+pub fn query_pause_state(deps: Deps) -> StdResult<PauseState> {
+    let config_holder_contract = load_config_holder_contract(deps.storage)?;
+
+    //pause state is not stored until guardian set it
+    match deps
+        .querier
+        .query_wasm_raw(config_holder_contract.to_string(), b"pause".as_ref())?
+    {
+        Some(pause_state) => from_slice(&pause_state),
+        None => Ok(PauseState::default()),
+    }
+}
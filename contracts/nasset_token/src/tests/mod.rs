@@ -5,11 +5,11 @@ pub const MOCK_REWARDS_CONTRACT_ADDR: &str = "rewards";
 pub const MOCK_TOKEN_CONTRACT_ADDR: &str = "token";
 pub const MOCK_OWNER_ADDR: &str = "owner";
 
-use basset_vault::nasset_token_config_holder::Config;
+use basset_vault::nasset_token_config_holder::{Config, PauseState};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_slice, to_binary, Addr, Coin, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
-    QueryRequest, SystemError, SystemResult, WasmQuery,
+    from_slice, to_binary, Addr, Binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
 };
 
 /// copypasted from TerraSwap
@@ -36,6 +36,7 @@ pub fn mock_dependencies(
 
 pub struct WasmMockQuerier {
     nasset_token_config: Option<Config>,
+    pause_state: Option<PauseState>,
     base: MockQuerier<Empty>,
 }
 
@@ -67,6 +68,7 @@ impl WasmMockQuerier {
                 }
 
                 let prefix_config = b"config";
+                let prefix_pause = b"pause";
 
                 if key.to_vec() == prefix_config {
                     if let Some(ref nasset_token_config) = self.nasset_token_config {
@@ -77,6 +79,13 @@ impl WasmMockQuerier {
                             request: key.into(),
                         })
                     }
+                } else if key.to_vec() == prefix_pause {
+                    match self.pause_state {
+                        Some(ref pause_state) => {
+                            SystemResult::Ok(ContractResult::from(to_binary(pause_state)))
+                        }
+                        None => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
@@ -90,6 +99,7 @@ impl WasmMockQuerier {
     pub fn new(base: MockQuerier<Empty>) -> Self {
         WasmMockQuerier {
             nasset_token_config: None,
+            pause_state: None,
             base,
         }
     }
@@ -97,4 +107,8 @@ impl WasmMockQuerier {
     pub fn with_nasset_token_config(&mut self, config: Config) {
         self.nasset_token_config = Some(config);
     }
+
+    pub fn with_pause_state(&mut self, pause_state: PauseState) {
+        self.pause_state = Some(pause_state);
+    }
 }
@@ -2,6 +2,7 @@ use cosmwasm_std::{to_binary, Api, CosmosMsg, Querier, Storage, SubMsg, Uint128,
 use cosmwasm_std::{DepsMut, OwnedDeps};
 
 use basset_vault::nasset_token::InstantiateMsg as TokenInstantiateMsg;
+use basset_vault::nasset_token_config_holder::PauseState;
 use basset_vault::nasset_token_rewards::{
     ExecuteMsg as NAssetRewardsExecuteMsg, TokenMsg as NassetRewardsTokenMsg,
 };
@@ -13,6 +14,7 @@ use super::mock_dependencies;
 use super::{MOCK_CONFIG_HOLDER_CONTRACT_ADDR, MOCK_OWNER_ADDR, MOCK_REWARDS_CONTRACT_ADDR};
 use crate::{
     contract::{execute, instantiate},
+    error::ContractError,
     state::load_config_holder_contract,
 };
 use cosmwasm_std::testing::{mock_env, mock_info};
@@ -459,3 +461,48 @@ fn send_from() {
         )
     );
 }
+
+#[test]
+fn transfers_paused() {
+    let mut deps = mock_dependencies(&[]);
+    let addr1 = "addr0001".to_string();
+    let addr2 = "addr0002".to_string();
+    let amount1 = Uint128::from(12340000u128);
+
+    do_init_with_minter(&mut deps, MOCK_OWNER_ADDR.to_string(), None);
+    do_mint(deps.as_mut(), addr1.clone(), amount1);
+
+    deps.querier
+        .with_pause_state(PauseState { transfers: true });
+
+    let info = mock_info(&addr1, &[]);
+    let msg = ExecuteMsg::Transfer {
+        recipient: addr2.clone(),
+        amount: Uint128::new(1u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert!(matches!(res, Err(ContractError::TransfersPaused)));
+
+    let msg = ExecuteMsg::Send {
+        contract: addr2.clone(),
+        amount: Uint128::new(1u128),
+        msg: to_binary(&"").unwrap(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert!(matches!(res, Err(ContractError::TransfersPaused)));
+
+    //burn is still allowed
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::new(1u128),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_pause_state(PauseState { transfers: false });
+
+    let msg = ExecuteMsg::Transfer {
+        recipient: addr2,
+        amount: Uint128::new(1u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
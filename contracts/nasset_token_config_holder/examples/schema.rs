@@ -2,7 +2,8 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use basset_vault::nasset_token_config_holder::{
-    AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, GuardianMsg, InstantiateMsg, MigrateMsg,
+    PauseStatusResponse, QueryMsg,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -16,7 +17,9 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(AnyoneMsg), &out_dir);
     export_schema(&schema_for!(GovernanceMsg), &out_dir);
+    export_schema(&schema_for!(GuardianMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(PauseStatusResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
@@ -5,13 +5,14 @@ use cosmwasm_std::{
 
 use crate::error::ContractError;
 use crate::state::{
-    load_config, load_gov_update, remove_gov_update, save_config, save_gov_update,
+    load_config, load_gov_update, load_guardian, load_pause_state, remove_gov_update,
+    remove_guardian, save_config, save_gov_update, save_guardian, save_pause_state,
     set_nasset_token_rewards_contract, GovernanceUpdateState,
 };
 use crate::ContractResult;
 use basset_vault::nasset_token_config_holder::{
-    AnyoneMsg, Config, ConfigResponse, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg,
+    AnyoneMsg, Config, ConfigResponse, ExecuteMsg, GovernanceMsg, GuardianMsg, InstantiateMsg,
+    MigrateMsg, PauseState, PauseStatusResponse, QueryMsg,
 };
 
 #[entry_point]
@@ -75,9 +76,46 @@ pub fn execute(
                     gov_addr,
                     seconds_to_wait_for_accept_gov_tx,
                 } => update_governance_addr(deps, env, gov_addr, seconds_to_wait_for_accept_gov_tx),
+
+                GovernanceMsg::UpdateGuardian { guardian_addr } => {
+                    update_guardian(deps, guardian_addr)
+                }
             }
         }
+
+        ExecuteMsg::Guardian { guardian_msg } => {
+            let config = load_config(deps.storage)?;
+            let guardian = load_guardian(deps.storage)?;
+            if Some(&info.sender) != guardian.as_ref() && info.sender != config.governance_contract
+            {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            match guardian_msg {
+                GuardianMsg::SetPause { transfers } => set_pause(deps, transfers),
+            }
+        }
+    }
+}
+
+fn update_guardian(deps: DepsMut, guardian_addr: Option<String>) -> ContractResult<Response> {
+    match guardian_addr {
+        Some(ref guardian_addr) => {
+            save_guardian(deps.storage, &deps.api.addr_validate(guardian_addr)?)?
+        }
+        None => remove_guardian(deps.storage),
     }
+
+    Ok(Response::default())
+}
+
+fn set_pause(deps: DepsMut, transfers: bool) -> ContractResult<Response> {
+    save_pause_state(deps.storage, &PauseState { transfers })?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_pause"),
+        ("transfers", &transfers.to_string()),
+    ]))
 }
 
 fn update_config(
@@ -142,9 +180,17 @@ fn get_time(block: &BlockInfo) -> u64 {
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::PauseStatus {} => to_binary(&query_pause_status(deps)?),
     }
 }
 
+pub fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    Ok(PauseStatusResponse {
+        guardian_addr: load_guardian(deps.storage)?.map(|addr| addr.to_string()),
+        transfers: load_pause_state(deps.storage)?.transfers,
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = load_config(deps.storage)?;
     Ok(ConfigResponse {
@@ -1,4 +1,4 @@
-use basset_vault::nasset_token_config_holder::Config;
+use basset_vault::nasset_token_config_holder::{Config, PauseState};
 use cosmwasm_std::{Addr, StdResult, Storage};
 use cw_storage_plus::Item;
 use serde::{Deserialize, Serialize};
@@ -11,6 +11,9 @@ pub struct GovernanceUpdateState {
 
 static KEY_CONFIG: Item<Config> = Item::new("config");
 static KEY_GOVERNANCE_UPDATE: Item<GovernanceUpdateState> = Item::new("gov_update");
+static KEY_GUARDIAN: Item<Addr> = Item::new("guardian");
+//nasset_token reads it with raw query
+static KEY_PAUSE: Item<PauseState> = Item::new("pause");
 
 pub fn load_config(storage: &dyn Storage) -> StdResult<Config> {
     KEY_CONFIG.load(storage)
@@ -44,3 +47,25 @@ pub fn save_gov_update(
 pub fn remove_gov_update(storage: &mut dyn Storage) -> () {
     KEY_GOVERNANCE_UPDATE.remove(storage)
 }
+
+pub fn load_guardian(storage: &dyn Storage) -> StdResult<Option<Addr>> {
+    KEY_GUARDIAN.may_load(storage)
+}
+
+pub fn save_guardian(storage: &mut dyn Storage, guardian: &Addr) -> StdResult<()> {
+    KEY_GUARDIAN.save(storage, guardian)
+}
+
+pub fn remove_guardian(storage: &mut dyn Storage) {
+    KEY_GUARDIAN.remove(storage)
+}
+
+pub fn load_pause_state(storage: &dyn Storage) -> StdResult<PauseState> {
+    KEY_PAUSE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_pause_state(storage: &mut dyn Storage, pause_state: &PauseState) -> StdResult<()> {
+    KEY_PAUSE.save(storage, pause_state)
+}
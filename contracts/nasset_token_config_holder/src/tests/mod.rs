@@ -1,3 +1,4 @@
 mod change_config;
 mod change_governance_addr;
 mod instantiate;
+mod pause;
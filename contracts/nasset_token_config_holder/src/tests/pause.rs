@@ -0,0 +1,68 @@
+use crate::error::ContractError;
+use crate::state::load_pause_state;
+
+use basset_vault::nasset_token_config_holder::{
+    ExecuteMsg, GovernanceMsg, GuardianMsg, PauseStatusResponse, QueryMsg,
+};
+use cosmwasm_std::from_binary;
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::testing::{mock_env, mock_info};
+
+#[test]
+fn guardian_can_pause_transfers() {
+    let mut deps = mock_dependencies(&[]);
+    let governance_contract_addr = "addr0000".to_string();
+    let guardian_addr = "addr0002".to_string();
+
+    let msg = basset_vault::nasset_token_config_holder::InstantiateMsg {
+        governance_contract_addr: governance_contract_addr.clone(),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0010", &[]);
+    crate::contract::instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // ====================================
+    // ====================================
+    // ====================================
+
+    let set_pause_msg = ExecuteMsg::Guardian {
+        guardian_msg: GuardianMsg::SetPause { transfers: true },
+    };
+
+    let info = mock_info(&guardian_addr, &[]);
+    let res = crate::contract::execute(deps.as_mut(), mock_env(), info, set_pause_msg.clone());
+    assert_eq!(ContractError::Unauthorized, res.err().unwrap());
+
+    let update_guardian_msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateGuardian {
+            guardian_addr: Some(guardian_addr.clone()),
+        },
+    };
+    let info = mock_info(&governance_contract_addr, &[]);
+    crate::contract::execute(deps.as_mut(), mock_env(), info, update_guardian_msg).unwrap();
+
+    let info = mock_info(&guardian_addr, &[]);
+    crate::contract::execute(deps.as_mut(), mock_env(), info, set_pause_msg).unwrap();
+    assert!(load_pause_state(&deps.storage).unwrap().transfers);
+
+    let pause_status: PauseStatusResponse = from_binary(
+        &crate::contract::query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        PauseStatusResponse {
+            guardian_addr: Some(guardian_addr),
+            transfers: true,
+        },
+        pause_status
+    );
+
+    //governance can unpause
+    let unpause_msg = ExecuteMsg::Guardian {
+        guardian_msg: GuardianMsg::SetPause { transfers: false },
+    };
+    let info = mock_info(&governance_contract_addr, &[]);
+    crate::contract::execute(deps.as_mut(), mock_env(), info, unpause_msg).unwrap();
+    assert!(!load_pause_state(&deps.storage).unwrap().transfers);
+}
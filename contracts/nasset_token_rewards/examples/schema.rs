@@ -2,8 +2,9 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use basset_vault::nasset_token_rewards::{
-    AccruedRewardsResponse, AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, HolderResponse,
-    HoldersResponse, InstantiateMsg, MigrateMsg, QueryMsg, StateResponse, TokenMsg,
+    AccruedRewardsResponse, AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, GuardianMsg,
+    HolderResponse, HoldersResponse, InstantiateMsg, MigrateMsg, PauseStatusResponse, QueryMsg,
+    StateResponse, TokenMsg,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -17,6 +18,7 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(AnyoneMsg), &out_dir);
     export_schema(&schema_for!(GovernanceMsg), &out_dir);
+    export_schema(&schema_for!(GuardianMsg), &out_dir);
     export_schema(&schema_for!(TokenMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
@@ -24,5 +26,6 @@ fn main() {
     export_schema(&schema_for!(AccruedRewardsResponse), &out_dir);
     export_schema(&schema_for!(HolderResponse), &out_dir);
     export_schema(&schema_for!(HoldersResponse), &out_dir);
+    export_schema(&schema_for!(PauseStatusResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
@@ -8,8 +8,9 @@ use crate::{
     error::ContractError,
     math::decimal_summation_in_256,
     state::{
-        load_config, load_gov_update, load_holder, load_state, remove_gov_update, save_config,
-        save_gov_update, save_state, Config, GovernanceUpdateState, Holder, State,
+        load_config, load_gov_update, load_guardian, load_holder, load_pause_state, load_state,
+        remove_gov_update, remove_guardian, save_config, save_gov_update, save_guardian,
+        save_pause_state, save_state, Config, GovernanceUpdateState, Holder, PauseState, State,
     },
     ContractResult,
 };
@@ -134,6 +135,8 @@ pub fn claim_rewards(
     info: MessageInfo,
     recipient: Option<String>,
 ) -> ContractResult<Response> {
+    assert_claim_not_paused(deps.as_ref())?;
+
     let holder_addr = &info.sender;
     match recipient {
         Some(recipient) => {
@@ -149,10 +152,46 @@ pub fn claim_rewards_for_someone(
     env: Env,
     recipient: String,
 ) -> ContractResult<Response> {
+    assert_claim_not_paused(deps.as_ref())?;
+
     let addr = deps.api.addr_validate(&recipient)?;
     claim_rewards_logic(deps, env, &addr, &addr)
 }
 
+fn assert_claim_not_paused(deps: Deps) -> ContractResult<()> {
+    if load_pause_state(deps.storage)?.claim {
+        return Err(ContractError::ClaimPaused);
+    }
+
+    Ok(())
+}
+
+pub fn update_guardian(deps: DepsMut, guardian_addr: Option<String>) -> ContractResult<Response> {
+    match guardian_addr {
+        Some(ref guardian_addr) => {
+            save_guardian(deps.storage, &deps.api.addr_validate(guardian_addr)?)?
+        }
+        None => remove_guardian(deps.storage),
+    }
+
+    Ok(Response::default())
+}
+
+pub fn set_pause(deps: DepsMut, info: MessageInfo, claim: bool) -> ContractResult<Response> {
+    let config = load_config(deps.storage)?;
+    let guardian = load_guardian(deps.storage)?;
+    if Some(&info.sender) != guardian.as_ref() && info.sender != config.governance_contract {
+        return Err(ContractError::Unauthorized);
+    }
+
+    save_pause_state(deps.storage, &PauseState { claim })?;
+
+    Ok(
+        Response::new()
+            .add_attributes(vec![("action", "set_pause"), ("claim", &claim.to_string())]),
+    )
+}
+
 fn claim_rewards_logic(
     deps: DepsMut,
     env: Env,
@@ -11,7 +11,8 @@ use crate::{
 };
 use crate::{state::Config, ContractResult};
 use basset_vault::nasset_token_rewards::{
-    AnyoneMsg, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg, QueryMsg, TokenMsg,
+    AnyoneMsg, ExecuteMsg, GovernanceMsg, GuardianMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    TokenMsg,
 };
 
 #[entry_point]
@@ -105,8 +106,16 @@ pub fn execute(
                     gov_addr,
                     seconds_to_wait_for_accept_gov_tx,
                 ),
+
+                GovernanceMsg::UpdateGuardian { guardian_addr } => {
+                    commands::update_guardian(deps, guardian_addr)
+                }
             }
         }
+
+        ExecuteMsg::Guardian { guardian_msg } => match guardian_msg {
+            GuardianMsg::SetPause { claim } => commands::set_pause(deps, info, claim),
+        },
     }
 }
 
@@ -124,6 +133,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             limit,
             order_by,
         } => to_binary(&queries::query_holders(deps, start_after, limit, order_by)?),
+        QueryMsg::PauseStatus {} => to_binary(&queries::query_pause_status(deps)?),
     }
 }
 
@@ -9,6 +9,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Claim is paused")]
+    ClaimPaused,
+
     #[error("Impossible: {0}")]
     Impossible(String),
 
@@ -42,6 +45,7 @@ impl Into<StdError> for ContractError {
         match self {
             ContractError::Std(std) => std,
             ContractError::Unauthorized => StdError::generic_err("unauthorized"),
+            ContractError::ClaimPaused => StdError::generic_err("claim is paused"),
             ContractError::Impossible(msg) => {
                 StdError::generic_err(format!("impossible case, message: '{}'", msg))
             }
@@ -7,14 +7,15 @@ use crate::{
 use basset_vault::{
     common::OrderBy,
     nasset_token_rewards::{
-        AccruedRewardsResponse, ConfigResponse, HolderResponse, HoldersResponse, StateResponse,
+        AccruedRewardsResponse, ConfigResponse, HolderResponse, HoldersResponse,
+        PauseStatusResponse, StateResponse,
     },
 };
 use cosmwasm_std::{Addr, Decimal, Deps, Env, StdError, StdResult, Storage, Uint128};
 use cw0::{calc_range_end, calc_range_start};
 use cw_storage_plus::Bound;
 
-use crate::state::{load_config, load_holder, load_state, Config};
+use crate::state::{load_config, load_guardian, load_holder, load_pause_state, load_state, Config};
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = load_config(deps.storage)?;
@@ -137,6 +138,13 @@ pub fn holder_to_response(
     })
 }
 
+pub fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    Ok(PauseStatusResponse {
+        guardian_addr: load_guardian(deps.storage)?.map(|addr| addr.to_string()),
+        claim: load_pause_state(deps.storage)?.claim,
+    })
+}
+
 #[cfg(test)]
 mod test {
     use crate::state::save_holder;
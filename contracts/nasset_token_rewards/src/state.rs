@@ -24,6 +24,11 @@ pub struct Holder {
     pub pending_rewards: Decimal,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PauseState {
+    pub claim: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct GovernanceUpdateState {
     pub new_governance_contract_addr: Addr,
@@ -33,6 +38,8 @@ pub struct GovernanceUpdateState {
 static KEY_CONFIG: Item<Config> = Item::new("config");
 static KEY_STATE: Item<State> = Item::new("state");
 static KEY_GOVERNANCE_UPDATE: Item<GovernanceUpdateState> = Item::new("gov_update");
+static KEY_GUARDIAN: Item<Addr> = Item::new("guardian");
+static KEY_PAUSE: Item<PauseState> = Item::new("pause");
 pub(crate) static HOLDERS: Map<&Addr, Holder> = Map::new("state");
 
 pub fn load_state(storage: &dyn Storage) -> StdResult<State> {
@@ -75,3 +82,25 @@ pub fn save_gov_update(
 pub fn remove_gov_update(storage: &mut dyn Storage) -> () {
     KEY_GOVERNANCE_UPDATE.remove(storage)
 }
+
+pub fn load_guardian(storage: &dyn Storage) -> StdResult<Option<Addr>> {
+    KEY_GUARDIAN.may_load(storage)
+}
+
+pub fn save_guardian(storage: &mut dyn Storage, guardian: &Addr) -> StdResult<()> {
+    KEY_GUARDIAN.save(storage, guardian)
+}
+
+pub fn remove_guardian(storage: &mut dyn Storage) {
+    KEY_GUARDIAN.remove(storage)
+}
+
+pub fn load_pause_state(storage: &dyn Storage) -> StdResult<PauseState> {
+    KEY_PAUSE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_pause_state(storage: &mut dyn Storage, pause_state: &PauseState) -> StdResult<()> {
+    KEY_PAUSE.save(storage, pause_state)
+}
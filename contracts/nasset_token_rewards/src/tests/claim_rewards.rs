@@ -1,5 +1,5 @@
 use super::sdk::Sdk;
-use crate::tests::sdk::PSI_TOKEN_ADDR;
+use crate::tests::sdk::{GOVERNANCE_CONTRACT_ADDR, PSI_TOKEN_ADDR};
 use crate::{
     error::ContractError,
     state::{load_holder, load_state},
@@ -530,3 +530,25 @@ fn decrease_balance_should_update_index() {
     assert_eq!(rewards, state.prev_reward_balance);
     //===============================================================================
 }
+
+#[test]
+fn claim_rewards_fails_when_paused() {
+    let mut sdk = Sdk::init();
+    let user_1_address = Addr::unchecked("addr1000".to_string());
+    let governance = Addr::unchecked(GOVERNANCE_CONTRACT_ADDR);
+
+    sdk.set_psi_balance(Uint128::new(1000));
+    sdk.increase_user_balance(&user_1_address, Uint128::new(100));
+
+    //only guardian or governance can pause
+    let res = sdk.set_pause(&user_1_address, true);
+    assert_eq!(ContractError::Unauthorized, res.err().unwrap());
+
+    sdk.set_pause(&governance, true).unwrap();
+    let res = sdk.claim_rewards(&user_1_address);
+    assert_eq!(ContractError::ClaimPaused, res.err().unwrap());
+
+    sdk.set_pause(&governance, false).unwrap();
+    let res = sdk.claim_rewards(&user_1_address).unwrap();
+    assert_eq!(1, res.messages.len());
+}
@@ -107,6 +107,18 @@ impl Sdk {
         )
     }
 
+    pub fn set_pause(&mut self, sender: &Addr, claim: bool) -> ContractResult<Response<Empty>> {
+        let info = mock_info(sender.as_str(), &[]);
+        crate::contract::execute(
+            self.deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Guardian {
+                guardian_msg: basset_vault::nasset_token_rewards::GuardianMsg::SetPause { claim },
+            },
+        )
+    }
+
     pub fn query_holder_state(
         &self,
         holder: &Addr,
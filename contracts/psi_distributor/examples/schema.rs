@@ -2,7 +2,8 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use basset_vault::psi_distributor::{
-    AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    AnyoneMsg, ConfigResponse, ExecuteMsg, GovernanceMsg, InstantiateMsg, KeeperConfigResponse,
+    MigrateMsg, QueryMsg,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -18,5 +19,6 @@ fn main() {
     export_schema(&schema_for!(GovernanceMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(KeeperConfigResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
@@ -1,23 +1,25 @@
 use cosmwasm_std::{
-    to_binary, BlockInfo, CosmosMsg, DepsMut, Empty, Env, MessageInfo, Response, StdError, SubMsg,
-    WasmMsg,
+    to_binary, Addr, BlockInfo, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, SubMsg, WasmMsg,
 };
 
 use crate::error::ContractError;
 use crate::state::{
-    load_aim_ltv, load_config, load_gov_update, remove_gov_update, save_config, save_gov_update,
-    GovernanceUpdateState,
+    load_aim_ltv, load_config, load_gov_update, load_keeper_config,
+    load_last_keeper_reward_seconds, remove_gov_update, save_config, save_gov_update,
+    save_keeper_config, save_last_keeper_reward_seconds, GovernanceUpdateState,
 };
 use crate::{state::Config, ContractResult};
 use basset_vault::astroport_pair::Cw20HookMsg as AstroportCw20HookMsg;
 use basset_vault::nasset_token_rewards::{
     AnyoneMsg as NAssetTokenRewardsAnyoneMsg, ExecuteMsg as NAssetTokenRewardsExecuteMsg,
 };
+use basset_vault::psi_distributor::RewardsDistribution;
 use basset_vault::querier::query_token_balance;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cw20::Cw20ExecuteMsg;
 
-pub fn distribute_rewards(deps: DepsMut, env: Env) -> ContractResult<Response> {
+pub fn distribute_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<Response> {
     let config: Config = load_config(deps.storage)?;
     let psi_balance: Uint256 =
         query_token_balance(deps.as_ref(), &config.psi_token, &env.contract.address).into();
@@ -26,6 +28,12 @@ pub fn distribute_rewards(deps: DepsMut, env: Env) -> ContractResult<Response> {
         return Err(StdError::generic_err("psi balance is zero").into());
     }
 
+    let keeper_reward = calc_keeper_reward(deps.as_ref(), &env, &info.sender, psi_balance)?;
+    if !keeper_reward.is_zero() {
+        save_last_keeper_reward_seconds(deps.storage, &get_time(&env.block))?;
+    }
+    let psi_balance = psi_balance - keeper_reward;
+
     let aim_ltv = load_aim_ltv(deps.as_ref(), &config)?;
 
     let rewards_distribution = RewardsDistribution::calc(
@@ -36,7 +44,7 @@ pub fn distribute_rewards(deps: DepsMut, env: Env) -> ContractResult<Response> {
         config.tax_rate,
     );
 
-    let mut messages: Vec<SubMsg<Empty>> = Vec::with_capacity(4);
+    let mut messages: Vec<SubMsg<Empty>> = Vec::with_capacity(5);
     if !rewards_distribution.nasset_holder.is_zero() {
         messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: config.psi_token.to_string(),
@@ -83,7 +91,18 @@ pub fn distribute_rewards(deps: DepsMut, env: Env) -> ContractResult<Response> {
         })));
     }
 
-    Ok(Response::new()
+    if !keeper_reward.is_zero() {
+        messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.psi_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: keeper_reward.into(),
+            })?,
+        })));
+    }
+
+    let response = Response::new()
         .add_submessages(messages)
         .add_attributes(vec![
             ("action", "rewards_distribution"),
@@ -99,43 +118,38 @@ pub fn distribute_rewards(deps: DepsMut, env: Env) -> ContractResult<Response> {
                 "community_pool_rewards",
                 &rewards_distribution.community_pool.to_string(),
             ),
-        ]))
-}
+        ]);
 
-struct RewardsDistribution {
-    pub nasset_holder: Uint256,
-    pub governance: Uint256,
-    pub community_pool: Uint256,
+    if keeper_reward.is_zero() {
+        Ok(response)
+    } else {
+        Ok(response.add_attribute("keeper_reward", keeper_reward.to_string()))
+    }
 }
 
-impl RewardsDistribution {
-    pub fn calc(
-        psi_amount: Uint256,
-        aim_ltv: Decimal256,
-        manual_ltv: Decimal256,
-        fee_rate: Decimal256,
-        tax_rate: Decimal256,
-    ) -> Self {
-        if manual_ltv >= aim_ltv {
-            return Self {
-                nasset_holder: psi_amount,
-                governance: Uint256::zero(),
-                community_pool: Uint256::zero(),
-            };
-        }
-
-        let protocol_fee = (aim_ltv - manual_ltv) * fee_rate;
-        let protocol_rewards = psi_amount * protocol_fee;
-
-        let community_pool_rewards = protocol_rewards * tax_rate;
-        let governance_rewards = protocol_rewards - community_pool_rewards;
-        let nassest_holder_rewards = psi_amount - protocol_rewards;
-
-        Self {
-            nasset_holder: nassest_holder_rewards,
-            governance: governance_rewards,
-            community_pool: community_pool_rewards,
-        }
+/// Psi amount paid to DistributeRewards caller
+fn calc_keeper_reward(
+    deps: Deps,
+    env: &Env,
+    keeper: &Addr,
+    psi_balance: Uint256,
+) -> ContractResult<Uint256> {
+    let keeper_config = load_keeper_config(deps.storage)?;
+    if keeper_config.basset_vault.as_ref() == Some(keeper) {
+        return Ok(Uint256::zero());
+    }
+
+    let last_reward_time = load_last_keeper_reward_seconds(deps.storage)?;
+    if last_reward_time != 0 && get_time(&env.block) < last_reward_time + keeper_config.min_interval
+    {
+        return Ok(Uint256::zero());
+    }
+
+    let reward = psi_balance * keeper_config.reward_rate;
+    if reward > keeper_config.reward_cap {
+        Ok(keeper_config.reward_cap)
+    } else {
+        Ok(reward)
     }
 }
 
@@ -191,6 +205,36 @@ pub fn update_config(
     Ok(Response::default())
 }
 
+pub fn update_keeper_config(
+    deps: DepsMut,
+    reward_rate: Option<Decimal256>,
+    reward_cap: Option<Uint256>,
+    min_interval: Option<u64>,
+    basset_vault_addr: Option<String>,
+) -> ContractResult<Response> {
+    let mut keeper_config = load_keeper_config(deps.storage)?;
+
+    if let Some(reward_rate) = reward_rate {
+        validate_field_to_one(&reward_rate, "reward_rate", &Decimal256::one())?;
+        keeper_config.reward_rate = reward_rate;
+    }
+
+    if let Some(reward_cap) = reward_cap {
+        keeper_config.reward_cap = reward_cap;
+    }
+
+    if let Some(min_interval) = min_interval {
+        keeper_config.min_interval = min_interval;
+    }
+
+    if let Some(ref basset_vault_addr) = basset_vault_addr {
+        keeper_config.basset_vault = Some(deps.api.addr_validate(basset_vault_addr)?);
+    }
+
+    save_keeper_config(deps.storage, &keeper_config)?;
+    Ok(Response::default())
+}
+
 pub fn update_governance_addr(
     deps: DepsMut,
     env: Env,
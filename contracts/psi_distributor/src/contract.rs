@@ -7,7 +7,9 @@ use crate::{
     commands,
     error::ContractError,
     queries,
-    state::{load_config, load_legacy_config, save_config, Config},
+    state::{
+        load_config, load_legacy_config, save_config, save_keeper_config, Config, KeeperConfig,
+    },
     ContractResult,
 };
 use basset_vault::psi_distributor::{
@@ -18,7 +20,7 @@ use basset_vault::psi_distributor::{
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> ContractResult<Response> {
     let one = Decimal256::one();
@@ -47,6 +49,15 @@ pub fn instantiate(
     };
     save_config(deps.storage, &config)?;
 
+    //instantiated by basset_vault
+    save_keeper_config(
+        deps.storage,
+        &KeeperConfig {
+            basset_vault: Some(info.sender),
+            ..KeeperConfig::default()
+        },
+    )?;
+
     Ok(Response::default())
 }
 
@@ -59,7 +70,7 @@ pub fn execute(
 ) -> ContractResult<Response> {
     match msg {
         ExecuteMsg::Anyone { anyone_msg } => match anyone_msg {
-            AnyoneMsg::DistributeRewards {} => commands::distribute_rewards(deps, env),
+            AnyoneMsg::DistributeRewards {} => commands::distribute_rewards(deps, env, info),
             AnyoneMsg::AcceptGovernance {} => commands::accept_governance(deps, env, info),
         },
 
@@ -99,6 +110,19 @@ pub fn execute(
                     gov_addr,
                     seconds_to_wait_for_accept_gov_tx,
                 ),
+
+                GovernanceMsg::UpdateKeeperConfig {
+                    reward_rate,
+                    reward_cap,
+                    min_interval,
+                    basset_vault_addr,
+                } => commands::update_keeper_config(
+                    deps,
+                    reward_rate,
+                    reward_cap,
+                    min_interval,
+                    basset_vault_addr,
+                ),
             }
         }
     }
@@ -108,6 +132,7 @@ pub fn execute(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
+        QueryMsg::KeeperConfig {} => to_binary(&queries::query_keeper_config(deps)?),
     }
 }
 
@@ -1,8 +1,8 @@
-use basset_vault::psi_distributor::ConfigResponse;
+use basset_vault::psi_distributor::{ConfigResponse, KeeperConfigResponse};
 use cosmwasm_std::{Deps, StdResult};
 
-use crate::state::load_config;
 use crate::state::Config;
+use crate::state::{load_config, load_keeper_config, load_last_keeper_reward_seconds};
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = load_config(deps.storage)?;
@@ -19,3 +19,15 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         tax_rate: config.tax_rate,
     })
 }
+
+pub fn query_keeper_config(deps: Deps) -> StdResult<KeeperConfigResponse> {
+    let keeper_config = load_keeper_config(deps.storage)?;
+
+    Ok(KeeperConfigResponse {
+        reward_rate: keeper_config.reward_rate,
+        reward_cap: keeper_config.reward_cap,
+        min_interval: keeper_config.min_interval,
+        basset_vault_addr: keeper_config.basset_vault.map(|addr| addr.to_string()),
+        last_reward_time: load_last_keeper_reward_seconds(deps.storage)?,
+    })
+}
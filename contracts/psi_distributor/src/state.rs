@@ -1,7 +1,7 @@
 use cw_storage_plus::Item;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_bignumber::Decimal256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{Addr, Binary, Deps, QueryRequest, StdResult, Storage, WasmQuery};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -29,6 +29,18 @@ pub struct LegacyConfig {
     pub tax_rate: Decimal256,
 }
 
+/// Reward for calling DistributeRewards.
+/// Default is zero reward, i.e. disabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct KeeperConfig {
+    pub reward_rate: Decimal256,
+    pub reward_cap: Uint256,
+    pub min_interval: u64,
+    /// basset_vault calls DistributeRewards itself after buying Psi,
+    /// such calls are not rewarded
+    pub basset_vault: Option<Addr>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct GovernanceUpdateState {
     pub new_governance_contract_addr: Addr,
@@ -38,6 +50,8 @@ pub struct GovernanceUpdateState {
 static LEGACY_KEY_CONFIG: Item<LegacyConfig> = Item::new("config");
 static KEY_CONFIG: Item<Config> = Item::new("config");
 static KEY_GOVERNANCE_UPDATE: Item<GovernanceUpdateState> = Item::new("gov_update");
+static KEY_KEEPER_CONFIG: Item<KeeperConfig> = Item::new("keeper_config");
+static KEY_LAST_KEEPER_REWARD_SECONDS: Item<u64> = Item::new("last_keeper_reward_secs");
 
 pub fn load_legacy_config(storage: &dyn Storage) -> StdResult<LegacyConfig> {
     LEGACY_KEY_CONFIG.load(storage)
@@ -51,6 +65,32 @@ pub fn save_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
     KEY_CONFIG.save(storage, config)
 }
 
+pub fn load_keeper_config(storage: &dyn Storage) -> StdResult<KeeperConfig> {
+    KEY_KEEPER_CONFIG
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_keeper_config(
+    storage: &mut dyn Storage,
+    keeper_config: &KeeperConfig,
+) -> StdResult<()> {
+    KEY_KEEPER_CONFIG.save(storage, keeper_config)
+}
+
+pub fn load_last_keeper_reward_seconds(storage: &dyn Storage) -> StdResult<u64> {
+    KEY_LAST_KEEPER_REWARD_SECONDS
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn save_last_keeper_reward_seconds(
+    storage: &mut dyn Storage,
+    reward_seconds: &u64,
+) -> StdResult<()> {
+    KEY_LAST_KEEPER_REWARD_SECONDS.save(storage, reward_seconds)
+}
+
 pub fn load_gov_update(storage: &dyn Storage) -> StdResult<GovernanceUpdateState> {
     KEY_GOVERNANCE_UPDATE.load(storage)
 }
@@ -0,0 +1,101 @@
+use super::sdk::Sdk;
+use crate::{
+    error::ContractError,
+    tests::sdk::{GOVERNANCE_CONTRACT_ADDR, PSI_TOKEN_ADDR},
+};
+use basset_vault::psi_distributor::{ExecuteMsg, GovernanceMsg, KeeperConfigResponse, QueryMsg};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Response, SubMsg, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+const KEEPER: &str = "addr9990";
+
+fn update_keeper_config(
+    sdk: &mut Sdk,
+    reward_rate: Option<Decimal256>,
+    reward_cap: Option<Uint256>,
+    min_interval: Option<u64>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Governance {
+        governance_msg: GovernanceMsg::UpdateKeeperConfig {
+            reward_rate,
+            reward_cap,
+            min_interval,
+            basset_vault_addr: None,
+        },
+    };
+    let info = mock_info(GOVERNANCE_CONTRACT_ADDR, &[]);
+    crate::contract::execute(sdk.deps.as_mut(), mock_env(), info, msg)
+}
+
+fn keeper_reward_msg(amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: PSI_TOKEN_ADDR.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: KEEPER.to_string(),
+            amount: Uint128::new(amount),
+        })
+        .unwrap(),
+    }))
+}
+
+#[test]
+fn instantiator_is_not_rewarded() {
+    let mut sdk = Sdk::init();
+
+    let keeper_config: KeeperConfigResponse = from_binary(
+        &crate::contract::query(sdk.deps.as_ref(), mock_env(), QueryMsg::KeeperConfig {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        Some("addr9999".to_string()),
+        keeper_config.basset_vault_addr
+    );
+
+    update_keeper_config(
+        &mut sdk,
+        Some(Decimal256::percent(1)),
+        Some(Uint256::from(1_000u64)),
+        None,
+    )
+    .unwrap();
+    sdk.set_psi_balance(Uint128::new(1000));
+
+    //Sdk sends DistributeRewards from instantiator address
+    let response = sdk.distribute_rewards().unwrap();
+    assert_eq!(4, response.messages.len());
+}
+
+#[test]
+fn keeper_rewarded_once_per_interval() {
+    let mut sdk = Sdk::init();
+    update_keeper_config(
+        &mut sdk,
+        Some(Decimal256::percent(10)),
+        Some(Uint256::from(50u64)),
+        Some(100),
+    )
+    .unwrap();
+    sdk.set_psi_balance(Uint128::new(1000));
+
+    //10% of 1000 is more than cap
+    let response = sdk.distribute_rewards_by(KEEPER).unwrap();
+    assert_eq!(Some(&keeper_reward_msg(50)), response.messages.last());
+    assert!(response
+        .attributes
+        .contains(&cosmwasm_std::attr("nasset_holder_rewards", "855")));
+
+    //interval is not passed yet
+    let response = sdk.distribute_rewards_by(KEEPER).unwrap();
+    assert!(!response.messages.contains(&keeper_reward_msg(50)));
+}
+
+#[test]
+fn fail_to_set_keeper_reward_rate_greater_than_one() {
+    let mut sdk = Sdk::init();
+
+    let res = update_keeper_config(&mut sdk, Some(Decimal256::one()), None, None);
+    assert!(res.is_err());
+}
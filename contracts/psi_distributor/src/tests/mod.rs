@@ -2,6 +2,7 @@ mod change_config;
 mod change_governance_addr;
 mod distribute;
 mod instantiate;
+mod keeper_reward;
 mod sdk;
 
 use cosmwasm_bignumber::Decimal256;
@@ -60,4 +60,13 @@ impl Sdk {
         let info = mock_info("addr9999", &[]);
         crate::contract::execute(self.deps.as_mut(), mock_env(), info, distribute_msg)
     }
+
+    pub fn distribute_rewards_by(&mut self, sender: &str) -> ContractResult<Response> {
+        let distribute_msg = ExecuteMsg::Anyone {
+            anyone_msg: AnyoneMsg::DistributeRewards {},
+        };
+
+        let info = mock_info(sender, &[]);
+        crate::contract::execute(self.deps.as_mut(), mock_env(), info, distribute_msg)
+    }
 }
@@ -0,0 +1,67 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Deps, StdResult, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use super::market::{calc_redeemable_stables, query_market_state};
+use crate::querier::{
+    query_aterra_state, query_balance, query_token_balance, AnchorMarketCw20Msg, AnchorMarketMsg,
+};
+use crate::yield_venue::{YieldVenue, YieldVenueContracts};
+
+/// Anchor Earn, shares are aUST
+pub struct AnchorEarn {
+    contracts: YieldVenueContracts,
+}
+
+impl AnchorEarn {
+    pub fn new(contracts: YieldVenueContracts) -> Self {
+        Self { contracts }
+    }
+}
+
+impl YieldVenue for AnchorEarn {
+    fn deposit(&self, stable_denom: &str, amount: Uint256) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.contracts.market.to_string(),
+            msg: to_binary(&AnchorMarketMsg::DepositStable {})?,
+            funds: vec![Coin {
+                denom: stable_denom.to_string(),
+                amount: amount.into(),
+            }],
+        }))
+    }
+
+    fn redeem(&self, share_amount: Uint256) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.contracts.share_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.contracts.market.to_string(),
+                amount: share_amount.into(),
+                msg: to_binary(&AnchorMarketCw20Msg::RedeemStable {})?,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    fn share_balance(&self, deps: Deps, holder: &Addr) -> StdResult<Uint256> {
+        Ok(query_token_balance(deps, &self.contracts.share_token, holder).into())
+    }
+
+    fn exchange_rate(&self, deps: Deps) -> StdResult<Decimal256> {
+        Ok(query_aterra_state(deps, &self.contracts.market)?.exchange_rate)
+    }
+
+    fn available_liquidity(&self, deps: Deps, stable_denom: &str) -> StdResult<Uint256> {
+        let market_state = query_market_state(deps, &self.contracts.market)?;
+        let market_balance = query_balance(
+            &deps.querier,
+            &self.contracts.market,
+            stable_denom.to_string(),
+        )?;
+
+        Ok(calc_redeemable_stables(
+            &market_state,
+            market_balance.into(),
+        ))
+    }
+}
@@ -0,0 +1,153 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Deps, StdResult, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use super::basset_custody::get_basset_in_custody;
+use super::market::{
+    query_borrower_info, query_market_config, query_market_state, ConfigResponse, StateResponse,
+};
+use crate::lending_market::{LendingMarket, LendingMarketContracts, LoanInfo};
+use crate::querier::{
+    query_balance, AnchorCustodyCw20Msg, AnchorCustodyMsg, AnchorMarketMsg, AnchorOverseerMsg,
+};
+
+pub struct AnchorLendingMarket {
+    contracts: LendingMarketContracts,
+}
+
+impl AnchorLendingMarket {
+    pub fn new(contracts: LendingMarketContracts) -> Self {
+        Self { contracts }
+    }
+}
+
+impl LendingMarket for AnchorLendingMarket {
+    fn lock_collateral(
+        &self,
+        collateral_token: &Addr,
+        amount: Uint256,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: collateral_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.contracts.custody.to_string(),
+                    amount: amount.into(),
+                    msg: to_binary(&AnchorCustodyCw20Msg::DepositCollateral {})?,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.contracts.overseer.to_string(),
+                msg: to_binary(&AnchorOverseerMsg::LockCollateral {
+                    collaterals: vec![(collateral_token.to_string(), amount)],
+                })?,
+                funds: vec![],
+            }),
+        ])
+    }
+
+    fn unlock_collateral(
+        &self,
+        collateral_token: &Addr,
+        amount: Uint256,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.contracts.overseer.to_string(),
+                msg: to_binary(&AnchorOverseerMsg::UnlockCollateral {
+                    collaterals: vec![(collateral_token.to_string(), amount)],
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.contracts.custody.to_string(),
+                msg: to_binary(&AnchorCustodyMsg::WithdrawCollateral {
+                    amount: Some(amount),
+                })?,
+                funds: vec![],
+            }),
+        ])
+    }
+
+    fn borrow(&self, amount: Uint256) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.contracts.market.to_string(),
+            msg: to_binary(&AnchorMarketMsg::BorrowStable {
+                borrow_amount: amount,
+                to: None,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    fn repay(&self, stable_denom: &str, amount: Uint256) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.contracts.market.to_string(),
+            msg: to_binary(&AnchorMarketMsg::RepayStable {})?,
+            funds: vec![Coin {
+                denom: stable_denom.to_string(),
+                amount: amount.into(),
+            }],
+        }))
+    }
+
+    fn collateral_amount(&self, deps: Deps, borrower: &Addr) -> StdResult<Uint256> {
+        get_basset_in_custody(deps, &self.contracts.custody, borrower)
+    }
+
+    fn loan_info(&self, deps: Deps, borrower: &Addr) -> StdResult<LoanInfo> {
+        let borrower_info = query_borrower_info(deps, &self.contracts.market, borrower)?;
+        Ok(LoanInfo {
+            loan_amount: borrower_info.loan_amount,
+            pending_rewards: borrower_info.pending_rewards,
+        })
+    }
+
+    fn is_borrowing_possible(
+        &self,
+        deps: Deps,
+        stable_denom: &str,
+        amount: Uint256,
+    ) -> StdResult<bool> {
+        let market_state = query_market_state(deps, &self.contracts.market)?;
+        let market_balance = query_balance(
+            &deps.querier,
+            &self.contracts.market,
+            stable_denom.to_string(),
+        )?;
+        let market_config = query_market_config(deps, &self.contracts.market)?;
+        Ok(assert_max_borrow_factor(
+            market_config,
+            market_state,
+            market_balance.into(),
+            amount,
+        ))
+    }
+}
+
+//copypasted from anchor_market contract
+fn assert_max_borrow_factor(
+    market_config: ConfigResponse,
+    market_state: StateResponse,
+    market_balance: Uint256,
+    borrow_amount: Uint256,
+) -> bool {
+    let current_balance = Decimal256::from_uint256(market_balance);
+    let borrow_amount = Decimal256::from_uint256(borrow_amount);
+
+    // Assert max borrow factor
+    if market_state.total_liabilities + borrow_amount
+        > (current_balance + market_state.total_liabilities - market_state.total_reserves)
+            * market_config.max_borrow_factor
+    {
+        return false;
+    }
+
+    // Assert available balance
+    if borrow_amount + market_state.total_reserves > current_balance {
+        return false;
+    }
+
+    true
+}
@@ -1,10 +1,13 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, QueryRequest, StdResult, WasmQuery};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CanonicalAddr, Deps, QueryRequest, StdResult, WasmQuery,
+};
 use cosmwasm_storage::to_length_prefixed;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::querier::AnchorMarketQueryMsg;
+use crate::BLOCKS_PER_YEAR;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BorrowerInfoResponse {
@@ -56,6 +59,8 @@ pub fn query_borrower_info(
 pub struct StateResponse {
     pub total_liabilities: Decimal256,
     pub total_reserves: Decimal256,
+    //ANC per block for all borrowers
+    pub anc_emission_rate: Decimal256,
     // we do not need those fields, removing it will save some space in
     // compiled wasm file
     //
@@ -63,7 +68,47 @@ pub struct StateResponse {
     // pub last_reward_updated: u64,
     // pub global_interest_index: Decimal256,
     // pub global_reward_index: Decimal256,
-    // pub anc_emission_rate: Decimal256,
+}
+
+//copypasted from anchor_market contract ('assert_redeem_amount'):
+//redeem_amount + total_reserves should not be bigger than market balance
+pub fn calc_redeemable_stables(market_state: &StateResponse, market_balance: Uint256) -> Uint256 {
+    let current_balance = Decimal256::from_uint256(market_balance);
+    if current_balance <= market_state.total_reserves {
+        return Uint256::zero();
+    }
+
+    Uint256::one() * (current_balance - market_state.total_reserves)
+}
+
+/// Anchor APRs per stable
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorAprs {
+    pub deposit_apr: Decimal256,
+    pub borrow_apr: Decimal256,
+    pub distribution_apr: Decimal256,
+}
+
+/// `deposit_rate`, `borrow_rate` and `anc_emission_rate` are per block, as Anchor returns them
+pub fn calc_anchor_aprs(
+    deposit_rate: Decimal256,
+    borrow_rate: Decimal256,
+    anc_emission_rate: Decimal256,
+    total_liabilities: Decimal256,
+    anc_price: Decimal256,
+) -> AnchorAprs {
+    let blocks_per_year = Decimal256::from_uint256(Uint256::from(BLOCKS_PER_YEAR));
+    let distribution_apr = if total_liabilities.is_zero() {
+        Decimal256::zero()
+    } else {
+        anc_emission_rate * blocks_per_year * anc_price / total_liabilities
+    };
+
+    AnchorAprs {
+        deposit_apr: deposit_rate * blocks_per_year,
+        borrow_apr: borrow_rate * blocks_per_year,
+        distribution_apr,
+    }
 }
 
 pub fn query_market_state(deps: Deps, anchor_market_contract: &Addr) -> StdResult<StateResponse> {
@@ -84,13 +129,13 @@ pub struct ConfigResponse {
     // pub contract_addr: CanonicalAddr,
     // pub owner_addr: CanonicalAddr,
     // pub aterra_contract: CanonicalAddr,
-    // pub interest_model: CanonicalAddr,
     // pub distribution_model: CanonicalAddr,
     // pub overseer_contract: CanonicalAddr,
     // pub collector_contract: CanonicalAddr,
     // pub distributor_contract: CanonicalAddr,
     // pub stable_denom: String,
     // pub reserve_factor: Decimal256,
+    pub interest_model: CanonicalAddr,
     pub max_borrow_factor: Decimal256,
 }
 
@@ -104,3 +149,38 @@ pub fn query_market_config(deps: Deps, anchor_market_contract: &Addr) -> StdResu
 
     Ok(market_config)
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InterestModelQueryMsg {
+    BorrowRate {
+        market_balance: Uint256,
+        total_liabilities: Decimal256,
+        total_reserves: Decimal256,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BorrowRateResponse {
+    //per block
+    pub rate: Decimal256,
+}
+
+pub fn query_borrow_rate(
+    deps: Deps,
+    interest_model: &Addr,
+    market_balance: Uint256,
+    market_state: &StateResponse,
+) -> StdResult<Decimal256> {
+    let borrow_rate: BorrowRateResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: interest_model.to_string(),
+            msg: to_binary(&InterestModelQueryMsg::BorrowRate {
+                market_balance,
+                total_liabilities: market_state.total_liabilities,
+                total_reserves: market_state.total_reserves,
+            })?,
+        }))?;
+
+    Ok(borrow_rate.rate)
+}
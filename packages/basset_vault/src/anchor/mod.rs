@@ -1,2 +1,6 @@
 pub mod basset_custody;
+pub mod earn;
+pub mod lending_market;
 pub mod market;
+pub mod oracle;
+pub mod overseer;
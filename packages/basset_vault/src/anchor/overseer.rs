@@ -0,0 +1,33 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{to_binary, Addr, Deps, QueryRequest, StdResult, WasmQuery};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverseerQueryMsg {
+    EpochState {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochStateResponse {
+    //per block
+    pub deposit_rate: Decimal256,
+    // we do not need those fields, removing it will save some space in
+    // compiled wasm file
+    //
+    // pub prev_aterra_supply: Uint256,
+    // pub prev_exchange_rate: Decimal256,
+    // pub prev_interest_buffer: Uint256,
+    // pub last_executed_height: u64,
+}
+
+pub fn query_deposit_rate(deps: Deps, anchor_overseer_contract: &Addr) -> StdResult<Decimal256> {
+    let epoch_state: EpochStateResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: anchor_overseer_contract.to_string(),
+            msg: to_binary(&OverseerQueryMsg::EpochState {})?,
+        }))?;
+
+    Ok(epoch_state.deposit_rate)
+}
@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{to_binary, Addr, Deps, QueryRequest, StdResult, Uint128, WasmQuery};
+
+use crate::terraswap::AssetInfo;
+
+//copypasted from astroport oracle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Amount of other pool asset for `amount` of `token` by TWAP price
+    Consult { token: AssetInfo, amount: Uint128 },
+}
+
+pub fn query_consult(
+    deps: Deps,
+    oracle_contract: &Addr,
+    token: AssetInfo,
+    amount: Uint128,
+) -> StdResult<Uint256> {
+    let return_amount: Uint256 = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: oracle_contract.to_string(),
+        msg: to_binary(&QueryMsg::Consult { token, amount })?,
+    }))?;
+
+    Ok(return_amount)
+}
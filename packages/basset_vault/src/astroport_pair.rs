@@ -1,10 +1,11 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Decimal;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{to_binary, Addr, Decimal, Deps, QueryRequest, StdResult, Uint128, WasmQuery};
 use cw20::Cw20ReceiveMsg;
 
-use crate::terraswap::Asset;
+use crate::terraswap::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -37,3 +38,50 @@ pub enum Cw20HookMsg {
     },
     WithdrawLiquidity {},
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Simulation { offer_asset: Asset },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+pub fn query_simulation(
+    deps: Deps,
+    pair_contract: &Addr,
+    offer_asset: Asset,
+) -> StdResult<SimulationResponse> {
+    let simulation: SimulationResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pair_contract.to_string(),
+            msg: to_binary(&QueryMsg::Simulation { offer_asset })?,
+        }))?;
+
+    Ok(simulation)
+}
+
+/// Pool price of one `token` (6 decimals) in stables, spread and commission excluded
+pub fn query_spot_price(deps: Deps, pair_contract: &Addr, token: &Addr) -> StdResult<Decimal256> {
+    let offer_amount = Uint128::new(1_000_000);
+    let simulation = query_simulation(
+        deps,
+        pair_contract,
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: token.clone(),
+            },
+            amount: offer_amount,
+        },
+    )?;
+    let spot_return_amount =
+        simulation.return_amount + simulation.commission_amount + simulation.spread_amount;
+
+    Ok(Decimal256::from_uint256(Uint256::from(spot_return_amount))
+        / Decimal256::from_uint256(Uint256::from(offer_amount)))
+}
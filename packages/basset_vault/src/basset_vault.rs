@@ -2,8 +2,13 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Attribute, Binary, CosmosMsg};
 use cw20::Cw20ReceiveMsg;
 
+use crate::astroport_router::SwapOperation;
+use crate::lending_market::LendingMarketKind;
+use crate::yield_venue::YieldVenueKind;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     // message was too big and error returned on initialisation
@@ -65,6 +70,7 @@ pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
     Yourself { yourself_msg: YourselfMsg },
     Governance { governance_msg: GovernanceMsg },
+    Guardian { guardian_msg: GuardianMsg },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -72,6 +78,12 @@ pub enum ExecuteMsg {
 pub enum YourselfMsg {
     SwapAnc {},
     DisributeRewards {},
+    ProcessWithdrawal {},
+    RepayAfterDeleverage {},
+    //deposit stables above aim buffer to (just selected) yield venue
+    DepositToYieldVenue {},
+    //lock bAsset bought on rewards distribution as extra collateral
+    LockCompoundedBasset {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -84,6 +96,9 @@ pub enum AnyoneMsg {
     // This command utilise it.
     ClaimRemainder {},
     AcceptGovernance {},
+    //Sell part of collateral to repay loan, when LTV is above emergency trigger
+    //and loan can't be repaid from stables on balance and redeemable aUST
+    EmergencyDeleverage {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -99,19 +114,122 @@ pub enum GovernanceMsg {
         basset_vault_strategy_contract_addr: Option<String>,
         claiming_rewards_delay: Option<u64>,
         over_loan_balance_value: Option<Decimal256>,
+        //max price impact on ANC -> UST swap
+        anc_stable_max_spread: Option<Decimal256>,
+        //max price impact on UST -> PSI swap
+        psi_stable_max_spread: Option<Decimal256>,
+        //Astroport TWAP oracle of ANC/UST pair. ANC is not sold if swap
+        //returns less than TWAP price minus 'anc_stable_max_spread'
+        anc_stable_twap_oracle_addr: Option<String>,
+        //Astroport TWAP oracle of PSI/UST pair, same check for buying PSI
+        psi_stable_twap_oracle_addr: Option<String>,
     },
     UpdateGovernanceContract {
         gov_addr: String,
         //how long to wait for 'AcceptGovernance' transaction
         seconds_to_wait_for_accept_gov_tx: u64,
     },
+    UpdateKeeperConfig {
+        //share of ANC selling profit paid to HonestWork caller
+        honest_work_reward_rate: Option<Decimal256>,
+        //UST paid to Rebalance caller, if rebalance was needed.
+        //Paid from ANC selling profit set aside for it
+        rebalance_reward: Option<Uint256>,
+        //max UST paid to keeper at once
+        reward_cap: Option<Uint256>,
+        //min seconds between two rewarded calls of the same message
+        min_interval: Option<u64>,
+    },
+    UpdateGuardian {
+        //None removes guardian
+        guardian_addr: Option<String>,
+    },
+    //Disabled until all fields are set. Set 'max_basset_amount' to zero to disable it again
+    UpdateEmergencyDeleverageConfig {
+        //bAsset -> UST pair
+        basset_stable_swap_contract_addr: Option<String>,
+        //LTV (loan / collateral value) at which collateral can be sold,
+        //should be a bit lower than Anchor 'basset_max_ltv'
+        trigger_ltv: Option<Decimal256>,
+        //max bAsset sold in one EmergencyDeleverage
+        max_basset_amount: Option<Uint256>,
+        //max price impact on bAsset -> UST swap
+        max_spread: Option<Decimal256>,
+    },
+    //Sunset vault: disable deposits, repay whole loan and redeem all aUST
+    //(step by step, on Rebalance). After that vault is withdraw-only and
+    //remaining stables are withdrawn by nAsset holders pro-rata. Irreversible
+    Unwind {},
+    //Redeem whole position from current yield venue and deposit stables to new one.
+    //Fails if current venue has not enough liquidity to redeem everything
+    UpdateYieldVenue {
+        yield_venue: YieldVenueKind,
+    },
+    //Swap rewards through DEX router by stored routes instead of
+    //'anc_stable_swap_contract' and 'psi_stable_swap_contract' pairs.
+    //Set empty route to swap that token on pair again
+    UpdateSwapRouterConfig {
+        router_contract_addr: Option<String>,
+        //ANC -> ... -> stable
+        anc_route: Option<Vec<SwapOperation>>,
+        //stable -> ... -> Psi
+        psi_route: Option<Vec<SwapOperation>>,
+    },
+    //Part of rewards for buying Psi buys bAsset instead, which is locked as collateral
+    //without minting nAsset. Disabled until all fields are set.
+    //Set 'compound_share' to zero to disable it again
+    UpdateCompoundingConfig {
+        //bAsset -> UST pair
+        basset_stable_swap_contract_addr: Option<String>,
+        //share of Psi buying stables to spend on bAsset, from 0 to 1
+        compound_share: Option<Decimal256>,
+        //max price impact on UST -> bAsset swap
+        max_spread: Option<Decimal256>,
+    },
+}
+
+//governance can send it too
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardianMsg {
+    //true - pause, false - unpause, None - do not change.
+    //Repaying loan on Rebalance is never paused
+    SetPause {
+        deposit: Option<bool>,
+        withdraw: Option<bool>,
+        borrow: Option<bool>,
+        honest_work: Option<bool>,
+        claim_remainder: Option<bool>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Deposit {},
-    Withdraw {},
+    Deposit {
+        //fail if less nAsset would be minted
+        min_nasset_out: Option<Uint256>,
+        //unix timestamp (seconds), fail if block time is after it
+        deadline: Option<u64>,
+        //nAsset is minted to this address instead of sender
+        recipient: Option<String>,
+    },
+    Withdraw {
+        //fail if less bAsset would be returned
+        min_basset_out: Option<Uint256>,
+        //unix timestamp (seconds), fail if block time is after it
+        deadline: Option<u64>,
+        //bAsset is sent to this address instead of sender
+        recipient: Option<String>,
+        //if set, bAsset is sent to 'recipient' contract with cw20 'Send' and this hook msg
+        msg: Option<Binary>,
+    },
+    //lock nAsset in withdrawal queue, it is processed on Rebalance or HonestWork
+    //when there is enough liquidity to repay loan
+    RequestWithdraw {
+        //bAsset is sent to this address instead of sender
+        recipient: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -121,6 +239,53 @@ pub enum QueryMsg {
     Rebalance {},
     ChildContractsCodeId {},
     IsRewardsClaimable {},
+    //nAsset amount for given bAsset amount at current price
+    ConvertToShares {
+        basset_amount: Uint256,
+    },
+    //bAsset amount for given nAsset amount at current price
+    ConvertToAssets {
+        nasset_amount: Uint256,
+    },
+    //nAsset amount to be minted on Deposit of given bAsset amount
+    PreviewDeposit {
+        basset_amount: Uint256,
+    },
+    //bAsset amount to be returned on Withdraw of given nAsset amount
+    PreviewWithdraw {
+        nasset_amount: Uint256,
+    },
+    MaxDeposit {},
+    MaxWithdraw {
+        address: String,
+    },
+    TotalAssets {},
+    PositionHealth {},
+    KeeperConfig {},
+    PauseStatus {},
+    UnwindStatus {},
+    PendingWithdrawals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    //how loan would be repaid on Rebalance right now
+    RepaymentPlan {},
+    EmergencyDeleverageConfig {},
+    SwapRouterConfig {},
+    CompoundingConfig {},
+    //what Rebalance would do right now
+    SimulateRebalance {},
+    //what HonestWork would do right now
+    SimulateHonestWork {},
+    //cumulative vault accounting
+    Stats {},
+    //results of every HonestWork, oldest first
+    Epochs {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    //projected yearly yield for nAsset holders by current Anchor rates and pool prices
+    EstimatedApr {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -141,6 +306,15 @@ pub struct ConfigResponse {
     pub claiming_rewards_delay: u64,
     pub over_loan_balance_value: Decimal256,
     pub psi_distributor_addr: String,
+    pub anc_stable_max_spread: Decimal256,
+    pub psi_stable_max_spread: Decimal256,
+    //reference prices for reward swaps, None if only price impact is checked
+    pub anc_stable_twap_oracle_addr: Option<String>,
+    pub psi_stable_twap_oracle_addr: Option<String>,
+    //money market used to lock bAsset and borrow stables
+    pub lending_market: LendingMarketKind,
+    //venue where borrowed stables are deposited
+    pub yield_venue: YieldVenueKind,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -177,5 +351,251 @@ pub struct IsRewardsClaimableResponse {
     pub current_height: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConvertToSharesResponse {
+    pub nasset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConvertToAssetsResponse {
+    pub basset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreviewDepositResponse {
+    pub nasset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreviewWithdrawResponse {
+    pub basset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaxDepositResponse {
+    pub basset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaxWithdrawResponse {
+    pub nasset_amount: Uint256,
+    pub basset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalAssetsResponse {
+    pub basset_in_custody: Uint256,
+    pub nasset_supply: Uint256,
+    //bAsset transferred directly to vault, not a part of share price
+    pub unsolicited_basset: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionHealthResponse {
+    pub basset_in_custody: Uint256,
+    pub loan_amount: Uint256,
+    pub basset_price: Decimal256,
+    pub price_last_updated_base: u64,
+    pub price_last_updated_quote: u64,
+    //basset_in_custody * basset_price * basset_max_ltv
+    pub max_borrow_amount: Uint256,
+    //loan_amount / max_borrow_amount, None if nothing can be borrowed
+    pub current_ltv: Option<Decimal256>,
+    pub borrow_ltv_min: Decimal256,
+    pub borrow_ltv_aim: Decimal256,
+    pub borrow_ltv_max: Decimal256,
+    //bAsset price at which loan reaches Anchor borrow limit, None if there is no loan
+    pub liquidation_price: Option<Decimal256>,
+    pub aterra_balance: Uint256,
+    pub aterra_exchange_rate: Decimal256,
+    pub aterra_value: Uint256,
+    pub stable_balance: Uint256,
+    pub aim_buffer_size: Uint256,
+    //(aterra_value + stable_balance) / loan_amount, None if there is no loan
+    pub coverage_ratio: Option<Decimal256>,
+    pub over_loan_balance_value: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperConfigResponse {
+    pub honest_work_reward_rate: Decimal256,
+    pub rebalance_reward: Uint256,
+    pub reward_cap: Uint256,
+    pub min_interval: u64,
+    pub last_honest_work_reward_time: u64,
+    pub last_rebalance_reward_time: u64,
+    //ANC selling profit set aside to pay Rebalance rewards
+    pub rebalance_reward_reserve: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+    pub guardian_addr: Option<String>,
+    pub deposit: bool,
+    pub withdraw: bool,
+    pub borrow: bool,
+    pub honest_work: bool,
+    pub claim_remainder: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnwindStatusResponse {
+    pub unwinding: bool,
+    //loan is repaid and aUST is redeemed, withdrawals are available
+    pub completed: bool,
+    pub loan_amount: Uint256,
+    pub aterra_balance: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingWithdrawalsResponse {
+    //nAsset locked in all pending requests
+    pub total_nasset_amount: Uint256,
+    pub pending_count: u64,
+    //first request to be processed goes first
+    pub requests: Vec<PendingWithdrawalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingWithdrawalResponse {
+    pub id: u64,
+    pub recipient: String,
+    pub nasset_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RepaymentPlanResponse {
+    //zero if strategy do not ask to repay loan
+    pub to_repay_amount: Uint256,
+    pub aim_buffer_size: Uint256,
+    pub aterra_to_redeem: Uint256,
+    //stables we receive from Anchor for redeemed aterra (after tax)
+    pub expected_stables: Uint256,
+    pub repay_amount: Uint256,
+    //false if Anchor liquidity is not enough and loan
+    //will be repaid step by step
+    pub is_complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmergencyDeleverageConfigResponse {
+    pub enabled: bool,
+    pub basset_stable_swap_contract_addr: Option<String>,
+    pub trigger_ltv: Decimal256,
+    pub max_basset_amount: Uint256,
+    pub max_spread: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapRouterConfigResponse {
+    pub router_contract_addr: Option<String>,
+    //empty if ANC is swapped on pair
+    pub anc_route: Vec<SwapOperation>,
+    //empty if Psi is bought on pair
+    pub psi_route: Vec<SwapOperation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompoundingConfigResponse {
+    pub enabled: bool,
+    pub basset_stable_swap_contract_addr: Option<String>,
+    pub compound_share: Decimal256,
+    pub max_spread: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateRebalanceResponse {
+    //strategy action
+    pub rebalance: RebalanceResponse,
+    pub unwinding: bool,
+    //why nothing is done, e.g. "borrow_paused"
+    pub skipped: Option<String>,
+    //UST paid to Rebalance caller (tax included)
+    pub keeper_reward: Uint256,
+    //messages sent by Rebalance, and by reply handler on successful borrowing
+    pub messages: Vec<CosmosMsg>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateHonestWorkResponse {
+    pub claimable: bool,
+    //pending rewards and ANC on balance
+    pub anc_amount: Uint256,
+    //UST received for ANC by pair simulation, zero if price impact is too high
+    pub expected_stables: Uint256,
+    //UST paid to HonestWork caller (tax included)
+    pub keeper_reward: Uint256,
+    //messages sent on DistributeRewards, after ANC is swapped
+    pub messages: Vec<CosmosMsg>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub anc_claimed: Uint256,
+    pub anc_sold: Uint256,
+    //UST received for sold ANC
+    pub stables_realised: Uint256,
+    pub keeper_rewards: Uint256,
+    pub deposited_to_anc: Uint256,
+    pub stables_to_psi: Uint256,
+    //expected by swap simulation at the moment of buying
+    pub psi_bought: Uint256,
+    //loan growth not caused by vault borrows, counted when vault queries loan
+    pub interest_paid: Uint256,
+    //UST spent on bAsset in compounding mode
+    pub stables_to_compound: Uint256,
+    //bAsset locked as extra collateral in compounding mode
+    pub basset_compounded: Uint256,
+    pub rebalances: u64,
+    pub epochs: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochsResponse {
+    pub epochs: Vec<EpochResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochResponse {
+    pub id: u64,
+    //block time in seconds
+    pub time: u64,
+    pub height: u64,
+    pub anc_sold: Uint256,
+    pub stables_realised: Uint256,
+    pub keeper_reward: Uint256,
+    pub deposited_to_anc: Uint256,
+    pub stables_to_psi: Uint256,
+    pub psi_bought: Uint256,
+    //accrued since previous epoch
+    pub interest_paid: Uint256,
+    pub loan_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EstimatedAprResponse {
+    //loan / collateral value, aim LTV is used if there is no collateral
+    pub ltv: Decimal256,
+    //Anchor Earn rate
+    pub deposit_apr: Decimal256,
+    pub borrow_apr: Decimal256,
+    //value of ANC emitted per borrowed UST
+    pub distribution_apr: Decimal256,
+    pub anc_price: Decimal256,
+    pub psi_price: Decimal256,
+    //yearly income and cost per collateral value (rate * ltv)
+    pub deposit_yield: Decimal256,
+    pub distribution_yield: Decimal256,
+    pub borrow_cost: Decimal256,
+    //part of bought Psi that goes to nAsset holders
+    pub nasset_holder_share: Decimal256,
+    //(deposit_yield + distribution_yield - borrow_cost) * nasset_holder_share
+    pub apr: Decimal256,
+    //Psi received per year for one UST of collateral value
+    pub psi_per_collateral_value: Decimal256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
 
+use crate::anchor::oracle::PriceResponse;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub governance_contract_addr: String,
@@ -29,11 +31,14 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum AnyoneMsg {
     AcceptGovernance {},
+    //store current bAsset price to estimate its volatility
+    RecordPrice {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GovernanceMsg {
+    //changing LTVs here stops 'RampLtv'
     UpdateConfig {
         oracle_addr: Option<String>,
         basset_token_addr: Option<String>,
@@ -44,12 +49,93 @@ pub enum GovernanceMsg {
         basset_max_ltv: Option<Decimal256>,
         buffer_part: Option<Decimal256>,
         price_timeframe: Option<u64>,
+        stale_price_policy: Option<StalePricePolicy>,
     },
     UpdateGovernanceContract {
         gov_addr: String,
         //how long to wait for 'AcceptGovernance' transaction
         seconds_to_wait_for_accept_gov_tx: u64,
     },
+    UpdatePriceSources {
+        //feeds used together with 'oracle_contract', empty list leaves only it
+        price_sources: Option<Vec<PriceSource>>,
+        //max (highest - lowest) / median price, Borrow is refused above it
+        max_deviation: Option<Decimal256>,
+    },
+    //Compare Anchor borrow APR with Earn deposit APR and ANC emissions before borrowing.
+    //Disabled until all fields are set.
+    //Set 'negative_carry_ltv_factor' to zero to disable it again
+    UpdateCarryConfig {
+        anchor_market_addr: Option<String>,
+        anchor_overseer_addr: Option<String>,
+        //ANC -> UST pair to price ANC emissions
+        anc_stable_swap_addr: Option<String>,
+        anchor_token_addr: Option<String>,
+        //min (deposit APR + distribution APR - borrow APR) to borrow more
+        min_spread: Option<Decimal256>,
+        //LTV bands are multiplied by it while carry is negative, from 0 to 1
+        negative_carry_ltv_factor: Option<Decimal256>,
+    },
+    //Tighten LTV bands and enlarge buffer when recorded bAsset price is volatile.
+    //Disabled until all fields are set.
+    //Set 'max_observations' to zero to disable it again
+    UpdateVolatilityConfig {
+        //how many recorded prices are kept, oldest is dropped
+        max_observations: Option<u32>,
+        //min seconds between 'RecordPrice' calls
+        min_interval: Option<u64>,
+        //price change per hour, bands are not changed below it
+        calm_volatility: Option<Decimal256>,
+        //price change per hour, bands are changed at most above it
+        stress_volatility: Option<Decimal256>,
+        //max value subtracted from 'borrow_ltv_aim' and 'borrow_ltv_max'
+        max_ltv_reduction: Option<Decimal256>,
+        //max value added to 'buffer_part'
+        max_buffer_part_increase: Option<Decimal256>,
+    },
+    //Move LTV bands linearly from current values to targets till 'end_time'.
+    //Starts from current point of unfinished ramp
+    RampLtv {
+        target_aim: Decimal256,
+        target_min: Decimal256,
+        target_max: Decimal256,
+        //seconds
+        end_time: u64,
+    },
+    //LTV bands stay at current point of the ramp
+    StopRamp {},
+}
+
+/// bAsset price feed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    //Anchor oracle or any feed with the same 'Price { base, quote }' query
+    Oracle { contract_addr: String },
+    //Astroport oracle with TWAP of bAsset/stable pool
+    AstroportTwap { oracle_contract_addr: String },
+}
+
+/// What strategy does when bAsset price is older than `price_timeframe`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StalePricePolicy {
+    //multiply all LTV bands by factor
+    ScaleLtv { factor: Decimal256 },
+    //return Nothing until price is fresh
+    Freeze {},
+    //return Repay if needed, but never Borrow
+    RepayOnly {},
+    //use price from this source, RepayOnly if it is stale or unavailable too
+    Fallback { source: PriceSource },
+}
+
+impl Default for StalePricePolicy {
+    fn default() -> Self {
+        StalePricePolicy::ScaleLtv {
+            factor: Decimal256::percent(50),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,6 +146,17 @@ pub enum QueryMsg {
         borrowed_amount: Uint256,
         locked_basset_amount: Uint256,
     },
+    //every price used by BorrowerAction and their median
+    PriceSources {},
+    //median of available sources, fails if it is stale
+    FreshPrice {},
+    CarryConfig {},
+    //current borrowing profitability
+    Carry {},
+    VolatilityConfig {},
+    //LTV bands and buffer after volatility adjustment
+    EffectiveBands {},
+    LtvRamp {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -74,18 +171,108 @@ pub struct ConfigResponse {
     pub basset_max_ltv: Decimal256,
     pub buffer_part: Decimal256,
     pub price_timeframe: u64,
+    pub stale_price_policy: StalePricePolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourcePriceResponse {
+    pub source: PriceSource,
+    //None if source can't be queried
+    pub price: Option<PriceResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceSourcesResponse {
+    //'oracle_contract' is the first one
+    pub sources: Vec<SourcePriceResponse>,
+    //None if no source can be queried
+    pub median_price: Option<Decimal256>,
+    //(highest - lowest) / median price
+    pub deviation: Decimal256,
+    pub max_deviation: Decimal256,
+    pub borrow_allowed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FreshPriceResponse {
+    pub rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarryConfigResponse {
+    pub anchor_market_contract: Option<String>,
+    pub anchor_overseer_contract: Option<String>,
+    pub anc_stable_swap_contract: Option<String>,
+    pub anchor_token: Option<String>,
+    pub min_spread: Decimal256,
+    pub negative_carry_ltv_factor: Decimal256,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarryResponse {
+    pub deposit_apr: Decimal256,
+    //ANC emissions value per borrowed stable
+    pub distribution_apr: Decimal256,
+    pub borrow_apr: Decimal256,
+    //|deposit_apr + distribution_apr - borrow_apr|
+    pub spread: Decimal256,
+    pub is_negative: bool,
+    pub borrow_allowed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VolatilityConfigResponse {
+    pub max_observations: u32,
+    pub min_interval: u64,
+    pub calm_volatility: Decimal256,
+    pub stress_volatility: Decimal256,
+    pub max_ltv_reduction: Decimal256,
+    pub max_buffer_part_increase: Decimal256,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EffectiveBandsResponse {
+    pub borrow_ltv_max: Decimal256,
+    pub borrow_ltv_min: Decimal256,
+    pub borrow_ltv_aim: Decimal256,
+    pub buffer_part: Decimal256,
+    //mean price change between recorded observations
+    pub volatility: Decimal256,
+    pub observations: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LtvRampResponse {
+    //current point of the ramp
+    pub borrow_ltv_max: Decimal256,
+    pub borrow_ltv_min: Decimal256,
+    pub borrow_ltv_aim: Decimal256,
+    pub target_borrow_ltv_max: Decimal256,
+    pub target_borrow_ltv_min: Decimal256,
+    pub target_borrow_ltv_aim: Decimal256,
+    //None if there is no ramp
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub is_active: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum BorrowerActionResponse {
-    Nothing {},
+    //'price_is_stale' means decision is made by stale price policy
+    Nothing {
+        price_is_stale: bool,
+    },
     Borrow {
         amount: Uint256,
         advised_buffer_size: Uint256,
+        price_is_stale: bool,
     },
     Repay {
         amount: Uint256,
         advised_buffer_size: Uint256,
+        price_is_stale: bool,
     },
 }
 
@@ -94,6 +281,7 @@ impl BorrowerActionResponse {
         BorrowerActionResponse::Repay {
             amount,
             advised_buffer_size,
+            price_is_stale: false,
         }
     }
 
@@ -101,11 +289,31 @@ impl BorrowerActionResponse {
         BorrowerActionResponse::Borrow {
             amount,
             advised_buffer_size,
+            price_is_stale: false,
         }
     }
 
     pub fn nothing() -> Self {
-        BorrowerActionResponse::Nothing {}
+        BorrowerActionResponse::Nothing {
+            price_is_stale: false,
+        }
+    }
+
+    pub fn price_is_stale(&self) -> bool {
+        match self {
+            BorrowerActionResponse::Nothing { price_is_stale }
+            | BorrowerActionResponse::Borrow { price_is_stale, .. }
+            | BorrowerActionResponse::Repay { price_is_stale, .. } => *price_is_stale,
+        }
+    }
+
+    pub fn with_stale_price(mut self) -> Self {
+        match &mut self {
+            BorrowerActionResponse::Nothing { price_is_stale }
+            | BorrowerActionResponse::Borrow { price_is_stale, .. }
+            | BorrowerActionResponse::Repay { price_is_stale, .. } => *price_is_stale = true,
+        }
+        self
     }
 }
 
@@ -127,5 +335,29 @@ pub fn query_borrower_action(
     Ok(borrower_action)
 }
 
+pub fn query_strategy_config(
+    deps: Deps,
+    basset_vault_strategy_contract: &Addr,
+) -> StdResult<ConfigResponse> {
+    let config: ConfigResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: basset_vault_strategy_contract.to_string(),
+        msg: to_binary(&QueryMsg::Config {})?,
+    }))?;
+
+    Ok(config)
+}
+
+pub fn query_strategy_fresh_price(
+    deps: Deps,
+    basset_vault_strategy_contract: &Addr,
+) -> StdResult<Decimal256> {
+    let price: FreshPriceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: basset_vault_strategy_contract.to_string(),
+        msg: to_binary(&QueryMsg::FreshPrice {})?,
+    }))?;
+
+    Ok(price.rate)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
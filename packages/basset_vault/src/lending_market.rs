@@ -0,0 +1,78 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Addr, CosmosMsg, Deps, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::anchor::lending_market::AnchorLendingMarket;
+
+/// Money market that `basset_vault` uses to lock bAsset and borrow stables
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LendingMarketKind {
+    Anchor {},
+}
+
+impl Default for LendingMarketKind {
+    fn default() -> Self {
+        LendingMarketKind::Anchor {}
+    }
+}
+
+/// Contracts of selected money market
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LendingMarketContracts {
+    pub market: Addr,
+    pub overseer: Addr,
+    pub custody: Addr,
+}
+
+impl LendingMarketKind {
+    pub fn adapter(&self, contracts: LendingMarketContracts) -> Box<dyn LendingMarket> {
+        match self {
+            LendingMarketKind::Anchor {} => Box::new(AnchorLendingMarket::new(contracts)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoanInfo {
+    pub loan_amount: Uint256,
+    //market rewards that can be claimed by borrower
+    pub pending_rewards: Decimal256,
+}
+
+/// Everything vault needs from money market to manage its position
+pub trait LendingMarket {
+    /// Messages to deposit `amount` of `collateral_token` from vault balance and lock it
+    fn lock_collateral(
+        &self,
+        collateral_token: &Addr,
+        amount: Uint256,
+    ) -> StdResult<Vec<CosmosMsg>>;
+
+    /// Messages to unlock `amount` of `collateral_token` and withdraw it to vault balance
+    fn unlock_collateral(
+        &self,
+        collateral_token: &Addr,
+        amount: Uint256,
+    ) -> StdResult<Vec<CosmosMsg>>;
+
+    /// Borrowed stables are sent to borrower
+    fn borrow(&self, amount: Uint256) -> StdResult<CosmosMsg>;
+
+    fn repay(&self, stable_denom: &str, amount: Uint256) -> StdResult<CosmosMsg>;
+
+    /// Locked collateral amount, zero if there is no position
+    fn collateral_amount(&self, deps: Deps, borrower: &Addr) -> StdResult<Uint256>;
+
+    /// Current loan, zero if there is no position
+    fn loan_info(&self, deps: Deps, borrower: &Addr) -> StdResult<LoanInfo>;
+
+    /// Checks that market has enough liquidity to lend `amount` right now
+    fn is_borrowing_possible(
+        &self,
+        deps: Deps,
+        stable_denom: &str,
+        amount: Uint256,
+    ) -> StdResult<bool>;
+}
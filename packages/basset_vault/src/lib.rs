@@ -1,18 +1,24 @@
 pub mod anchor;
 pub mod astroport_factory;
+pub mod astroport_oracle;
 pub mod astroport_pair;
+pub mod astroport_router;
 pub mod basset_vault;
 pub mod basset_vault_strategy;
 pub mod common;
+pub mod lending_market;
 pub mod nasset_token;
 pub mod nasset_token_config_holder;
 pub mod nasset_token_rewards;
 pub mod psi_distributor;
 pub mod querier;
 pub mod terraswap;
+pub mod yield_venue;
 
 // hom many iterations is available for loan repayment
 pub const BASSET_VAULT_LOAN_REPAYMENT_MAX_RECURSION_DEEP: u8 = 10;
+//same as Anchor uses to calculate its APYs
+pub const BLOCKS_PER_YEAR: u64 = 4_656_810;
 
 #[inline]
 fn concat(namespace: &[u8], key: &[u8]) -> Vec<u8> {
@@ -12,6 +12,7 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     Anyone { anyone_msg: AnyoneMsg },
     Governance { governance_msg: GovernanceMsg },
+    Guardian { guardian_msg: GuardianMsg },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,6 +36,18 @@ pub enum GovernanceMsg {
         //how long to wait for 'AcceptGovernance' transaction
         seconds_to_wait_for_accept_gov_tx: u64,
     },
+    UpdateGuardian {
+        //None removes guardian
+        guardian_addr: Option<String>,
+    },
+}
+
+//governance can send it too
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardianMsg {
+    //pause nAsset Transfer, TransferFrom, Send and SendFrom
+    SetPause { transfers: bool },
 }
 
 //nasset_token use this
@@ -44,10 +57,17 @@ pub struct Config {
     pub governance_contract: Addr,
 }
 
+//nasset_token use this
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseState {
+    pub transfers: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    PauseStatus {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -56,5 +76,11 @@ pub struct ConfigResponse {
     pub governance_contract_addr: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+    pub guardian_addr: Option<String>,
+    pub transfers: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -17,6 +17,7 @@ pub enum ExecuteMsg {
     Anyone { anyone_msg: AnyoneMsg },
     Token { token_msg: TokenMsg },
     Governance { governance_msg: GovernanceMsg },
+    Guardian { guardian_msg: GuardianMsg },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -41,6 +42,18 @@ pub enum GovernanceMsg {
         //how long to wait for 'AcceptGovernance' transaction
         seconds_to_wait_for_accept_gov_tx: u64,
     },
+    UpdateGuardian {
+        //None removes guardian
+        guardian_addr: Option<String>,
+    },
+}
+
+//governance can send it too
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardianMsg {
+    //pause ClaimRewards and ClaimRewardsForSomeone
+    SetPause { claim: bool },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -72,6 +85,7 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    PauseStatus {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -106,5 +120,11 @@ pub struct HoldersResponse {
     pub holders: Vec<HolderResponse>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+    pub guardian_addr: Option<String>,
+    pub claim: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
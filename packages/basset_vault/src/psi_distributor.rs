@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_bignumber::Decimal256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{to_binary, Addr, Deps, QueryRequest, StdResult, WasmQuery};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -47,12 +48,23 @@ pub enum GovernanceMsg {
         //how long to wait for 'AcceptGovernance' transaction
         seconds_to_wait_for_accept_gov_tx: u64,
     },
+    UpdateKeeperConfig {
+        //share of distributed Psi paid to DistributeRewards caller
+        reward_rate: Option<Decimal256>,
+        //max Psi paid to keeper at once
+        reward_cap: Option<Uint256>,
+        //min seconds between two rewarded calls
+        min_interval: Option<u64>,
+        //calls from basset_vault are not rewarded
+        basset_vault_addr: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    KeeperConfig {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -68,7 +80,71 @@ pub struct ConfigResponse {
     pub tax_rate: Decimal256,
 }
 
+pub fn query_psi_distributor_config(
+    deps: Deps,
+    psi_distributor_contract: &Addr,
+) -> StdResult<ConfigResponse> {
+    let config: ConfigResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: psi_distributor_contract.to_string(),
+        msg: to_binary(&QueryMsg::Config {})?,
+    }))?;
+
+    Ok(config)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperConfigResponse {
+    pub reward_rate: Decimal256,
+    pub reward_cap: Uint256,
+    pub min_interval: u64,
+    pub basset_vault_addr: Option<String>,
+    pub last_reward_time: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub nasset_psi_swap_contract_addr: String,
 }
+
+/// Split of distributed Psi between nAsset holders and protocol.
+/// Shared with basset_vault to estimate nAsset holders yield
+pub struct RewardsDistribution {
+    pub nasset_holder: Uint256,
+    pub governance: Uint256,
+    pub community_pool: Uint256,
+}
+
+impl RewardsDistribution {
+    pub fn calc(
+        psi_amount: Uint256,
+        aim_ltv: Decimal256,
+        manual_ltv: Decimal256,
+        fee_rate: Decimal256,
+        tax_rate: Decimal256,
+    ) -> Self {
+        let protocol_rewards = psi_amount * Self::protocol_fee(aim_ltv, manual_ltv, fee_rate);
+
+        let community_pool_rewards = protocol_rewards * tax_rate;
+        let governance_rewards = protocol_rewards - community_pool_rewards;
+        let nassest_holder_rewards = psi_amount - protocol_rewards;
+
+        Self {
+            nasset_holder: nassest_holder_rewards,
+            governance: governance_rewards,
+            community_pool: community_pool_rewards,
+        }
+    }
+
+    /// Share of Psi that goes to governance and community pool
+    pub fn protocol_fee(
+        aim_ltv: Decimal256,
+        manual_ltv: Decimal256,
+        fee_rate: Decimal256,
+    ) -> Decimal256 {
+        if manual_ltv >= aim_ltv {
+            return Decimal256::zero();
+        }
+
+        (aim_ltv - manual_ltv) * fee_rate
+    }
+}
@@ -0,0 +1,70 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Addr, CosmosMsg, Deps, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::anchor::earn::AnchorEarn;
+
+/// Where `basset_vault` deposits borrowed stables to earn yield
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldVenueKind {
+    AnchorEarn {},
+}
+
+impl Default for YieldVenueKind {
+    fn default() -> Self {
+        YieldVenueKind::AnchorEarn {}
+    }
+}
+
+/// Contracts of selected yield venue
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldVenueContracts {
+    pub market: Addr,
+    //token received for deposited stables
+    pub share_token: Addr,
+}
+
+impl YieldVenueKind {
+    pub fn adapter(&self, contracts: YieldVenueContracts) -> Box<dyn YieldVenue> {
+        match self {
+            YieldVenueKind::AnchorEarn {} => Box::new(AnchorEarn::new(contracts)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldPosition {
+    pub share_amount: Uint256,
+    //stables per one share
+    pub exchange_rate: Decimal256,
+}
+
+impl YieldPosition {
+    pub fn value(&self) -> Uint256 {
+        self.share_amount * self.exchange_rate
+    }
+}
+
+/// Everything vault needs from yield venue to keep buffer and handle profit
+pub trait YieldVenue {
+    fn deposit(&self, stable_denom: &str, amount: Uint256) -> StdResult<CosmosMsg>;
+
+    /// Stables for redeemed shares are sent to holder
+    fn redeem(&self, share_amount: Uint256) -> StdResult<CosmosMsg>;
+
+    fn share_balance(&self, deps: Deps, holder: &Addr) -> StdResult<Uint256>;
+
+    fn exchange_rate(&self, deps: Deps) -> StdResult<Decimal256>;
+
+    /// Stables venue can pay out right now
+    fn available_liquidity(&self, deps: Deps, stable_denom: &str) -> StdResult<Uint256>;
+
+    fn position(&self, deps: Deps, holder: &Addr) -> StdResult<YieldPosition> {
+        Ok(YieldPosition {
+            share_amount: self.share_balance(deps, holder)?,
+            exchange_rate: self.exchange_rate(deps)?,
+        })
+    }
+}